@@ -0,0 +1,434 @@
+//! Lookup tables between the format enums of the graphics APIs and the formats of this crate.
+//!
+//! The tables are searched in both directions, the first matching entry is used by the reverse lookups.
+//! Formats without a decoder in this crate, e.g. the signed BC4 and BC5 variants, aren't listed.
+
+use crate::format::NumericFormat::{Sfloat, Snorm, Srgb, Ufloat, Uint, Unorm};
+use crate::format::{FormatDescriptor, NumericFormat, TextureFormat};
+use crate::raw::{Endian, RawFormat};
+
+const fn d(format: TextureFormat, numeric_format: NumericFormat) -> FormatDescriptor {
+    FormatDescriptor::new(format, numeric_format)
+}
+
+const fn raw(format: RawFormat, numeric_format: NumericFormat) -> FormatDescriptor {
+    FormatDescriptor::new(TextureFormat::Raw(format, Endian::Little), numeric_format)
+}
+
+/// `DXGI_FORMAT` values, the typeless formats are mapped to UNORM.
+pub static DXGI_FORMATS: [(u32, FormatDescriptor); 48] = [
+    (2, raw(RawFormat::RgbaFloat, Sfloat)),
+    (10, raw(RawFormat::RgbaHalf, Sfloat)),
+    (16, raw(RawFormat::RgFloat, Sfloat)),
+    (24, raw(RawFormat::Rgb10A2, Unorm)),
+    (28, raw(RawFormat::Rgba32, Unorm)),
+    (29, raw(RawFormat::Rgba32, Srgb)),
+    (30, raw(RawFormat::Rgba32, Uint)),
+    (34, raw(RawFormat::RgHalf, Sfloat)),
+    (41, raw(RawFormat::RFloat, Sfloat)),
+    (49, raw(RawFormat::Rg16, Unorm)),
+    (50, raw(RawFormat::Rg16, Uint)),
+    (54, raw(RawFormat::RHalf, Sfloat)),
+    (56, raw(RawFormat::R16, Unorm)),
+    (57, raw(RawFormat::R16, Uint)),
+    (61, raw(RawFormat::R8, Unorm)),
+    (62, raw(RawFormat::R8, Uint)),
+    (65, raw(RawFormat::Alpha8, Unorm)),
+    (71, d(TextureFormat::Bc1a, Unorm)),
+    (72, d(TextureFormat::Bc1a, Srgb)),
+    (74, d(TextureFormat::Bc2, Unorm)),
+    (75, d(TextureFormat::Bc2, Srgb)),
+    (77, d(TextureFormat::Bc3, Unorm)),
+    (78, d(TextureFormat::Bc3, Srgb)),
+    (80, d(TextureFormat::Bc4, Unorm)),
+    (83, d(TextureFormat::Bc5, Unorm)),
+    (85, raw(RawFormat::Rgb565, Unorm)),
+    (86, raw(RawFormat::Argb1555, Unorm)),
+    (87, raw(RawFormat::Bgra32, Unorm)),
+    (88, raw(RawFormat::Bgrx32, Unorm)),
+    (91, raw(RawFormat::Bgra32, Srgb)),
+    (93, raw(RawFormat::Bgrx32, Srgb)),
+    (95, d(TextureFormat::Bc6Unsigned, Ufloat)),
+    (96, d(TextureFormat::Bc6Signed, Sfloat)),
+    (98, d(TextureFormat::Bc7, Unorm)),
+    (99, d(TextureFormat::Bc7, Srgb)),
+    (115, raw(RawFormat::Argb4444, Unorm)),
+    // the typeless formats
+    (27, raw(RawFormat::Rgba32, Unorm)),
+    (48, raw(RawFormat::Rg16, Unorm)),
+    (60, raw(RawFormat::R8, Unorm)),
+    (70, d(TextureFormat::Bc1a, Unorm)),
+    (73, d(TextureFormat::Bc2, Unorm)),
+    (76, d(TextureFormat::Bc3, Unorm)),
+    (79, d(TextureFormat::Bc4, Unorm)),
+    (82, d(TextureFormat::Bc5, Unorm)),
+    (90, raw(RawFormat::Bgra32, Unorm)),
+    (92, raw(RawFormat::Bgrx32, Unorm)),
+    (94, d(TextureFormat::Bc6Unsigned, Ufloat)),
+    (97, d(TextureFormat::Bc7, Unorm)),
+];
+
+/// `VkFormat` values.
+pub static VK_FORMATS: [(u32, FormatDescriptor); 101] = [
+    (2, raw(RawFormat::Rgba4444, Unorm)),
+    (4, raw(RawFormat::Rgb565, Unorm)),
+    (6, raw(RawFormat::Rgba5551, Unorm)),
+    (8, raw(RawFormat::Argb1555, Unorm)),
+    (9, raw(RawFormat::R8, Unorm)),
+    (13, raw(RawFormat::R8, Uint)),
+    (15, raw(RawFormat::R8, Srgb)),
+    (16, raw(RawFormat::Rg16, Unorm)),
+    (20, raw(RawFormat::Rg16, Uint)),
+    (22, raw(RawFormat::Rg16, Srgb)),
+    (23, raw(RawFormat::Rgb24, Unorm)),
+    (27, raw(RawFormat::Rgb24, Uint)),
+    (29, raw(RawFormat::Rgb24, Srgb)),
+    (30, raw(RawFormat::Bgr24, Unorm)),
+    (34, raw(RawFormat::Bgr24, Uint)),
+    (36, raw(RawFormat::Bgr24, Srgb)),
+    (37, raw(RawFormat::Rgba32, Unorm)),
+    (41, raw(RawFormat::Rgba32, Uint)),
+    (43, raw(RawFormat::Rgba32, Srgb)),
+    (44, raw(RawFormat::Bgra32, Unorm)),
+    (48, raw(RawFormat::Bgra32, Uint)),
+    (50, raw(RawFormat::Bgra32, Srgb)),
+    (64, raw(RawFormat::Rgb10A2, Unorm)),
+    (70, raw(RawFormat::R16, Unorm)),
+    (74, raw(RawFormat::R16, Uint)),
+    (76, raw(RawFormat::RHalf, Sfloat)),
+    (83, raw(RawFormat::RgHalf, Sfloat)),
+    (97, raw(RawFormat::RgbaHalf, Sfloat)),
+    (100, raw(RawFormat::RFloat, Sfloat)),
+    (103, raw(RawFormat::RgFloat, Sfloat)),
+    (109, raw(RawFormat::RgbaFloat, Sfloat)),
+    (131, d(TextureFormat::Bc1, Unorm)),
+    (132, d(TextureFormat::Bc1, Srgb)),
+    (133, d(TextureFormat::Bc1a, Unorm)),
+    (134, d(TextureFormat::Bc1a, Srgb)),
+    (135, d(TextureFormat::Bc2, Unorm)),
+    (136, d(TextureFormat::Bc2, Srgb)),
+    (137, d(TextureFormat::Bc3, Unorm)),
+    (138, d(TextureFormat::Bc3, Srgb)),
+    (139, d(TextureFormat::Bc4, Unorm)),
+    (141, d(TextureFormat::Bc5, Unorm)),
+    (143, d(TextureFormat::Bc6Unsigned, Ufloat)),
+    (144, d(TextureFormat::Bc6Signed, Sfloat)),
+    (145, d(TextureFormat::Bc7, Unorm)),
+    (146, d(TextureFormat::Bc7, Srgb)),
+    (147, d(TextureFormat::Etc2Rgb, Unorm)),
+    (148, d(TextureFormat::Etc2Rgb, Srgb)),
+    (149, d(TextureFormat::Etc2Rgba1, Unorm)),
+    (150, d(TextureFormat::Etc2Rgba1, Srgb)),
+    (151, d(TextureFormat::Etc2Rgba8, Unorm)),
+    (152, d(TextureFormat::Etc2Rgba8, Srgb)),
+    (153, d(TextureFormat::EacR, Unorm)),
+    (154, d(TextureFormat::EacRSigned, Snorm)),
+    (155, d(TextureFormat::EacRg, Unorm)),
+    (156, d(TextureFormat::EacRgSigned, Snorm)),
+    (157, d(TextureFormat::Astc(4, 4), Unorm)),
+    (158, d(TextureFormat::Astc(4, 4), Srgb)),
+    (159, d(TextureFormat::Astc(5, 4), Unorm)),
+    (160, d(TextureFormat::Astc(5, 4), Srgb)),
+    (161, d(TextureFormat::Astc(5, 5), Unorm)),
+    (162, d(TextureFormat::Astc(5, 5), Srgb)),
+    (163, d(TextureFormat::Astc(6, 5), Unorm)),
+    (164, d(TextureFormat::Astc(6, 5), Srgb)),
+    (165, d(TextureFormat::Astc(6, 6), Unorm)),
+    (166, d(TextureFormat::Astc(6, 6), Srgb)),
+    (167, d(TextureFormat::Astc(8, 5), Unorm)),
+    (168, d(TextureFormat::Astc(8, 5), Srgb)),
+    (169, d(TextureFormat::Astc(8, 6), Unorm)),
+    (170, d(TextureFormat::Astc(8, 6), Srgb)),
+    (171, d(TextureFormat::Astc(8, 8), Unorm)),
+    (172, d(TextureFormat::Astc(8, 8), Srgb)),
+    (173, d(TextureFormat::Astc(10, 5), Unorm)),
+    (174, d(TextureFormat::Astc(10, 5), Srgb)),
+    (175, d(TextureFormat::Astc(10, 6), Unorm)),
+    (176, d(TextureFormat::Astc(10, 6), Srgb)),
+    (177, d(TextureFormat::Astc(10, 8), Unorm)),
+    (178, d(TextureFormat::Astc(10, 8), Srgb)),
+    (179, d(TextureFormat::Astc(10, 10), Unorm)),
+    (180, d(TextureFormat::Astc(10, 10), Srgb)),
+    (181, d(TextureFormat::Astc(12, 10), Unorm)),
+    (182, d(TextureFormat::Astc(12, 10), Srgb)),
+    (183, d(TextureFormat::Astc(12, 12), Unorm)),
+    (184, d(TextureFormat::Astc(12, 12), Srgb)),
+    // VK_EXT_texture_compression_astc_hdr
+    (1000066000, d(TextureFormat::Astc(4, 4), Sfloat)),
+    (1000066001, d(TextureFormat::Astc(5, 4), Sfloat)),
+    (1000066002, d(TextureFormat::Astc(5, 5), Sfloat)),
+    (1000066003, d(TextureFormat::Astc(6, 5), Sfloat)),
+    (1000066004, d(TextureFormat::Astc(6, 6), Sfloat)),
+    (1000066005, d(TextureFormat::Astc(8, 5), Sfloat)),
+    (1000066006, d(TextureFormat::Astc(8, 6), Sfloat)),
+    (1000066007, d(TextureFormat::Astc(8, 8), Sfloat)),
+    (1000066008, d(TextureFormat::Astc(10, 5), Sfloat)),
+    (1000066009, d(TextureFormat::Astc(10, 6), Sfloat)),
+    (1000066010, d(TextureFormat::Astc(10, 8), Sfloat)),
+    (1000066011, d(TextureFormat::Astc(10, 10), Sfloat)),
+    (1000066012, d(TextureFormat::Astc(12, 10), Sfloat)),
+    (1000066013, d(TextureFormat::Astc(12, 12), Sfloat)),
+    // VK_IMG_format_pvrtc, PVRTC1
+    (1000054000, d(TextureFormat::Pvrtc2bpp, Unorm)),
+    (1000054001, d(TextureFormat::Pvrtc4bpp, Unorm)),
+    (1000054004, d(TextureFormat::Pvrtc2bpp, Srgb)),
+    (1000054005, d(TextureFormat::Pvrtc4bpp, Srgb)),
+];
+
+/// OpenGL internal formats.
+pub static GL_INTERNAL_FORMATS: [(u32, FormatDescriptor); 93] = [
+    // GL_AMD_compressed_ATC_texture
+    (0x8C92, d(TextureFormat::AtcRgb4, Unorm)),
+    (0x8C93, d(TextureFormat::AtcRgba8Explicit, Unorm)),
+    (0x87EE, d(TextureFormat::AtcRgba8, Unorm)),
+    // GL_KHR_texture_compression_astc_ldr and GL_KHR_texture_compression_astc_hdr
+    (0x93B0, d(TextureFormat::Astc(4, 4), Unorm)),
+    (0x93B1, d(TextureFormat::Astc(5, 4), Unorm)),
+    (0x93B2, d(TextureFormat::Astc(5, 5), Unorm)),
+    (0x93B3, d(TextureFormat::Astc(6, 5), Unorm)),
+    (0x93B4, d(TextureFormat::Astc(6, 6), Unorm)),
+    (0x93B5, d(TextureFormat::Astc(8, 5), Unorm)),
+    (0x93B6, d(TextureFormat::Astc(8, 6), Unorm)),
+    (0x93B7, d(TextureFormat::Astc(8, 8), Unorm)),
+    (0x93B8, d(TextureFormat::Astc(10, 5), Unorm)),
+    (0x93B9, d(TextureFormat::Astc(10, 6), Unorm)),
+    (0x93BA, d(TextureFormat::Astc(10, 8), Unorm)),
+    (0x93BB, d(TextureFormat::Astc(10, 10), Unorm)),
+    (0x93BC, d(TextureFormat::Astc(12, 10), Unorm)),
+    (0x93BD, d(TextureFormat::Astc(12, 12), Unorm)),
+    (0x93D0, d(TextureFormat::Astc(4, 4), Srgb)),
+    (0x93D1, d(TextureFormat::Astc(5, 4), Srgb)),
+    (0x93D2, d(TextureFormat::Astc(5, 5), Srgb)),
+    (0x93D3, d(TextureFormat::Astc(6, 5), Srgb)),
+    (0x93D4, d(TextureFormat::Astc(6, 6), Srgb)),
+    (0x93D5, d(TextureFormat::Astc(8, 5), Srgb)),
+    (0x93D6, d(TextureFormat::Astc(8, 6), Srgb)),
+    (0x93D7, d(TextureFormat::Astc(8, 8), Srgb)),
+    (0x93D8, d(TextureFormat::Astc(10, 5), Srgb)),
+    (0x93D9, d(TextureFormat::Astc(10, 6), Srgb)),
+    (0x93DA, d(TextureFormat::Astc(10, 8), Srgb)),
+    (0x93DB, d(TextureFormat::Astc(10, 10), Srgb)),
+    (0x93DC, d(TextureFormat::Astc(12, 10), Srgb)),
+    (0x93DD, d(TextureFormat::Astc(12, 12), Srgb)),
+    // S3TC, RGTC and BPTC
+    (0x83F0, d(TextureFormat::Bc1, Unorm)),
+    (0x8C4C, d(TextureFormat::Bc1, Srgb)),
+    (0x83F1, d(TextureFormat::Bc1a, Unorm)),
+    (0x8C4D, d(TextureFormat::Bc1a, Srgb)),
+    (0x83F2, d(TextureFormat::Bc2, Unorm)),
+    (0x8C4E, d(TextureFormat::Bc2, Srgb)),
+    (0x83F3, d(TextureFormat::Bc3, Unorm)),
+    (0x8C4F, d(TextureFormat::Bc3, Srgb)),
+    (0x8DBB, d(TextureFormat::Bc4, Unorm)),
+    (0x8DBD, d(TextureFormat::Bc5, Unorm)),
+    (0x8E8C, d(TextureFormat::Bc7, Unorm)),
+    (0x8E8D, d(TextureFormat::Bc7, Srgb)),
+    (0x8E8E, d(TextureFormat::Bc6Signed, Sfloat)),
+    (0x8E8F, d(TextureFormat::Bc6Unsigned, Ufloat)),
+    // ETC and EAC
+    (0x8D64, d(TextureFormat::Etc1, Unorm)),
+    (0x9274, d(TextureFormat::Etc2Rgb, Unorm)),
+    (0x9275, d(TextureFormat::Etc2Rgb, Srgb)),
+    (0x9276, d(TextureFormat::Etc2Rgba1, Unorm)),
+    (0x9277, d(TextureFormat::Etc2Rgba1, Srgb)),
+    (0x9278, d(TextureFormat::Etc2Rgba8, Unorm)),
+    (0x9279, d(TextureFormat::Etc2Rgba8, Srgb)),
+    (0x9270, d(TextureFormat::EacR, Unorm)),
+    (0x9271, d(TextureFormat::EacRSigned, Snorm)),
+    (0x9272, d(TextureFormat::EacRg, Unorm)),
+    (0x9273, d(TextureFormat::EacRgSigned, Snorm)),
+    // GL_3DFX_texture_compression_FXT1, the RGBA variant is preferred by the reverse lookup
+    (0x86B1, d(TextureFormat::Fxt1, Unorm)),
+    (0x86B0, d(TextureFormat::Fxt1, Unorm)),
+    // GL_IMG_texture_compression_pvrtc and GL_EXT_pvrtc_sRGB, the RGBA variants are preferred by the reverse lookup
+    (0x8C03, d(TextureFormat::Pvrtc2bpp, Unorm)),
+    (0x8C02, d(TextureFormat::Pvrtc4bpp, Unorm)),
+    (0x8A56, d(TextureFormat::Pvrtc2bpp, Srgb)),
+    (0x8A57, d(TextureFormat::Pvrtc4bpp, Srgb)),
+    (0x8C01, d(TextureFormat::Pvrtc2bpp, Unorm)),
+    (0x8C00, d(TextureFormat::Pvrtc4bpp, Unorm)),
+    (0x8A54, d(TextureFormat::Pvrtc2bpp, Srgb)),
+    (0x8A55, d(TextureFormat::Pvrtc4bpp, Srgb)),
+    // sized uncompressed formats
+    (0x8058, raw(RawFormat::Rgba32, Unorm)),
+    (0x8C43, raw(RawFormat::Rgba32, Srgb)),
+    (0x8D7C, raw(RawFormat::Rgba32, Uint)),
+    (0x8051, raw(RawFormat::Rgb24, Unorm)),
+    (0x8C41, raw(RawFormat::Rgb24, Srgb)),
+    (0x8D7D, raw(RawFormat::Rgb24, Uint)),
+    (0x93A1, raw(RawFormat::Bgra32, Unorm)),
+    (0x8229, raw(RawFormat::R8, Unorm)),
+    (0x8232, raw(RawFormat::R8, Uint)),
+    (0x8FBD, raw(RawFormat::R8, Srgb)),
+    (0x822B, raw(RawFormat::Rg16, Unorm)),
+    (0x8238, raw(RawFormat::Rg16, Uint)),
+    (0x822A, raw(RawFormat::R16, Unorm)),
+    (0x8234, raw(RawFormat::R16, Uint)),
+    (0x803C, raw(RawFormat::Alpha8, Unorm)),
+    (0x8040, raw(RawFormat::L8, Unorm)),
+    (0x8042, raw(RawFormat::L16, Unorm)),
+    (0x8D62, raw(RawFormat::Rgb565, Unorm)),
+    (0x8056, raw(RawFormat::Rgba4444, Unorm)),
+    (0x8057, raw(RawFormat::Rgba5551, Unorm)),
+    (0x8059, raw(RawFormat::Rgb10A2, Unorm)),
+    (0x822D, raw(RawFormat::RHalf, Sfloat)),
+    (0x822F, raw(RawFormat::RgHalf, Sfloat)),
+    (0x881A, raw(RawFormat::RgbaHalf, Sfloat)),
+    (0x822E, raw(RawFormat::RFloat, Sfloat)),
+    (0x8230, raw(RawFormat::RgFloat, Sfloat)),
+    (0x8814, raw(RawFormat::RgbaFloat, Sfloat)),
+];
+
+/// `MTLPixelFormat` values.
+pub static MTL_PIXEL_FORMATS: [(u32, FormatDescriptor); 97] = [
+    (1, raw(RawFormat::Alpha8, Unorm)),
+    (10, raw(RawFormat::R8, Unorm)),
+    (11, raw(RawFormat::R8, Srgb)),
+    (13, raw(RawFormat::R8, Uint)),
+    (20, raw(RawFormat::R16, Unorm)),
+    (23, raw(RawFormat::R16, Uint)),
+    (25, raw(RawFormat::RHalf, Sfloat)),
+    (30, raw(RawFormat::Rg16, Unorm)),
+    (31, raw(RawFormat::Rg16, Srgb)),
+    (33, raw(RawFormat::Rg16, Uint)),
+    // the packed formats list their channels from the least significant bits
+    (40, raw(RawFormat::Rgb565, Unorm)),
+    (41, raw(RawFormat::Rgba5551, Unorm)),
+    (42, raw(RawFormat::Rgba4444, Unorm)),
+    (43, raw(RawFormat::Argb1555, Unorm)),
+    (55, raw(RawFormat::RFloat, Sfloat)),
+    (65, raw(RawFormat::RgHalf, Sfloat)),
+    (70, raw(RawFormat::Rgba32, Unorm)),
+    (71, raw(RawFormat::Rgba32, Srgb)),
+    (73, raw(RawFormat::Rgba32, Uint)),
+    (80, raw(RawFormat::Bgra32, Unorm)),
+    (81, raw(RawFormat::Bgra32, Srgb)),
+    (90, raw(RawFormat::Rgb10A2, Unorm)),
+    (105, raw(RawFormat::RgFloat, Sfloat)),
+    (115, raw(RawFormat::RgbaHalf, Sfloat)),
+    (125, raw(RawFormat::RgbaFloat, Sfloat)),
+    (130, d(TextureFormat::Bc1a, Unorm)),
+    (131, d(TextureFormat::Bc1a, Srgb)),
+    (132, d(TextureFormat::Bc2, Unorm)),
+    (133, d(TextureFormat::Bc2, Srgb)),
+    (134, d(TextureFormat::Bc3, Unorm)),
+    (135, d(TextureFormat::Bc3, Srgb)),
+    (140, d(TextureFormat::Bc4, Unorm)),
+    (142, d(TextureFormat::Bc5, Unorm)),
+    (150, d(TextureFormat::Bc6Signed, Sfloat)),
+    (151, d(TextureFormat::Bc6Unsigned, Ufloat)),
+    (152, d(TextureFormat::Bc7, Unorm)),
+    (153, d(TextureFormat::Bc7, Srgb)),
+    // the RGBA variants of PVRTC are preferred by the reverse lookup
+    (164, d(TextureFormat::Pvrtc2bpp, Unorm)),
+    (165, d(TextureFormat::Pvrtc2bpp, Srgb)),
+    (166, d(TextureFormat::Pvrtc4bpp, Unorm)),
+    (167, d(TextureFormat::Pvrtc4bpp, Srgb)),
+    (160, d(TextureFormat::Pvrtc2bpp, Unorm)),
+    (161, d(TextureFormat::Pvrtc2bpp, Srgb)),
+    (162, d(TextureFormat::Pvrtc4bpp, Unorm)),
+    (163, d(TextureFormat::Pvrtc4bpp, Srgb)),
+    (170, d(TextureFormat::EacR, Unorm)),
+    (172, d(TextureFormat::EacRSigned, Snorm)),
+    (174, d(TextureFormat::EacRg, Unorm)),
+    (176, d(TextureFormat::EacRgSigned, Snorm)),
+    (178, d(TextureFormat::Etc2Rgba8, Unorm)),
+    (179, d(TextureFormat::Etc2Rgba8, Srgb)),
+    (180, d(TextureFormat::Etc2Rgb, Unorm)),
+    (181, d(TextureFormat::Etc2Rgb, Srgb)),
+    (182, d(TextureFormat::Etc2Rgba1, Unorm)),
+    (183, d(TextureFormat::Etc2Rgba1, Srgb)),
+    (204, d(TextureFormat::Astc(4, 4), Unorm)),
+    (205, d(TextureFormat::Astc(5, 4), Unorm)),
+    (206, d(TextureFormat::Astc(5, 5), Unorm)),
+    (207, d(TextureFormat::Astc(6, 5), Unorm)),
+    (208, d(TextureFormat::Astc(6, 6), Unorm)),
+    (210, d(TextureFormat::Astc(8, 5), Unorm)),
+    (211, d(TextureFormat::Astc(8, 6), Unorm)),
+    (212, d(TextureFormat::Astc(8, 8), Unorm)),
+    (213, d(TextureFormat::Astc(10, 5), Unorm)),
+    (214, d(TextureFormat::Astc(10, 6), Unorm)),
+    (215, d(TextureFormat::Astc(10, 8), Unorm)),
+    (216, d(TextureFormat::Astc(10, 10), Unorm)),
+    (217, d(TextureFormat::Astc(12, 10), Unorm)),
+    (218, d(TextureFormat::Astc(12, 12), Unorm)),
+    (186, d(TextureFormat::Astc(4, 4), Srgb)),
+    (187, d(TextureFormat::Astc(5, 4), Srgb)),
+    (188, d(TextureFormat::Astc(5, 5), Srgb)),
+    (189, d(TextureFormat::Astc(6, 5), Srgb)),
+    (190, d(TextureFormat::Astc(6, 6), Srgb)),
+    (192, d(TextureFormat::Astc(8, 5), Srgb)),
+    (193, d(TextureFormat::Astc(8, 6), Srgb)),
+    (194, d(TextureFormat::Astc(8, 8), Srgb)),
+    (195, d(TextureFormat::Astc(10, 5), Srgb)),
+    (196, d(TextureFormat::Astc(10, 6), Srgb)),
+    (197, d(TextureFormat::Astc(10, 8), Srgb)),
+    (198, d(TextureFormat::Astc(10, 10), Srgb)),
+    (199, d(TextureFormat::Astc(12, 10), Srgb)),
+    (200, d(TextureFormat::Astc(12, 12), Srgb)),
+    (222, d(TextureFormat::Astc(4, 4), Sfloat)),
+    (223, d(TextureFormat::Astc(5, 4), Sfloat)),
+    (224, d(TextureFormat::Astc(5, 5), Sfloat)),
+    (225, d(TextureFormat::Astc(6, 5), Sfloat)),
+    (226, d(TextureFormat::Astc(6, 6), Sfloat)),
+    (228, d(TextureFormat::Astc(8, 5), Sfloat)),
+    (229, d(TextureFormat::Astc(8, 6), Sfloat)),
+    (230, d(TextureFormat::Astc(8, 8), Sfloat)),
+    (231, d(TextureFormat::Astc(10, 5), Sfloat)),
+    (232, d(TextureFormat::Astc(10, 6), Sfloat)),
+    (233, d(TextureFormat::Astc(10, 8), Sfloat)),
+    (234, d(TextureFormat::Astc(10, 10), Sfloat)),
+    (235, d(TextureFormat::Astc(12, 10), Sfloat)),
+    (236, d(TextureFormat::Astc(12, 12), Sfloat)),
+];
+
+fn lookup(table: &[(u32, FormatDescriptor)], value: u32) -> Option<FormatDescriptor> {
+    table.iter().find(|(v, _)| *v == value).map(|(_, d)| *d)
+}
+
+fn reverse_lookup(table: &[(u32, FormatDescriptor)], descriptor: FormatDescriptor) -> Option<u32> {
+    table
+        .iter()
+        .find(|(_, d)| *d == descriptor)
+        .map(|(v, _)| *v)
+}
+
+/// Maps a `DXGI_FORMAT` to a format descriptor.
+pub fn from_dxgi_format(dxgi_format: u32) -> Option<FormatDescriptor> {
+    lookup(&DXGI_FORMATS, dxgi_format)
+}
+
+/// Maps a format descriptor to a `DXGI_FORMAT`.
+pub fn to_dxgi_format(descriptor: FormatDescriptor) -> Option<u32> {
+    reverse_lookup(&DXGI_FORMATS, descriptor)
+}
+
+/// Maps a `VkFormat` to a format descriptor.
+pub fn from_vk_format(vk_format: u32) -> Option<FormatDescriptor> {
+    lookup(&VK_FORMATS, vk_format)
+}
+
+/// Maps a format descriptor to a `VkFormat`.
+pub fn to_vk_format(descriptor: FormatDescriptor) -> Option<u32> {
+    reverse_lookup(&VK_FORMATS, descriptor)
+}
+
+/// Maps an OpenGL internal format to a format descriptor.
+pub fn from_gl_internal_format(gl_internal_format: u32) -> Option<FormatDescriptor> {
+    lookup(&GL_INTERNAL_FORMATS, gl_internal_format)
+}
+
+/// Maps a format descriptor to an OpenGL internal format.
+pub fn to_gl_internal_format(descriptor: FormatDescriptor) -> Option<u32> {
+    reverse_lookup(&GL_INTERNAL_FORMATS, descriptor)
+}
+
+/// Maps a `MTLPixelFormat` to a format descriptor.
+pub fn from_mtl_pixel_format(mtl_pixel_format: u32) -> Option<FormatDescriptor> {
+    lookup(&MTL_PIXEL_FORMATS, mtl_pixel_format)
+}
+
+/// Maps a format descriptor to a `MTLPixelFormat`.
+pub fn to_mtl_pixel_format(descriptor: FormatDescriptor) -> Option<u32> {
+    reverse_lookup(&MTL_PIXEL_FORMATS, descriptor)
+}
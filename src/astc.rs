@@ -1,18 +1,9 @@
 #![allow(clippy::too_many_arguments)]
 use crate::bitreader::{getbits, getbits64};
-use crate::color::{color, copy_block_buffer};
+use crate::color::{color, copy_block_buffer, f32_to_u8, floor, Flip};
 use crate::f16::fp16_ieee_to_fp32_value;
 use core::result::Result;
 
-#[inline]
-fn floor(x: f32) -> f32 {
-    let mut i = x as i32;
-    if x < 0.0 && x != i as f32 {
-        i -= 1;
-    }
-    i as f32
-}
-
 static BIT_REVERSE_TABLE: [u8; 256] = [
     0x00, 0x80, 0x40, 0xC0, 0x20, 0xA0, 0x60, 0xE0, 0x10, 0x90, 0x50, 0xD0, 0x30, 0xB0, 0x70, 0xF0,
     0x08, 0x88, 0x48, 0xC8, 0x28, 0xA8, 0x68, 0xE8, 0x18, 0x98, 0x58, 0xD8, 0x38, 0xB8, 0x78, 0xF8,
@@ -223,6 +214,12 @@ const fn select_color(v0: i32, v1: i32, weight: i32) -> u8 {
         as u8
 }
 
+// the sRGB decode mode expands the 8 bit endpoints with 0x80 and uses the top 8 bits of the result
+#[inline]
+const fn select_color_srgb(v0: i32, v1: i32, weight: i32) -> u8 {
+    ((((v0 << 8 | 0x80) * (64 - weight) + (v1 << 8 | 0x80) * weight + 32) >> 6) >> 8) as u8
+}
+
 #[inline]
 fn select_color_hdr(v0: i32, v1: i32, weight: i32) -> u8 {
     let c: u16 = (((v0 << 4) * (64 - weight) + (v1 << 4) * weight + 32) >> 6) as u16;
@@ -242,11 +239,6 @@ fn select_color_hdr(v0: i32, v1: i32, weight: i32) -> u8 {
     }
 }
 
-#[inline]
-fn f32_to_u8(f: f32) -> u8 {
-    floor(f * 255.0).clamp(0.0, 255.0) as u8
-}
-
 #[inline]
 fn f16ptr_to_u8(ptr: &[u8]) -> u8 {
     f32_to_u8(fp16_ieee_to_fp32_value(u16::from_le_bytes([
@@ -257,8 +249,10 @@ fn f16ptr_to_u8(ptr: &[u8]) -> u8 {
 struct BlockData {
     bw: usize,
     bh: usize,
+    bd: usize,
     width: usize,
     height: usize,
+    depth: usize,
     part_num: usize,
     dual_plane: bool,
     plane_selector: usize,
@@ -268,8 +262,9 @@ struct BlockData {
     cem_range: usize,
     endpoint_value_num: usize,
     endpoints: [[i32; 8]; 4],
-    weights: [[i32; 2]; 144],
-    partition: [usize; 144],
+    weights: [[i32; 2]; 216],
+    partition: [usize; 216],
+    srgb: bool,
 }
 
 impl BlockData {
@@ -277,8 +272,10 @@ impl BlockData {
         Self {
             bw: 0,
             bh: 0,
+            bd: 1,
             width: 0,
             height: 0,
+            depth: 1,
             part_num: 0,
             dual_plane: false,
             plane_selector: 0,
@@ -288,8 +285,9 @@ impl BlockData {
             cem_range: 0,
             endpoint_value_num: 0,
             endpoints: [[0; 8]; 4],
-            weights: [[0; 2]; 144],
-            partition: [0; 144],
+            weights: [[0; 2]; 216],
+            partition: [0; 216],
+            srgb: false,
         }
     }
 }
@@ -537,7 +535,9 @@ fn decode_block_params(buf: &[u8], block_data: &mut BlockData) {
     block_data.dual_plane = (buf[1] & 4) != 0;
     block_data.weight_range = ((buf[0] >> 4 & 1) | (buf[1] << 2 & 8)) as usize;
 
-    if buf[0] & 3 != 0 {
+    if block_data.bd > 1 {
+        decode_block_mode_3d(buf, block_data);
+    } else if buf[0] & 3 != 0 {
         block_data.weight_range |= (buf[0] << 1 & 6) as usize;
         match buf[0] & 0xc {
             0 => {
@@ -590,7 +590,7 @@ fn decode_block_params(buf: &[u8], block_data: &mut BlockData) {
 
     block_data.part_num = ((buf[1] >> 3 & 3) + 1) as usize;
 
-    block_data.weight_num = block_data.width * block_data.height;
+    block_data.weight_num = block_data.width * block_data.height * block_data.depth;
     if block_data.dual_plane {
         block_data.weight_num *= 2;
     }
@@ -688,6 +688,39 @@ fn decode_block_params(buf: &[u8], block_data: &mut BlockData) {
     }
 }
 
+fn decode_block_mode_3d(buf: &[u8], block_data: &mut BlockData) {
+    let mode = u8ptr_to_u16(buf) as usize;
+    let a = mode >> 5 & 3;
+    if mode & 3 != 0 {
+        block_data.weight_range |= mode << 1 & 6;
+        block_data.width = a + 2;
+        block_data.height = (mode >> 7 & 3) + 2;
+        block_data.depth = (mode >> 2 & 3) + 2;
+    } else {
+        block_data.weight_range |= mode >> 1 & 6;
+        let b = mode >> 9 & 3;
+        let (width, height, depth) = match mode >> 7 & 3 {
+            0 => (6, b + 2, a + 2),
+            1 => (a + 2, 6, b + 2),
+            2 => (a + 2, b + 2, 6),
+            // a == 3 is reserved and rejected before the parameters are decoded
+            _ => match a {
+                0 => (6, 2, 2),
+                1 => (2, 6, 2),
+                _ => (2, 2, 6),
+            },
+        };
+        block_data.width = width;
+        block_data.height = height;
+        block_data.depth = depth;
+        // bits 9 and 10 are part of the grid size in these modes
+        if mode >> 7 & 3 != 3 {
+            block_data.dual_plane = false;
+            block_data.weight_range &= 7;
+        }
+    }
+}
+
 fn decode_endpoints_hdr7(endpoints: &mut [i32], v: &[i32]) {
     let modeval = (v[2] >> 4 & 0x8) | (v[1] >> 5 & 0x4) | (v[0] >> 6);
     let (major_component, mode) = {
@@ -1474,6 +1507,11 @@ fn decode_weights(buf: &[u8], data: &mut BlockData) {
     let dt = (1024 + data.bh / 2) / (data.bh - 1);
     let pn = if data.dual_plane { 2 } else { 1 };
 
+    if data.bd > 1 {
+        infill_weights_3d(&wv, ds, dt, pn, data);
+        return;
+    }
+
     let mut i = 0;
     for t in 0..data.bh {
         for s in 0..data.bw {
@@ -1500,8 +1538,50 @@ fn decode_weights(buf: &[u8], data: &mut BlockData) {
     }
 }
 
+// 3D blocks use simplex interpolation between four of the eight surrounding grid weights
+fn infill_weights_3d(wv: &[i32], ds: usize, dt: usize, pn: usize, data: &mut BlockData) {
+    let dr = (1024 + data.bd / 2) / (data.bd - 1);
+    let n = data.width;
+    let nm = data.width * data.height;
+
+    let mut i = 0;
+    for r in 0..data.bd {
+        for t in 0..data.bh {
+            for s in 0..data.bw {
+                let gs = (ds * s * (data.width - 1) + 32) >> 6;
+                let gt = (dt * t * (data.height - 1) + 32) >> 6;
+                let gr = (dr * r * (data.depth - 1) + 32) >> 6;
+                let fs = (gs & 0xf) as i32;
+                let ft = (gt & 0xf) as i32;
+                let fr = (gr & 0xf) as i32;
+                let v = (gs >> 4) + (gt >> 4) * n + (gr >> 4) * nm;
+
+                let (s1, s2, w0, w1, w2, w3) =
+                    match ((fs > ft) as u8) << 2 | ((ft > fr) as u8) << 1 | (fs > fr) as u8 {
+                        7 => (1, n, 16 - fs, fs - ft, ft - fr, fr),
+                        3 => (n, 1, 16 - ft, ft - fs, fs - fr, fr),
+                        5 => (1, nm, 16 - fs, fs - fr, fr - ft, ft),
+                        4 => (nm, 1, 16 - fr, fr - fs, fs - ft, ft),
+                        2 => (n, nm, 16 - ft, ft - fr, fr - fs, fs),
+                        _ => (nm, n, 16 - fr, fr - ft, ft - fs, fs),
+                    };
+
+                for p in 0..pn {
+                    let p0 = wv[v * pn + p];
+                    let p1 = wv[(v + s1) * pn + p];
+                    let p2 = wv[(v + s1 + s2) * pn + p];
+                    let p3 = wv[(v + 1 + n + nm) * pn + p];
+                    data.weights[i][p] = (p0 * w0 + p1 * w1 + p2 * w2 + p3 * w3 + 8) >> 4;
+                }
+
+                i += 1;
+            }
+        }
+    }
+}
+
 fn select_partition(buf: &[u8], data: &mut BlockData) {
-    let small_block = data.bw * data.bh < 31;
+    let small_block = data.bw * data.bh * data.bd < 31;
     // TODO - check if this cast is correct, original code uses (int *)buf
     let seed = (i32::from_le_bytes(buf[0..4].try_into().unwrap()) >> 13 & 0x3ff)
         | (data.part_num as i32 - 1) << 10;
@@ -1518,68 +1598,50 @@ fn select_partition(buf: &[u8], data: &mut BlockData) {
     rnum ^= rnum << 6;
     rnum ^= rnum >> 17;
 
-    let mut seeds: [i32; 8] = [0; 8];
+    let mut seeds: [i32; 12] = [0; 12];
     (0..8).for_each(|i| {
         let v = rnum >> (i * 4) & 0xF;
         seeds[i] = (v * v) as i32;
     });
+    // the z seeds are only used by 3D blocks
+    (0..4).for_each(|i| {
+        let v = rnum.rotate_right(18 + i as u32 * 4) & 0xF;
+        seeds[8 + i] = (v * v) as i32;
+    });
     let sh: [i32; 2] = [
         if seed & 2 != 0 { 4 } else { 5 },
         if data.part_num == 3 { 6 } else { 5 },
     ];
 
-    if seed & 1 != 0 {
-        (0..8).for_each(|i| seeds[i] >>= sh[i % 2]);
+    let (sh1, sh2) = if seed & 1 != 0 {
+        (sh[0], sh[1])
     } else {
-        (0..8).for_each(|i| seeds[i] >>= sh[1 - i % 2]);
-    }
+        (sh[1], sh[0])
+    };
+    (0..8).for_each(|i| seeds[i] >>= if i % 2 == 0 { sh1 } else { sh2 });
+    let sh3 = if seed & 0x10 != 0 { sh1 } else { sh2 };
+    (8..12).for_each(|i| seeds[i] >>= sh3);
 
     let mut i = 0;
-    if small_block {
-        for t in 0..data.bh {
-            for s in 0..data.bw {
-                let x = s << 1;
-                let y = t << 1;
-                let a = (seeds[0] * x as i32 + seeds[1] * y as i32 + (rnum >> 14) as i32) & 0x3f;
-                let b = (seeds[2] * x as i32 + seeds[3] * y as i32 + (rnum >> 10) as i32) & 0x3f;
-                let c = if data.part_num < 3 {
-                    0
-                } else {
-                    (seeds[4] * x as i32 + seeds[5] * y as i32 + (rnum >> 6) as i32) & 0x3f
-                };
-                let d = if data.part_num < 4 {
-                    0
-                } else {
-                    (seeds[6] * x as i32 + seeds[7] * y as i32 + (rnum >> 2) as i32) & 0x3f
-                };
-                data.partition[i] = {
-                    if a >= b && a >= c && a >= d {
-                        0
-                    } else if b >= c && b >= d {
-                        1
-                    } else if c >= d {
-                        2
-                    } else {
-                        3
-                    }
-                };
-                i += 1;
-            }
-        }
-    } else {
+    for z in 0..data.bd {
         for y in 0..data.bh {
             for x in 0..data.bw {
-                let a = (seeds[0] * x as i32 + seeds[1] * y as i32 + (rnum >> 14) as i32) & 0x3f;
-                let b = (seeds[2] * x as i32 + seeds[3] * y as i32 + (rnum >> 10) as i32) & 0x3f;
+                let (x, y, z) = if small_block {
+                    ((x << 1) as i32, (y << 1) as i32, (z << 1) as i32)
+                } else {
+                    (x as i32, y as i32, z as i32)
+                };
+                let a = (seeds[0] * x + seeds[1] * y + seeds[10] * z + (rnum >> 14) as i32) & 0x3f;
+                let b = (seeds[2] * x + seeds[3] * y + seeds[11] * z + (rnum >> 10) as i32) & 0x3f;
                 let c = if data.part_num < 3 {
                     0
                 } else {
-                    (seeds[4] * x as i32 + seeds[5] * y as i32 + (rnum >> 6) as i32) & 0x3f
+                    (seeds[4] * x + seeds[5] * y + seeds[8] * z + (rnum >> 6) as i32) & 0x3f
                 };
                 let d = if data.part_num < 4 {
                     0
                 } else {
-                    (seeds[6] * x as i32 + seeds[7] * y as i32 + (rnum >> 2) as i32) & 0x3f
+                    (seeds[6] * x + seeds[7] * y + seeds[9] * z + (rnum >> 2) as i32) & 0x3f
                 };
                 data.partition[i] = {
                     if a >= b && a >= c && a >= d {
@@ -1635,28 +1697,53 @@ fn applicate_color(data: &mut BlockData, outbuf: &mut [u32]) {
         select_color,
         select_color_hdr,
     ];
+    static FUNC_TABLE_C_SRGB: [fn(i32, i32, i32) -> u8; 16] = [
+        select_color_srgb,
+        select_color_srgb,
+        select_color_hdr,
+        select_color_hdr,
+        select_color_srgb,
+        select_color_srgb,
+        select_color_srgb,
+        select_color_hdr,
+        select_color_srgb,
+        select_color_srgb,
+        select_color_srgb,
+        select_color_hdr,
+        select_color_srgb,
+        select_color_srgb,
+        select_color_hdr,
+        select_color_hdr,
+    ];
+    // the sRGB decode mode doesn't change the expansion of the alpha endpoints
+    let table_a = &FUNC_TABLE_A;
+    let table_c = if data.srgb {
+        &FUNC_TABLE_C_SRGB
+    } else {
+        &FUNC_TABLE_C
+    };
     if data.dual_plane {
         let mut ps: [usize; 4] = [0; 4];
         ps[data.plane_selector] = 1;
         if data.part_num > 1 {
-            (0..(data.bw * data.bh)).for_each(|i| {
+            (0..(data.bw * data.bh * data.bd)).for_each(|i| {
                 let p = data.partition[i];
-                let r: u8 = FUNC_TABLE_C[data.cem[p]](
+                let r: u8 = table_c[data.cem[p]](
                     data.endpoints[p][0],
                     data.endpoints[p][4],
                     data.weights[i][ps[0]],
                 );
-                let g: u8 = FUNC_TABLE_C[data.cem[p]](
+                let g: u8 = table_c[data.cem[p]](
                     data.endpoints[p][1],
                     data.endpoints[p][5],
                     data.weights[i][ps[1]],
                 );
-                let b: u8 = FUNC_TABLE_C[data.cem[p]](
+                let b: u8 = table_c[data.cem[p]](
                     data.endpoints[p][2],
                     data.endpoints[p][6],
                     data.weights[i][ps[2]],
                 );
-                let a: u8 = FUNC_TABLE_A[data.cem[p]](
+                let a: u8 = table_a[data.cem[p]](
                     data.endpoints[p][3],
                     data.endpoints[p][7],
                     data.weights[i][ps[3]],
@@ -1664,23 +1751,23 @@ fn applicate_color(data: &mut BlockData, outbuf: &mut [u32]) {
                 outbuf[i] = color(r, g, b, a);
             });
         } else {
-            (0..(data.bw * data.bh)).for_each(|i| {
-                let r: u8 = FUNC_TABLE_C[data.cem[0]](
+            (0..(data.bw * data.bh * data.bd)).for_each(|i| {
+                let r: u8 = table_c[data.cem[0]](
                     data.endpoints[0][0],
                     data.endpoints[0][4],
                     data.weights[i][ps[0]],
                 );
-                let g: u8 = FUNC_TABLE_C[data.cem[0]](
+                let g: u8 = table_c[data.cem[0]](
                     data.endpoints[0][1],
                     data.endpoints[0][5],
                     data.weights[i][ps[1]],
                 );
-                let b: u8 = FUNC_TABLE_C[data.cem[0]](
+                let b: u8 = table_c[data.cem[0]](
                     data.endpoints[0][2],
                     data.endpoints[0][6],
                     data.weights[i][ps[2]],
                 );
-                let a: u8 = FUNC_TABLE_A[data.cem[0]](
+                let a: u8 = table_a[data.cem[0]](
                     data.endpoints[0][3],
                     data.endpoints[0][7],
                     data.weights[i][ps[3]],
@@ -1689,24 +1776,24 @@ fn applicate_color(data: &mut BlockData, outbuf: &mut [u32]) {
             });
         }
     } else if data.part_num > 1 {
-        (0..(data.bw * data.bh)).for_each(|i| {
+        (0..(data.bw * data.bh * data.bd)).for_each(|i| {
             let p = data.partition[i];
-            let r: u8 = FUNC_TABLE_C[data.cem[p]](
+            let r: u8 = table_c[data.cem[p]](
                 data.endpoints[p][0],
                 data.endpoints[p][4],
                 data.weights[i][0],
             );
-            let g: u8 = FUNC_TABLE_C[data.cem[p]](
+            let g: u8 = table_c[data.cem[p]](
                 data.endpoints[p][1],
                 data.endpoints[p][5],
                 data.weights[i][0],
             );
-            let b: u8 = FUNC_TABLE_C[data.cem[p]](
+            let b: u8 = table_c[data.cem[p]](
                 data.endpoints[p][2],
                 data.endpoints[p][6],
                 data.weights[i][0],
             );
-            let a: u8 = FUNC_TABLE_A[data.cem[p]](
+            let a: u8 = table_a[data.cem[p]](
                 data.endpoints[p][3],
                 data.endpoints[p][7],
                 data.weights[i][0],
@@ -1714,23 +1801,23 @@ fn applicate_color(data: &mut BlockData, outbuf: &mut [u32]) {
             outbuf[i] = color(r, g, b, a);
         });
     } else {
-        (0..(data.bw * data.bh)).for_each(|i| {
-            let r: u8 = FUNC_TABLE_C[data.cem[0]](
+        (0..(data.bw * data.bh * data.bd)).for_each(|i| {
+            let r: u8 = table_c[data.cem[0]](
                 data.endpoints[0][0],
                 data.endpoints[0][4],
                 data.weights[i][0],
             );
-            let g: u8 = FUNC_TABLE_C[data.cem[0]](
+            let g: u8 = table_c[data.cem[0]](
                 data.endpoints[0][1],
                 data.endpoints[0][5],
                 data.weights[i][0],
             );
-            let b: u8 = FUNC_TABLE_C[data.cem[0]](
+            let b: u8 = table_c[data.cem[0]](
                 data.endpoints[0][2],
                 data.endpoints[0][6],
                 data.weights[i][0],
             );
-            let a: u8 = FUNC_TABLE_A[data.cem[0]](
+            let a: u8 = table_a[data.cem[0]](
                 data.endpoints[0][3],
                 data.endpoints[0][7],
                 data.weights[i][0],
@@ -1742,6 +1829,47 @@ fn applicate_color(data: &mut BlockData, outbuf: &mut [u32]) {
 
 #[inline]
 pub fn decode_astc_block(buf: &[u8], block_width: usize, block_height: usize, outbuf: &mut [u32]) {
+    decode_astc_3d_block(buf, block_width, block_height, 1, outbuf)
+}
+
+/// Decodes a block of an sRGB ASTC texture with the sRGB decode mode of the specification,
+/// the output are the sRGB encoded values.
+#[inline]
+pub fn decode_astc_srgb_block(
+    buf: &[u8],
+    block_width: usize,
+    block_height: usize,
+    outbuf: &mut [u32],
+) {
+    decode_block(buf, block_width, block_height, 1, true, outbuf)
+}
+
+/// Decodes a block of a 3D ASTC texture, the texels are written slice by slice.
+/// A block depth of 1 decodes the block as 2D block.
+pub fn decode_astc_3d_block(
+    buf: &[u8],
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    outbuf: &mut [u32],
+) {
+    decode_block(buf, block_width, block_height, block_depth, false, outbuf)
+}
+
+fn decode_block(
+    buf: &[u8],
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    srgb: bool,
+    outbuf: &mut [u32],
+) {
+    let block_size = block_width * block_height * block_depth;
+    let reserved_mode = if block_depth > 1 {
+        (buf[0] & 0xe3) == 0xe0 && (buf[1] & 1) == 1
+    } else {
+        (buf[0] & 0xc3) == 0xc0 && (buf[1] & 1) == 1
+    };
     if buf[0] == 0xfc && (buf[1] & 1) == 1 {
         let c: u32 = if buf[1] & 2 != 0 {
             color(
@@ -1753,15 +1881,21 @@ pub fn decode_astc_block(buf: &[u8], block_width: usize, block_height: usize, ou
         } else {
             color(buf[9], buf[11], buf[13], buf[15])
         };
-        outbuf[0..(block_width * block_height)].fill(c);
-    } else if ((buf[0] & 0xc3) == 0xc0 && (buf[1] & 1) == 1) || (buf[0] & 0xf) == 0 {
+        outbuf[0..block_size].fill(c);
+    } else if reserved_mode || (buf[0] & 0xf) == 0 {
         let c: u32 = color(255, 0, 255, 255);
-        outbuf[0..(block_width * block_height)].fill(c);
+        outbuf[0..block_size].fill(c);
     } else {
         let mut block_data = BlockData::default();
         block_data.bw = block_width;
         block_data.bh = block_height;
+        block_data.bd = block_depth;
+        block_data.srgb = srgb;
         decode_block_params(buf, &mut block_data);
+        if block_data.weight_num > 64 || (block_data.dual_plane && block_data.part_num == 4) {
+            outbuf[0..block_size].fill(color(255, 0, 255, 255));
+            return;
+        }
         decode_endpoints(buf, &mut block_data);
         decode_weights(buf, &mut block_data);
         if block_data.part_num > 1 {
@@ -1778,6 +1912,93 @@ pub fn decode_astc(
     block_width: usize,
     block_height: usize,
     image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_astc_image(
+        data,
+        width,
+        height,
+        block_width,
+        block_height,
+        false,
+        image,
+        Flip::NONE,
+    )
+}
+
+/// Decodes an ASTC texture into an image mirrored by flip.
+pub fn decode_astc_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    block_width: usize,
+    block_height: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_astc_image(
+        data,
+        width,
+        height,
+        block_width,
+        block_height,
+        false,
+        image,
+        flip,
+    )
+}
+
+/// Decodes an sRGB ASTC texture with the sRGB decode mode of the specification,
+/// the image receives the sRGB encoded values.
+pub fn decode_astc_srgb(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    block_width: usize,
+    block_height: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_astc_srgb_flipped(
+        data,
+        width,
+        height,
+        block_width,
+        block_height,
+        image,
+        Flip::NONE,
+    )
+}
+
+/// Decodes an sRGB ASTC texture with the sRGB decode mode into an image mirrored by flip.
+pub fn decode_astc_srgb_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    block_width: usize,
+    block_height: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_astc_image(
+        data,
+        width,
+        height,
+        block_width,
+        block_height,
+        true,
+        image,
+        flip,
+    )
+}
+
+fn decode_astc_image(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    block_width: usize,
+    block_height: usize,
+    srgb: bool,
+    image: &mut [u32],
+    flip: Flip,
 ) -> Result<(), &'static str> {
     let num_blocks_x = width.div_ceil(block_width);
     let num_blocks_y = height.div_ceil(block_height);
@@ -1799,7 +2020,14 @@ pub fn decode_astc(
 
     (0..num_blocks_y).for_each(|by| {
         (0..num_blocks_x).for_each(|bx| {
-            decode_astc_block(&data[data_offset..], block_width, block_height, &mut buffer);
+            decode_block(
+                &data[data_offset..],
+                block_width,
+                block_height,
+                1,
+                srgb,
+                &mut buffer,
+            );
             copy_block_buffer(
                 bx,
                 by,
@@ -1809,6 +2037,7 @@ pub fn decode_astc(
                 block_height,
                 &buffer,
                 image,
+                flip,
             );
             data_offset += 16;
         });
@@ -1817,6 +2046,176 @@ pub fn decode_astc(
     Ok(())
 }
 
+/// Decodes a 3D ASTC texture into an image with the depth slices stored one after another.
+pub fn decode_astc_3d(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    depth: usize,
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_astc_3d_flipped(
+        data,
+        width,
+        height,
+        depth,
+        block_width,
+        block_height,
+        block_depth,
+        image,
+        Flip::NONE,
+    )
+}
+
+/// Decodes a 3D ASTC texture into an image with the depth slices stored one after another,
+/// each slice is mirrored by flip.
+pub fn decode_astc_3d_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    depth: usize,
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_astc_3d_image(
+        data,
+        width,
+        height,
+        depth,
+        block_width,
+        block_height,
+        block_depth,
+        image,
+        false,
+        flip,
+    )
+}
+
+/// Decodes a 3D ASTC texture with sRGB endpoints into an image with the depth slices stored one after another,
+/// see [`decode_astc_srgb()`].
+pub fn decode_astc_3d_srgb(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    depth: usize,
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_astc_3d_srgb_flipped(
+        data,
+        width,
+        height,
+        depth,
+        block_width,
+        block_height,
+        block_depth,
+        image,
+        Flip::NONE,
+    )
+}
+
+/// Decodes a 3D ASTC texture with sRGB endpoints into an image with the depth slices stored one after another,
+/// each slice is mirrored by flip.
+pub fn decode_astc_3d_srgb_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    depth: usize,
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_astc_3d_image(
+        data,
+        width,
+        height,
+        depth,
+        block_width,
+        block_height,
+        block_depth,
+        image,
+        true,
+        flip,
+    )
+}
+
+fn decode_astc_3d_image(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    depth: usize,
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    image: &mut [u32],
+    srgb: bool,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    let num_blocks_x = width.div_ceil(block_width);
+    let num_blocks_y = height.div_ceil(block_height);
+    let num_blocks_z = depth.div_ceil(block_depth);
+    let slice_size = width * height;
+    let block_slice_size = block_width * block_height;
+    let mut buffer: [u32; 216] = [0; 216];
+    let mut data_offset = 0;
+
+    if data.len() < num_blocks_x * num_blocks_y * num_blocks_z * 16 {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < slice_size * depth {
+        return Err("Image buffer is too small!");
+    }
+
+    if block_slice_size * block_depth > 216 {
+        return Err("Block size is too big!");
+    }
+
+    (0..num_blocks_z).for_each(|bz| {
+        (0..num_blocks_y).for_each(|by| {
+            (0..num_blocks_x).for_each(|bx| {
+                decode_block(
+                    &data[data_offset..],
+                    block_width,
+                    block_height,
+                    block_depth,
+                    srgb,
+                    &mut buffer,
+                );
+                (0..block_depth)
+                    .take_while(|z| bz * block_depth + z < depth)
+                    .for_each(|z| {
+                        let slice_offset = (bz * block_depth + z) * slice_size;
+                        copy_block_buffer(
+                            bx,
+                            by,
+                            width,
+                            height,
+                            block_width,
+                            block_height,
+                            &buffer[z * block_slice_size..],
+                            &mut image[slice_offset..slice_offset + slice_size],
+                            flip,
+                        );
+                    });
+                data_offset += 16;
+            });
+        });
+    });
+
+    Ok(())
+}
+
 // generate some sized astc block decode functions
 macro_rules! astc_decode_func {
     ($x: expr, $y: expr) => {
@@ -1847,3 +2246,31 @@ astc_decode_func!(10, 8);
 astc_decode_func!(10, 10);
 astc_decode_func!(12, 10);
 astc_decode_func!(12, 12);
+
+// generate the sized 3d astc block decode functions
+macro_rules! astc_3d_decode_func {
+    ($x: expr, $y: expr, $z: expr) => {
+        paste::item! {
+            pub fn [<decode_astc_ $x _ $y _ $z>](
+                data: &[u8],
+                width: usize,
+                height: usize,
+                depth: usize,
+                image: &mut [u32],
+            ) -> Result<(), &'static str> {
+                decode_astc_3d(data, width, height, depth, $x, $y, $z, image)
+            }
+        }
+    };
+}
+
+astc_3d_decode_func!(3, 3, 3);
+astc_3d_decode_func!(4, 3, 3);
+astc_3d_decode_func!(4, 4, 3);
+astc_3d_decode_func!(4, 4, 4);
+astc_3d_decode_func!(5, 4, 4);
+astc_3d_decode_func!(5, 5, 4);
+astc_3d_decode_func!(5, 5, 5);
+astc_3d_decode_func!(6, 5, 5);
+astc_3d_decode_func!(6, 6, 5);
+astc_3d_decode_func!(6, 6, 6);
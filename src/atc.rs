@@ -1,4 +1,5 @@
 #![allow(clippy::identity_op)]
+use crate::bcn::bc2::decode_bc2_alpha;
 use crate::bcn::bc3::decode_bc3_alpha;
 use crate::color::color;
 use crate::macros::block_decoder;
@@ -86,5 +87,18 @@ pub fn decode_atc_rgba8_block(data: &[u8], outbuf: &mut [u32]) {
     decode_bc3_alpha(data, outbuf, 3);
 }
 
+#[inline]
+pub fn decode_atc_rgba8_explicit_block(data: &[u8], outbuf: &mut [u32]) {
+    decode_atc_rgb4_block(&data[8..], outbuf);
+    decode_bc2_alpha(data, outbuf, 3);
+}
+
 block_decoder!("atc_rgb4", 4, 4, 8, decode_atc_rgb4_block);
 block_decoder!("atc_rgba8", 4, 4, 16, decode_atc_rgba8_block);
+block_decoder!(
+    "atc_rgba8_explicit",
+    4,
+    4,
+    16,
+    decode_atc_rgba8_explicit_block
+);
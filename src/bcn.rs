@@ -11,8 +11,8 @@ pub(crate) mod consts;
 
 pub use bc1::decode_bc1_block;
 pub use bc1::decode_bc1a_block;
-pub use bc2::decode_bc2_block;
-pub use bc3::decode_bc3_block;
+pub use bc2::{decode_bc2_block, decode_dxt2_block, decode_dxt2_premultiplied_block};
+pub use bc3::{decode_bc3_block, decode_dxt4_block, decode_dxt4_premultiplied_block};
 pub use bc4::decode_bc4_block;
 pub use bc5::decode_bc5_block;
 pub use bc6::{decode_bc6_block, decode_bc6_block_signed, decode_bc6_block_unsigned};
@@ -27,6 +27,22 @@ block_decoder!("bc5", 4, 4, 16, decode_bc5_block);
 block_decoder!("bc6_signed", 4, 4, 16, decode_bc6_block_signed);
 block_decoder!("bc6_unsigned", 4, 4, 16, decode_bc6_block_unsigned);
 block_decoder!("bc7", 4, 4, 16, decode_bc7_block);
+block_decoder!("dxt2", 4, 4, 16, decode_dxt2_block);
+block_decoder!(
+    "dxt2_premultiplied",
+    4,
+    4,
+    16,
+    decode_dxt2_premultiplied_block
+);
+block_decoder!("dxt4", 4, 4, 16, decode_dxt4_block);
+block_decoder!(
+    "dxt4_premultiplied",
+    4,
+    4,
+    16,
+    decode_dxt4_premultiplied_block
+);
 
 pub fn decode_bc6(
     data: &[u8],
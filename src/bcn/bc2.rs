@@ -1,4 +1,5 @@
 use crate::bcn::bc1::decode_bc1_block;
+use crate::color::unpremultiply;
 
 #[inline]
 pub fn decode_bc2_alpha(data: &[u8], outbuf: &mut [u32], channel: usize) {
@@ -21,3 +22,18 @@ pub fn decode_bc2_block(data: &[u8], outbuf: &mut [u32]) {
     decode_bc1_block(&data[8..], outbuf);
     decode_bc2_alpha(data, outbuf, 3);
 }
+
+/// DXT2 is BC2 with premultiplied alpha, the color is un-premultiplied after decoding.
+#[inline]
+pub fn decode_dxt2_block(data: &[u8], outbuf: &mut [u32]) {
+    decode_bc2_block(data, outbuf);
+    outbuf[0..16]
+        .iter_mut()
+        .for_each(|p| *p = unpremultiply(*p));
+}
+
+/// DXT2 block decoding that keeps the premultiplied color as it is stored.
+#[inline]
+pub fn decode_dxt2_premultiplied_block(data: &[u8], outbuf: &mut [u32]) {
+    decode_bc2_block(data, outbuf);
+}
@@ -1,4 +1,5 @@
 use crate::bcn::bc1::decode_bc1_block;
+use crate::color::unpremultiply;
 
 #[inline]
 pub fn decode_bc3_alpha(data: &[u8], outbuf: &mut [u32], channel: usize) {
@@ -35,3 +36,18 @@ pub fn decode_bc3_block(data: &[u8], outbuf: &mut [u32]) {
     decode_bc1_block(&data[8..], outbuf);
     decode_bc3_alpha(data, outbuf, 3);
 }
+
+/// DXT4 is BC3 with premultiplied alpha, the color is un-premultiplied after decoding.
+#[inline]
+pub fn decode_dxt4_block(data: &[u8], outbuf: &mut [u32]) {
+    decode_bc3_block(data, outbuf);
+    outbuf[0..16]
+        .iter_mut()
+        .for_each(|p| *p = unpremultiply(*p));
+}
+
+/// DXT4 block decoding that keeps the premultiplied color as it is stored.
+#[inline]
+pub fn decode_dxt4_premultiplied_block(data: &[u8], outbuf: &mut [u32]) {
+    decode_bc3_block(data, outbuf);
+}
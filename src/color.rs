@@ -27,6 +27,36 @@ pub const fn color(r: u8, g: u8, b: u8, a: u8) -> u32 {
     u32::from_le_bytes([b, g, r, a])
 }
 
+#[inline]
+pub fn floor(x: f32) -> f32 {
+    let mut i = x as i32;
+    if x < 0.0 && x != i as f32 {
+        i -= 1;
+    }
+    i as f32
+}
+
+#[inline]
+pub fn f32_to_u8(f: f32) -> u8 {
+    floor(f * 255.0).clamp(0.0, 255.0) as u8
+}
+
+/// Reverts the alpha premultiplication of a color, rounding to the nearest value.
+/// Fully transparent colors are returned as transparent black.
+#[inline]
+pub const fn unpremultiply(c: u32) -> u32 {
+    let [b, g, r, a] = c.to_le_bytes();
+    if a == 0 {
+        return color(0, 0, 0, 0);
+    }
+    #[inline]
+    const fn channel(v: u8, a: u8) -> u8 {
+        let v = (v as u32 * 255 + (a as u32 >> 1)) / a as u32;
+        (if v > 255 { 255 } else { v }) as u8
+    }
+    color(channel(r, a), channel(g, a), channel(b, a), a)
+}
+
 // #[cfg(target_endian = "little")]
 // #[inline]
 // pub fn alpha_mask(a: u8) -> u32 {
@@ -79,6 +109,52 @@ pub const fn rgb565_le(d: u16) -> (u8, u8, u8) {
 //     )
 // }
 
+/// The mirroring of a decoded image, applied while the blocks are written to the image.
+/// A vertical flip turns textures stored bottom-up, e.g. with a bottom-left origin, into top-down images.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Flip {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl Flip {
+    pub const NONE: Flip = Flip::new(false, false);
+    pub const HORIZONTAL: Flip = Flip::new(true, false);
+    pub const VERTICAL: Flip = Flip::new(false, true);
+    pub const BOTH: Flip = Flip::new(true, true);
+
+    pub const fn new(horizontal: bool, vertical: bool) -> Flip {
+        Flip {
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// The flip that mirrors an image by both flips, e.g. the flip asked for by a caller
+    /// applied to an image that a decoder has to flip anyway.
+    pub const fn combine(&self, flip: Flip) -> Flip {
+        Flip::new(
+            self.horizontal != flip.horizontal,
+            self.vertical != flip.vertical,
+        )
+    }
+
+    /// The index of the pixel (x, y) of a width * height image after flipping,
+    /// used by the decoders that write single pixels.
+    #[inline]
+    pub(crate) const fn pixel_index(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> usize {
+        let x = if self.horizontal { width - 1 - x } else { x };
+        let y = if self.vertical { height - 1 - y } else { y };
+        y * width + x
+    }
+}
+
 #[inline]
 pub fn copy_block_buffer(
     bx: usize,
@@ -89,19 +165,83 @@ pub fn copy_block_buffer(
     bh: usize,
     buffer: &[u32],
     image: &mut [u32],
+    flip: Flip,
 ) {
-    let x: usize = bw * bx;
-    let copy_width: usize = if bw * (bx + 1) > w { w - bw * bx } else { bw };
+    let x_0 = bw * bx;
+    let copy_width: usize = if bw * (bx + 1) > w { w - x_0 } else { bw };
+    let x = if flip.horizontal {
+        w - x_0 - copy_width
+    } else {
+        x_0
+    };
 
     let y_0 = by * bh;
     let copy_height: usize = if bh * (by + 1) > h { h - y_0 } else { bh };
     let mut buffer_offset = 0;
 
     for y in y_0..y_0 + copy_height {
+        let y = if flip.vertical { h - 1 - y } else { y };
         let image_offset = y * w + x;
-        image[image_offset..image_offset + copy_width]
-            .copy_from_slice(&buffer[buffer_offset..buffer_offset + copy_width]);
+        let row = &buffer[buffer_offset..buffer_offset + copy_width];
+        let image_row = &mut image[image_offset..image_offset + copy_width];
+        if flip.horizontal {
+            image_row
+                .iter_mut()
+                .zip(row.iter().rev())
+                .for_each(|(dst, src)| *dst = *src);
+        } else {
+            image_row.copy_from_slice(row);
+        }
 
         buffer_offset += bw;
     }
 }
+
+/// Decodes a texture of independent blocks of up to 144 pixels into an image.
+pub fn decode_blocks(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    block_width: usize,
+    block_height: usize,
+    block_bytes: usize,
+    image: &mut [u32],
+    flip: Flip,
+    decode_block: impl Fn(&[u8], &mut [u32]),
+) -> Result<(), &'static str> {
+    let num_blocks_x = width.div_ceil(block_width);
+    let num_blocks_y = height.div_ceil(block_height);
+    let mut buffer = [color(0, 0, 0, 255); 144];
+
+    if data.len() < num_blocks_x * num_blocks_y * block_bytes {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < width * height {
+        return Err("Image buffer is too small!");
+    }
+
+    if block_width * block_height > buffer.len() {
+        return Err("Block size is too big!");
+    }
+
+    let buffer = &mut buffer[..block_width * block_height];
+    data.chunks_exact(block_bytes)
+        .take(num_blocks_x * num_blocks_y)
+        .enumerate()
+        .for_each(|(i, block)| {
+            decode_block(block, buffer);
+            copy_block_buffer(
+                i % num_blocks_x,
+                i / num_blocks_x,
+                width,
+                height,
+                block_width,
+                block_height,
+                buffer,
+                image,
+                flip,
+            );
+        });
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! Parsers for texture container files.
+//!
+//! The parsers don't copy the texture data,
+//! they locate the surfaces of the textures and map their formats to the decoders of this crate.
+
+use crate::color::Flip;
+use crate::colorspace::ColorSpace;
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+pub mod astc;
+pub mod basis;
+pub mod dds;
+pub mod gnf;
+pub mod ktx;
+pub mod ktx2;
+pub mod pkm;
+pub mod pvr;
+#[cfg(feature = "alloc")]
+mod zlib;
+
+mod detect;
+pub use detect::*;
+
+/// A single mip level of a face of an array element, with the byte range of its data in the file.
+/// The depth slices of volume textures are stored one after another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Surface {
+    pub level: usize,
+    pub layer: usize,
+    pub face: usize,
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl Surface {
+    /// Returns the data of the surface from the data of the file.
+    pub fn data<'a>(&self, file_data: &'a [u8]) -> &'a [u8] {
+        &file_data[self.offset..self.offset + self.size]
+    }
+}
+
+/// The number of levels of a complete mip chain, a texture can't have more mip levels than that.
+pub(crate) const fn max_mip_levels(width: usize, height: usize, depth: usize) -> usize {
+    let size = width | height | depth | 1;
+    (usize::BITS - size.leading_zeros()) as usize
+}
+
+/// Decodes a surface of a file into an image of width * height * depth pixels
+pub fn decode_surface(
+    format: TextureFormat,
+    file_data: &[u8],
+    surface: &Surface,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_surface_flipped(
+        format,
+        file_data,
+        surface,
+        ColorSpace::Linear,
+        Flip::NONE,
+        image,
+    )
+}
+
+/// Decodes a surface of a file whose colors are stored in the given color space
+/// into an image of width * height * depth pixels, each depth slice is mirrored by flip,
+/// see [`TextureFormat::decode_with_color_space()`].
+pub fn decode_surface_flipped(
+    format: TextureFormat,
+    file_data: &[u8],
+    surface: &Surface,
+    color_space: ColorSpace,
+    flip: Flip,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    let slice_pixels = surface.width * surface.height;
+    if image.len() < slice_pixels * surface.depth {
+        return Err("Image buffer is too small!");
+    }
+    if file_data.len() < surface.offset + surface.size {
+        return Err("Not enough data to decode image!");
+    }
+    let slice_size = surface.size / surface.depth;
+    surface
+        .data(file_data)
+        .chunks_exact(slice_size)
+        .zip(image.chunks_exact_mut(slice_pixels))
+        .try_for_each(|(data, image)| {
+            format.decode_with_color_space_flipped(
+                data,
+                surface.width,
+                surface.height,
+                color_space,
+                flip,
+                image,
+            )
+        })
+}
+
+/// Decodes a surface of a file into a newly allocated image,
+/// the depth slices of volume textures are stacked vertically.
+#[cfg(feature = "alloc")]
+pub fn decode_surface_image(
+    format: TextureFormat,
+    file_data: &[u8],
+    surface: &Surface,
+) -> Result<DecodedImage, &'static str> {
+    let mut image = alloc::vec![0; surface.width * surface.height * surface.depth];
+    decode_surface(format, file_data, surface, &mut image)?;
+    Ok(DecodedImage {
+        width: surface.width,
+        height: surface.height * surface.depth,
+        image,
+    })
+}
@@ -0,0 +1,142 @@
+use crate::format::{TextureFormat, ASTC_BLOCK_SIZES};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::astc::decode_astc_3d;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+/// The magic of .astc files, stored in little endian byte order
+pub const ASTC_MAGIC: u32 = 0x5CA1AB13;
+
+/// The 3D block sizes of the ASTC specification
+const ASTC_BLOCK_SIZES_3D: [(u8, u8, u8); 10] = [
+    (3, 3, 3),
+    (4, 3, 3),
+    (4, 4, 3),
+    (4, 4, 4),
+    (5, 4, 4),
+    (5, 5, 4),
+    (5, 5, 5),
+    (6, 5, 5),
+    (6, 6, 5),
+    (6, 6, 6),
+];
+
+/// The 16 byte header of .astc files written by the ASTC encoders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AstcHeader {
+    pub block_width: u8,
+    pub block_height: u8,
+    /// 1 for 2D block sizes
+    pub block_depth: u8,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+impl AstcHeader {
+    pub const SIZE: usize = 16;
+
+    /// Parses the header of an .astc file, starting with the magic.
+    pub fn parse(data: &[u8]) -> Result<AstcHeader, &'static str> {
+        if data.len() < Self::SIZE || data[0..4] != ASTC_MAGIC.to_le_bytes() {
+            return Err("Not an ASTC file!");
+        }
+        // the sizes are 24 bit little endian values
+        let read_u24 = |offset: usize| {
+            u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], 0])
+        };
+        let header = AstcHeader {
+            block_width: data[4],
+            block_height: data[5],
+            block_depth: data[6],
+            width: read_u24(7),
+            height: read_u24(10),
+            depth: read_u24(13),
+        };
+        if !header.is_valid_block_size() {
+            return Err("Invalid ASTC block size!");
+        }
+        Ok(header)
+    }
+
+    /// Whether the block size is one of the 2D or 3D block sizes of the ASTC specification.
+    pub const fn is_valid_block_size(&self) -> bool {
+        let (bw, bh, bd) = (self.block_width, self.block_height, self.block_depth);
+        let mut i = 0;
+        if bd == 1 {
+            while i < ASTC_BLOCK_SIZES.len() {
+                if ASTC_BLOCK_SIZES[i].0 == bw && ASTC_BLOCK_SIZES[i].1 == bh {
+                    return true;
+                }
+                i += 1;
+            }
+        } else {
+            while i < ASTC_BLOCK_SIZES_3D.len() {
+                let (w, h, d) = ASTC_BLOCK_SIZES_3D[i];
+                if w == bw && h == bh && d == bd {
+                    return true;
+                }
+                i += 1;
+            }
+        }
+        false
+    }
+
+    /// The format of 2D textures, None for 3D and invalid block sizes.
+    pub const fn format(&self) -> Option<TextureFormat> {
+        if self.block_depth == 1 && self.is_valid_block_size() {
+            Some(TextureFormat::Astc(self.block_width, self.block_height))
+        } else {
+            None
+        }
+    }
+
+    /// The size of the encoded data following the header in bytes, None if it overflows.
+    pub fn data_size(&self) -> Option<usize> {
+        (self.width as usize)
+            .div_ceil(self.block_width as usize)
+            .checked_mul((self.height as usize).div_ceil(self.block_height as usize))?
+            .checked_mul((self.depth.max(1) as usize).div_ceil(self.block_depth as usize))?
+            .checked_mul(16)
+    }
+}
+
+/// Decodes an .astc file with 2D or 3D blocks.
+/// The depth slices of volume textures are stacked vertically.
+#[cfg(feature = "alloc")]
+pub fn decode_astc_file(data: &[u8]) -> Result<DecodedImage, &'static str> {
+    let header = AstcHeader::parse(data)?;
+    let (width, height, depth) = (
+        header.width as usize,
+        header.height as usize,
+        header.depth.max(1) as usize,
+    );
+    match header.data_size() {
+        Some(size) if size <= data.len() - AstcHeader::SIZE => {}
+        _ => return Err("Not enough data to decode image!"),
+    }
+    let num_pixels = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(depth))
+        .ok_or("Invalid ASTC dimensions!")?;
+    let mut image = alloc::vec![0; num_pixels];
+    decode_astc_3d(
+        &data[AstcHeader::SIZE..],
+        width,
+        height,
+        depth,
+        header.block_width as usize,
+        header.block_height as usize,
+        header.block_depth as usize,
+        &mut image,
+    )?;
+    Ok(DecodedImage {
+        width,
+        height: height * depth,
+        image,
+    })
+}
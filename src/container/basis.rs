@@ -0,0 +1,63 @@
+use core::result::Result;
+
+/// The signature at the start of .basis files, "sB"
+pub const BASIS_SIG: [u8; 2] = *b"sB";
+
+/// The texture format of the slices of a .basis file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BasisTexFormat {
+    Etc1s,
+    Uastc4x4,
+    Other(u8),
+}
+
+/// The header of a Basis Universal file.
+/// There's no transcoder in this crate, the header only identifies the file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BasisHeader {
+    pub version: u16,
+    pub header_size: u16,
+    pub data_size: u32,
+    pub total_slices: u32,
+    pub total_images: u32,
+    pub tex_format: BasisTexFormat,
+    pub flags: u16,
+    /// 0 for 2D textures, 1 for 2D arrays, 2 for cubemap arrays, 3 for video frames and 4 for volumes
+    pub tex_type: u8,
+}
+
+impl BasisHeader {
+    pub const SIZE: usize = 77;
+
+    /// Parses the header of a .basis file, starting with the signature.
+    pub fn parse(data: &[u8]) -> Result<BasisHeader, &'static str> {
+        if data.len() < Self::SIZE || data[0..2] != BASIS_SIG {
+            return Err("Not a Basis file!");
+        }
+        // the values are little endian and packed without alignment
+        let read = |offset: usize, size: usize| {
+            data[offset..offset + size]
+                .iter()
+                .rev()
+                .fold(0u32, |v, &b| v << 8 | b as u32)
+        };
+        let header = BasisHeader {
+            version: read(2, 2) as u16,
+            header_size: read(4, 2) as u16,
+            data_size: read(8, 4),
+            total_slices: read(14, 3),
+            total_images: read(17, 3),
+            tex_format: match data[20] {
+                0 => BasisTexFormat::Etc1s,
+                1 => BasisTexFormat::Uastc4x4,
+                v => BasisTexFormat::Other(v),
+            },
+            flags: read(21, 2) as u16,
+            tex_type: data[23],
+        };
+        if (header.header_size as usize) < Self::SIZE {
+            return Err("Invalid Basis header size!");
+        }
+        Ok(header)
+    }
+}
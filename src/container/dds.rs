@@ -0,0 +1,445 @@
+use super::{decode_surface_flipped, max_mip_levels, Surface};
+use crate::api::from_dxgi_format;
+use crate::color::Flip;
+use crate::colorspace::ColorSpace;
+use crate::format::TextureFormat;
+use crate::raw::{Endian, RawFormat};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+pub const DDS_MAGIC: [u8; 4] = *b"DDS ";
+
+// pixel format flags
+pub const DDPF_ALPHAPIXELS: u32 = 0x1;
+pub const DDPF_ALPHA: u32 = 0x2;
+pub const DDPF_FOURCC: u32 = 0x4;
+pub const DDPF_RGB: u32 = 0x40;
+pub const DDPF_LUMINANCE: u32 = 0x20000;
+
+// caps2 flags
+pub const DDSCAPS2_CUBEMAP: u32 = 0x200;
+pub const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xfc00;
+pub const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+// DX10 header values
+pub const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+pub const DDS_DIMENSION_TEXTURE3D: u32 = 4;
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+/// The pixel format structure of the DDS header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DdsPixelFormat {
+    pub flags: u32,
+    pub four_cc: [u8; 4],
+    pub rgb_bit_count: u32,
+    pub r_bit_mask: u32,
+    pub g_bit_mask: u32,
+    pub b_bit_mask: u32,
+    pub a_bit_mask: u32,
+}
+
+impl DdsPixelFormat {
+    /// Maps the pixel format to a texture format, DX10 files have to use [`DdsHeaderDxt10::format()`] instead.
+    pub fn format(&self) -> Option<TextureFormat> {
+        if self.flags & DDPF_FOURCC != 0 {
+            return match &self.four_cc {
+                b"DXT1" => Some(TextureFormat::Bc1a),
+                b"DXT2" => Some(TextureFormat::Dxt2),
+                b"DXT3" => Some(TextureFormat::Bc2),
+                b"DXT4" => Some(TextureFormat::Dxt4),
+                b"DXT5" => Some(TextureFormat::Bc3),
+                b"ATI1" | b"BC4U" => Some(TextureFormat::Bc4),
+                b"ATI2" | b"BC5U" => Some(TextureFormat::Bc5),
+                b"ATC " => Some(TextureFormat::AtcRgb4),
+                b"ATCI" => Some(TextureFormat::AtcRgba8),
+                b"ATCA" => Some(TextureFormat::AtcRgba8Explicit),
+                // D3DFMT values stored in the FourCC field
+                _ => {
+                    let raw = match u32::from_le_bytes(self.four_cc) {
+                        111 => RawFormat::RHalf,
+                        112 => RawFormat::RgHalf,
+                        113 => RawFormat::RgbaHalf,
+                        114 => RawFormat::RFloat,
+                        115 => RawFormat::RgFloat,
+                        116 => RawFormat::RgbaFloat,
+                        _ => return None,
+                    };
+                    Some(TextureFormat::Raw(raw, Endian::Little))
+                }
+            };
+        }
+
+        let alpha_mask = if self.flags & (DDPF_ALPHAPIXELS | DDPF_ALPHA) != 0 {
+            self.a_bit_mask
+        } else {
+            0
+        };
+        let masks = (
+            self.rgb_bit_count,
+            self.r_bit_mask,
+            self.g_bit_mask,
+            self.b_bit_mask,
+            alpha_mask,
+        );
+        let raw = if self.flags & DDPF_RGB != 0 {
+            match masks {
+                (32, 0xff0000, 0xff00, 0xff, 0xff000000) => RawFormat::Bgra32,
+                (32, 0xff0000, 0xff00, 0xff, 0) => RawFormat::Bgrx32,
+                (32, 0xff, 0xff00, 0xff0000, 0xff000000) => RawFormat::Rgba32,
+                (32, 0x3ff, 0xffc00, 0x3ff00000, 0xc0000000) => RawFormat::Rgb10A2,
+                (24, 0xff0000, 0xff00, 0xff, 0) => RawFormat::Bgr24,
+                (24, 0xff, 0xff00, 0xff0000, 0) => RawFormat::Rgb24,
+                (16, 0xf800, 0x7e0, 0x1f, 0) => RawFormat::Rgb565,
+                (16, 0x7c00, 0x3e0, 0x1f, 0x8000) => RawFormat::Argb1555,
+                (16, 0xf00, 0xf0, 0xf, 0xf000) => RawFormat::Argb4444,
+                _ => return None,
+            }
+        } else if self.flags & DDPF_LUMINANCE != 0 {
+            match masks {
+                (8, 0xff, _, _, 0) => RawFormat::L8,
+                _ => return None,
+            }
+        } else if self.flags & DDPF_ALPHA != 0 {
+            match masks {
+                (8, _, _, _, 0xff) => RawFormat::Alpha8,
+                _ => return None,
+            }
+        } else {
+            return None;
+        };
+        Some(TextureFormat::Raw(raw, Endian::Little))
+    }
+}
+
+/// The DX10 extension of the DDS header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DdsHeaderDxt10 {
+    pub dxgi_format: u32,
+    pub resource_dimension: u32,
+    pub misc_flag: u32,
+    pub array_size: u32,
+    pub misc_flags2: u32,
+}
+
+impl DdsHeaderDxt10 {
+    /// Maps the DXGI format to a texture format.
+    pub fn format(&self) -> Option<TextureFormat> {
+        from_dxgi_format(self.dxgi_format).map(|descriptor| descriptor.format)
+    }
+
+    /// The color space of the DXGI format, [`ColorSpace::Srgb`] for the `*_SRGB` formats.
+    pub fn color_space(&self) -> ColorSpace {
+        from_dxgi_format(self.dxgi_format)
+            .map_or(ColorSpace::Linear, |descriptor| descriptor.color_space())
+    }
+}
+
+/// The header of a DDS file, including the optional DX10 extension.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DdsHeader {
+    pub flags: u32,
+    pub height: u32,
+    pub width: u32,
+    pub pitch_or_linear_size: u32,
+    pub depth: u32,
+    pub mip_map_count: u32,
+    pub pixel_format: DdsPixelFormat,
+    pub caps: u32,
+    pub caps2: u32,
+    pub dxt10: Option<DdsHeaderDxt10>,
+}
+
+impl DdsHeader {
+    /// Parses the header of a DDS file, starting with the magic.
+    pub fn parse(data: &[u8]) -> Result<DdsHeader, &'static str> {
+        if data.len() < 128 || data[0..4] != DDS_MAGIC {
+            return Err("Not a DDS file!");
+        }
+        if read_u32(data, 4) != 124 {
+            return Err("Invalid DDS header size!");
+        }
+
+        let pixel_format = DdsPixelFormat {
+            flags: read_u32(data, 80),
+            four_cc: data[84..88].try_into().unwrap(),
+            rgb_bit_count: read_u32(data, 88),
+            r_bit_mask: read_u32(data, 92),
+            g_bit_mask: read_u32(data, 96),
+            b_bit_mask: read_u32(data, 100),
+            a_bit_mask: read_u32(data, 104),
+        };
+
+        let dxt10 = if pixel_format.flags & DDPF_FOURCC != 0 && &pixel_format.four_cc == b"DX10" {
+            if data.len() < 148 {
+                return Err("Not enough data for the DX10 header!");
+            }
+            Some(DdsHeaderDxt10 {
+                dxgi_format: read_u32(data, 128),
+                resource_dimension: read_u32(data, 132),
+                misc_flag: read_u32(data, 136),
+                array_size: read_u32(data, 140),
+                misc_flags2: read_u32(data, 144),
+            })
+        } else {
+            None
+        };
+
+        Ok(DdsHeader {
+            flags: read_u32(data, 8),
+            height: read_u32(data, 12),
+            width: read_u32(data, 16),
+            pitch_or_linear_size: read_u32(data, 20),
+            depth: read_u32(data, 24),
+            mip_map_count: read_u32(data, 28),
+            pixel_format,
+            caps: read_u32(data, 108),
+            caps2: read_u32(data, 112),
+            dxt10,
+        })
+    }
+
+    /// The offset of the texture data from the start of the file.
+    pub const fn data_offset(&self) -> usize {
+        if self.dxt10.is_some() {
+            148
+        } else {
+            128
+        }
+    }
+
+    /// The format of the texture, None if there's no decoder for it.
+    pub fn format(&self) -> Option<TextureFormat> {
+        match &self.dxt10 {
+            Some(dxt10) => dxt10.format(),
+            None => self.pixel_format.format(),
+        }
+    }
+
+    /// The color space of the texture, only the DX10 extension can declare sRGB formats.
+    pub fn color_space(&self) -> ColorSpace {
+        self.dxt10
+            .map_or(ColorSpace::Linear, |dxt10| dxt10.color_space())
+    }
+
+    pub fn mip_levels(&self) -> usize {
+        self.mip_map_count.max(1) as usize
+    }
+
+    /// The number of array elements, each consisting of [`DdsHeader::faces()`] faces.
+    pub fn array_size(&self) -> usize {
+        match &self.dxt10 {
+            Some(dxt10) => dxt10.array_size.max(1) as usize,
+            None => 1,
+        }
+    }
+
+    /// The number of faces per array element, 6 for complete cubemaps and 1 for other textures.
+    pub fn faces(&self) -> usize {
+        match &self.dxt10 {
+            Some(dxt10) if dxt10.misc_flag & DDS_RESOURCE_MISC_TEXTURECUBE != 0 => 6,
+            Some(_) => 1,
+            None if self.caps2 & DDSCAPS2_CUBEMAP != 0 => {
+                (self.caps2 & DDSCAPS2_CUBEMAP_ALLFACES).count_ones().max(1) as usize
+            }
+            None => 1,
+        }
+    }
+
+    /// The depth of volume textures, 1 for other textures.
+    pub fn depth(&self) -> usize {
+        let is_volume = match &self.dxt10 {
+            Some(dxt10) => dxt10.resource_dimension == DDS_DIMENSION_TEXTURE3D,
+            None => self.caps2 & DDSCAPS2_VOLUME != 0,
+        };
+        if is_volume {
+            self.depth.max(1) as usize
+        } else {
+            1
+        }
+    }
+}
+
+/// A parsed DDS file referencing the file data.
+#[derive(Clone, Copy, Debug)]
+pub struct DdsFile<'a> {
+    pub header: DdsHeader,
+    pub format: TextureFormat,
+    data: &'a [u8],
+}
+
+impl<'a> DdsFile<'a> {
+    /// Parses a DDS file and checks that it contains all of its surfaces.
+    pub fn parse(data: &'a [u8]) -> Result<DdsFile<'a>, &'static str> {
+        let header = DdsHeader::parse(data)?;
+        let format = header.format().ok_or("Unsupported DDS format!")?;
+        let max_levels = max_mip_levels(
+            header.width as usize,
+            header.height as usize,
+            header.depth(),
+        );
+        if header.mip_levels() > max_levels {
+            return Err("Invalid DDS mip map count!");
+        }
+        let file = DdsFile {
+            header,
+            format,
+            data,
+        };
+        // the size of all surfaces also bounds the array size by the available data
+        let size = (0..header.mip_levels())
+            .try_fold(0usize, |size, level| {
+                size.checked_add(file.checked_level_size(level)?)
+            })
+            .and_then(|size| size.checked_mul(header.faces()))
+            .and_then(|size| size.checked_mul(header.array_size()))
+            .and_then(|size| size.checked_add(header.data_offset()));
+        match size {
+            Some(size) if size <= data.len() => Ok(file),
+            _ => Err("Not enough data to decode image!"),
+        }
+    }
+
+    /// Iterates over all surfaces in the order they are stored:
+    /// array elements, then faces, then mip levels.
+    pub fn surfaces(&self) -> DdsSurfaces<'_> {
+        DdsSurfaces {
+            file: self,
+            layer: 0,
+            face: 0,
+            level: 0,
+            offset: self.header.data_offset(),
+        }
+    }
+
+    /// Returns the surface of the given mip level, array element and face.
+    pub fn surface(&self, level: usize, layer: usize, face: usize) -> Option<Surface> {
+        if level >= self.header.mip_levels()
+            || layer >= self.header.array_size()
+            || face >= self.header.faces()
+        {
+            return None;
+        }
+        let chain_size: usize = (0..self.header.mip_levels())
+            .map(|level| self.level_size(level))
+            .sum();
+        let offset = self.header.data_offset()
+            + (layer * self.header.faces() + face) * chain_size
+            + (0..level)
+                .map(|level| self.level_size(level))
+                .sum::<usize>();
+        Some(self.make_surface(level, layer, face, offset))
+    }
+
+    /// Returns the data of a surface.
+    pub fn surface_data(&self, surface: &Surface) -> &'a [u8] {
+        surface.data(self.data)
+    }
+
+    /// Decodes a surface into an image of width * height * depth pixels,
+    /// sRGB textures are decoded in the [`DdsHeader::color_space()`].
+    pub fn decode_surface(&self, surface: &Surface, image: &mut [u32]) -> Result<(), &'static str> {
+        decode_surface_flipped(
+            self.format,
+            self.data,
+            surface,
+            self.header.color_space(),
+            Flip::NONE,
+            image,
+        )
+    }
+
+    fn level_dimensions(&self, level: usize) -> (usize, usize, usize) {
+        (
+            (self.header.width as usize >> level).max(1),
+            (self.header.height as usize >> level).max(1),
+            (self.header.depth() >> level).max(1),
+        )
+    }
+
+    fn level_size(&self, level: usize) -> usize {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format.data_size(width, height) * depth
+    }
+
+    fn checked_level_size(&self, level: usize) -> Option<usize> {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format
+            .checked_data_size(width, height)?
+            .checked_mul(depth)
+    }
+
+    fn make_surface(&self, level: usize, layer: usize, face: usize, offset: usize) -> Surface {
+        let (width, height, depth) = self.level_dimensions(level);
+        Surface {
+            level,
+            layer,
+            face,
+            width,
+            height,
+            depth,
+            offset,
+            size: self.level_size(level),
+        }
+    }
+}
+
+/// Iterator over the surfaces of a DDS file, see [`DdsFile::surfaces()`].
+pub struct DdsSurfaces<'a> {
+    file: &'a DdsFile<'a>,
+    layer: usize,
+    face: usize,
+    level: usize,
+    offset: usize,
+}
+
+impl Iterator for DdsSurfaces<'_> {
+    type Item = Surface;
+
+    fn next(&mut self) -> Option<Surface> {
+        let header = &self.file.header;
+        if self.layer >= header.array_size() {
+            return None;
+        }
+        let surface = self
+            .file
+            .make_surface(self.level, self.layer, self.face, self.offset);
+        self.offset += surface.size;
+        self.level += 1;
+        if self.level == header.mip_levels() {
+            self.level = 0;
+            self.face += 1;
+            if self.face == header.faces() {
+                self.face = 0;
+                self.layer += 1;
+            }
+        }
+        Some(surface)
+    }
+}
+
+/// Decodes a mip level of a DDS file.
+/// The layer indexes the faces of all array elements, e.g. 7 is the 2nd face of the 2nd cubemap.
+/// The depth slices of volume textures are stacked vertically.
+#[cfg(feature = "alloc")]
+pub fn decode_dds(data: &[u8], level: usize, layer: usize) -> Result<DecodedImage, &'static str> {
+    let file = DdsFile::parse(data)?;
+    let faces = file.header.faces();
+    let surface = file
+        .surface(level, layer / faces, layer % faces)
+        .ok_or("The surface doesn't exist!")?;
+    let mut image = alloc::vec![0; surface.width * surface.height * surface.depth];
+    file.decode_surface(&surface, &mut image)?;
+    Ok(DecodedImage {
+        width: surface.width,
+        height: surface.height * surface.depth,
+        image,
+    })
+}
@@ -0,0 +1,102 @@
+use super::astc::AstcHeader;
+use super::basis::BasisHeader;
+use super::dds::DdsHeader;
+use super::gnf::GnfHeader;
+use super::ktx::KtxHeader;
+use super::ktx2::Ktx2Header;
+use super::pkm::PkmHeader;
+use super::pvr::PvrHeader;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::crnlib::{CrnFormat, CrnTextureInfo};
+#[cfg(feature = "alloc")]
+use crate::crunch::crn_consts::CRNSIG_VALUE;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+#[cfg(feature = "alloc")]
+use core::result::Result;
+
+/// A container file recognized by [`detect()`], with its parsed header.
+#[derive(Clone, Copy, Debug)]
+pub enum Container {
+    Dds(DdsHeader),
+    Ktx(KtxHeader),
+    Ktx2(Ktx2Header),
+    Pvr(PvrHeader),
+    Astc(AstcHeader),
+    Pkm(PkmHeader),
+    Basis(BasisHeader),
+    Gnf(GnfHeader),
+    #[cfg(feature = "alloc")]
+    Crunch(CrnTextureInfo),
+}
+
+/// Detects the container of a file from its magic bytes and parses its header.
+/// Returns None if the data doesn't start with a known and valid header.
+pub fn detect(data: &[u8]) -> Option<Container> {
+    let magic = data.get(0..4)?;
+    let container = match magic {
+        b"DDS " => Container::Dds(DdsHeader::parse(data).ok()?),
+        [0xAB, 0x4B, 0x54, 0x58] if data.get(4..7) == Some(b" 11") => {
+            Container::Ktx(KtxHeader::parse(data).ok()?)
+        }
+        [0xAB, 0x4B, 0x54, 0x58] if data.get(4..7) == Some(b" 20") => {
+            Container::Ktx2(Ktx2Header::parse(data).ok()?)
+        }
+        b"PVR\x03" => Container::Pvr(PvrHeader::parse(data).ok()?),
+        [0x13, 0xAB, 0xA1, 0x5C] => Container::Astc(AstcHeader::parse(data).ok()?),
+        b"PKM " => Container::Pkm(PkmHeader::parse(data).ok()?),
+        b"GNF " => Container::Gnf(GnfHeader::parse(data).ok()?),
+        [b's', b'B', ..] => Container::Basis(BasisHeader::parse(data).ok()?),
+        #[cfg(feature = "alloc")]
+        [a, b, ..] if u16::from_be_bytes([*a, *b]) == CRNSIG_VALUE => {
+            let mut info = CrnTextureInfo::default();
+            if !info.crnd_get_texture_info(data, data.len() as u32) {
+                return None;
+            }
+            Container::Crunch(info)
+        }
+        // the tag of legacy PVR v2 headers follows the header fields
+        _ => Container::Pvr(PvrHeader::parse(data).ok()?),
+    };
+    Some(container)
+}
+
+/// Detects the container of a file and decodes its first image,
+/// the first mip level of the first face of the first array element of the first texture.
+/// Basis Universal files are detected, but can't be decoded.
+///
+/// Crunch files with ETC formats are decoded with Unity's crunch,
+/// the other formats with the original crunch, use [`crate::decode_unity_crunch()`] for DXT files of Unity's crunch.
+#[cfg(feature = "alloc")]
+pub fn decode_any(data: &[u8]) -> Result<DecodedImage, &'static str> {
+    match detect(data).ok_or("Unknown texture container!")? {
+        Container::Dds(_) => super::dds::decode_dds(data, 0, 0),
+        Container::Ktx(_) => super::ktx::decode_ktx(data, 0, 0),
+        Container::Ktx2(_) => Ok(super::ktx2::decode_ktx2(data, 0, 0)?),
+        Container::Pvr(_) => super::pvr::decode_pvr(data, 0, 0),
+        Container::Astc(_) => super::astc::decode_astc_file(data),
+        Container::Pkm(_) => super::pkm::decode_pkm(data),
+        Container::Basis(_) => Err("Basis Universal files aren't supported!"),
+        Container::Gnf(_) => super::gnf::decode_gnf(data, 0, 0, 0),
+        Container::Crunch(info) => {
+            let (width, height) = (info.width as usize, info.height as usize);
+            let mut image = alloc::vec![0; width * height];
+            match info.format {
+                CrnFormat::Etc1
+                | CrnFormat::Etc2
+                | CrnFormat::Etc2a
+                | CrnFormat::Etc1s
+                | CrnFormat::Etc2as => crate::decode_unity_crunch(data, width, height, &mut image)?,
+                _ => crate::decode_crunch(data, width, height, &mut image)?,
+            }
+            Ok(DecodedImage {
+                width,
+                height,
+                image,
+            })
+        }
+    }
+}
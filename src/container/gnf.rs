@@ -0,0 +1,273 @@
+use crate::format::{FormatDescriptor, NumericFormat, TextureFormat};
+use crate::raw::{Endian, RawFormat};
+use crate::swizzle::gnm::{GnmSurface, GnmTileMode};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+pub const GNF_MAGIC: [u8; 4] = *b"GNF ";
+
+/// The header of a GNF file, followed by the texture descriptors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GnfHeader {
+    /// The size of the file up to the texture data, excluding the magic and this field
+    pub contents_size: u32,
+    pub version: u8,
+    /// The number of textures
+    pub count: u8,
+    /// The log2 of the alignment of the texture data
+    pub alignment: u8,
+    pub stream_size: u32,
+}
+
+impl GnfHeader {
+    pub const SIZE: usize = 16;
+
+    /// Parses the header of a GNF file, starting with the magic.
+    pub fn parse(data: &[u8]) -> Result<GnfHeader, &'static str> {
+        if data.len() < Self::SIZE || data[0..4] != GNF_MAGIC {
+            return Err("Not a GNF file!");
+        }
+        let read = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        Ok(GnfHeader {
+            contents_size: read(4),
+            version: data[8],
+            count: data[9],
+            alignment: data[10],
+            stream_size: read(12),
+        })
+    }
+
+    /// The offset of the texture data in the file.
+    pub const fn data_offset(&self) -> usize {
+        8 + self.contents_size as usize
+    }
+}
+
+/// The 32 byte texture descriptor (T#) of a GCN texture,
+/// GNF files store the offset of the data in the base address and the size of the data in the last register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GnmTexture {
+    pub regs: [u32; 8],
+}
+
+impl GnmTexture {
+    pub const SIZE: usize = 32;
+
+    pub fn parse(data: &[u8]) -> Result<GnmTexture, &'static str> {
+        if data.len() < Self::SIZE {
+            return Err("Not enough data for a texture descriptor!");
+        }
+        let mut regs = [0; 8];
+        regs.iter_mut()
+            .zip(data.chunks_exact(4))
+            .for_each(|(reg, v)| *reg = u32::from_le_bytes(v.try_into().unwrap()));
+        Ok(GnmTexture { regs })
+    }
+
+    const fn bits(&self, reg: usize, offset: u32, count: u32) -> u32 {
+        (self.regs[reg] >> offset) & ((1 << count) - 1)
+    }
+
+    /// The offset of the texture data relative to the data of the file.
+    pub const fn data_offset(&self) -> usize {
+        (self.regs[0] as usize) << 8
+    }
+
+    pub const fn data_size(&self) -> usize {
+        self.regs[7] as usize
+    }
+
+    /// The GCN `IMG_DATA_FORMAT`.
+    pub const fn data_format(&self) -> u32 {
+        self.bits(1, 20, 6)
+    }
+
+    /// The GCN `IMG_NUM_FORMAT`.
+    pub const fn num_format(&self) -> u32 {
+        self.bits(1, 26, 4)
+    }
+
+    pub const fn width(&self) -> usize {
+        self.bits(2, 0, 14) as usize + 1
+    }
+
+    pub const fn height(&self) -> usize {
+        self.bits(2, 14, 14) as usize + 1
+    }
+
+    /// The channels the x, y, z and w channels of the shader read, 4-7 select x-w.
+    pub const fn dst_sel(&self) -> [u32; 4] {
+        [
+            self.bits(3, 0, 3),
+            self.bits(3, 3, 3),
+            self.bits(3, 6, 3),
+            self.bits(3, 9, 3),
+        ]
+    }
+
+    pub const fn base_level(&self) -> u32 {
+        self.bits(3, 12, 4)
+    }
+
+    pub const fn last_level(&self) -> u32 {
+        self.bits(3, 16, 4)
+    }
+
+    pub const fn tiling_index(&self) -> u8 {
+        self.bits(3, 20, 5) as u8
+    }
+
+    pub const fn pow2_pad(&self) -> bool {
+        self.bits(3, 25, 1) != 0
+    }
+
+    /// The `SQ_RSRC_IMG` type, e.g. 9 for 2D and 13 for 2D array textures.
+    pub const fn texture_type(&self) -> u32 {
+        self.bits(3, 28, 4)
+    }
+
+    pub const fn depth(&self) -> usize {
+        self.bits(4, 0, 13) as usize + 1
+    }
+
+    /// The pitch of the base level in pixels.
+    pub const fn pitch(&self) -> usize {
+        self.bits(4, 13, 14) as usize + 1
+    }
+
+    pub const fn base_array(&self) -> usize {
+        self.bits(5, 0, 13) as usize
+    }
+
+    pub const fn last_array(&self) -> usize {
+        self.bits(5, 13, 13) as usize
+    }
+
+    /// The format of the texture, None if there's no decoder for it.
+    pub fn format(&self) -> Option<FormatDescriptor> {
+        let numeric_format = match self.num_format() {
+            0 => NumericFormat::Unorm,
+            1 => NumericFormat::Snorm,
+            4 => NumericFormat::Uint,
+            5 => NumericFormat::Sint,
+            7 => NumericFormat::Sfloat,
+            9 => NumericFormat::Srgb,
+            _ => return None,
+        };
+        let float = matches!(numeric_format, NumericFormat::Sfloat);
+        let raw = |format| TextureFormat::Raw(format, Endian::Little);
+        let format = match self.data_format() {
+            1 => raw(RawFormat::R8),
+            2 if float => raw(RawFormat::RHalf),
+            2 => raw(RawFormat::R16),
+            3 => raw(RawFormat::Rg16),
+            4 if float => raw(RawFormat::RFloat),
+            5 if float => raw(RawFormat::RgHalf),
+            9 => raw(RawFormat::Rgb10A2),
+            // the shader reads blue from the first byte for BGRA data
+            10 if self.dst_sel()[0] == 6 => raw(RawFormat::Bgra32),
+            10 => raw(RawFormat::Rgba32),
+            11 if float => raw(RawFormat::RgFloat),
+            12 if float => raw(RawFormat::RgbaHalf),
+            14 if float => raw(RawFormat::RgbaFloat),
+            35 => TextureFormat::Bc1,
+            36 => TextureFormat::Bc2,
+            37 => TextureFormat::Bc3,
+            38 => TextureFormat::Bc4,
+            39 => TextureFormat::Bc5,
+            40 if matches!(numeric_format, NumericFormat::Snorm) => {
+                return Some(FormatDescriptor::new(
+                    TextureFormat::Bc6Signed,
+                    NumericFormat::Sfloat,
+                ))
+            }
+            40 => {
+                return Some(FormatDescriptor::new(
+                    TextureFormat::Bc6Unsigned,
+                    NumericFormat::Ufloat,
+                ))
+            }
+            41 => TextureFormat::Bc7,
+            _ => return None,
+        };
+        Some(FormatDescriptor::new(format, numeric_format))
+    }
+
+    /// The tiled surface of the texture, which untiles and decodes its mip levels.
+    pub fn surface(&self) -> Result<GnmSurface, &'static str> {
+        let format = self
+            .format()
+            .ok_or("The GNM data format isn't supported!")?
+            .format;
+        let tile_mode = GnmTileMode::from_tiling_index(self.tiling_index())
+            .ok_or("The GNM tile mode isn't supported!")?;
+        Ok(GnmSurface {
+            format,
+            tile_mode,
+            width: self.width(),
+            height: self.height(),
+            pitch: self.pitch(),
+            array_size: self.last_array().saturating_sub(self.base_array()) + 1,
+            pow2_pad: self.pow2_pad(),
+        })
+    }
+}
+
+/// A parsed GNF file referencing the file data.
+#[derive(Clone, Copy, Debug)]
+pub struct GnfFile<'a> {
+    pub header: GnfHeader,
+    data: &'a [u8],
+}
+
+impl<'a> GnfFile<'a> {
+    /// Parses a GNF file and checks that it contains its texture descriptors.
+    pub fn parse(data: &'a [u8]) -> Result<GnfFile<'a>, &'static str> {
+        let header = GnfHeader::parse(data)?;
+        if data.len() < GnfHeader::SIZE + header.count as usize * GnmTexture::SIZE
+            || data.len() < header.data_offset()
+        {
+            return Err("Not enough data for the GNF header!");
+        }
+        Ok(GnfFile { header, data })
+    }
+
+    /// Returns the descriptor of a texture.
+    pub fn texture(&self, index: usize) -> Option<GnmTexture> {
+        if index >= self.header.count as usize {
+            return None;
+        }
+        GnmTexture::parse(&self.data[GnfHeader::SIZE + index * GnmTexture::SIZE..]).ok()
+    }
+
+    /// Returns the tiled data of a texture.
+    pub fn texture_data(&self, index: usize) -> Option<&'a [u8]> {
+        let texture = self.texture(index)?;
+        let start = self.header.data_offset() + texture.data_offset();
+        self.data.get(start..start + texture.data_size())
+    }
+}
+
+/// Decodes an array element of a mip level of a texture of a GNF file.
+#[cfg(feature = "alloc")]
+pub fn decode_gnf(
+    data: &[u8],
+    index: usize,
+    level: u32,
+    layer: usize,
+) -> Result<DecodedImage, &'static str> {
+    let file = GnfFile::parse(data)?;
+    let texture = file.texture(index).ok_or("The texture doesn't exist!")?;
+    let surface = texture.surface()?;
+    if level > texture.last_level().saturating_sub(texture.base_level())
+        || layer >= surface.array_size
+    {
+        return Err("The surface doesn't exist!");
+    }
+    let data = file
+        .texture_data(index)
+        .ok_or("Not enough data for the texture!")?;
+    surface.decode_mip(data, level, layer)
+}
@@ -0,0 +1,432 @@
+use super::{decode_surface_flipped, max_mip_levels, Surface};
+use crate::api::from_gl_internal_format;
+use crate::color::Flip;
+use crate::colorspace::ColorSpace;
+use crate::format::TextureFormat;
+use crate::raw::{Endian, RawFormat};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+pub const KTX_MAGIC: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+pub const KTX_ENDIANNESS: u32 = 0x04030201;
+
+#[inline]
+const fn align4(v: usize) -> usize {
+    (v + 3) & !3
+}
+
+/// Maps an OpenGL internal format to a texture format.
+/// Unsized internal formats are resolved with the format and type.
+pub fn gl_format_to_texture_format(
+    gl_internal_format: u32,
+    gl_format: u32,
+    gl_type: u32,
+    endian: Endian,
+) -> Option<TextureFormat> {
+    let raw = match from_gl_internal_format(gl_internal_format).map(|d| d.format) {
+        Some(TextureFormat::Raw(raw, _)) => raw,
+        Some(format) => return Some(format),
+        // unsized internal formats with GL_UNSIGNED_BYTE or packed types
+        None => match (gl_internal_format, gl_format, gl_type) {
+            (0x1908, 0x1908, 0x1401) => RawFormat::Rgba32,
+            (0x1908, 0x80E1, 0x1401) | (0x80E1, 0x80E1, 0x1401) => RawFormat::Bgra32,
+            (0x1907, 0x1907, 0x1401) => RawFormat::Rgb24,
+            (0x1906, 0x1906, 0x1401) => RawFormat::Alpha8,
+            (0x1909, 0x1909, 0x1401) => RawFormat::L8,
+            (0x1907, 0x1907, 0x8363) => RawFormat::Rgb565,
+            (0x1908, 0x1908, 0x8033) => RawFormat::Rgba4444,
+            (0x1908, 0x1908, 0x8034) => RawFormat::Rgba5551,
+            _ => return None,
+        },
+    };
+    Some(TextureFormat::Raw(raw, endian))
+}
+
+/// The header of a KTX 1 file, the values are already converted to the native byte order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KtxHeader {
+    /// true if the file was written in big endian byte order
+    pub swapped: bool,
+    pub gl_type: u32,
+    pub gl_type_size: u32,
+    pub gl_format: u32,
+    pub gl_internal_format: u32,
+    pub gl_base_internal_format: u32,
+    pub pixel_width: u32,
+    pub pixel_height: u32,
+    pub pixel_depth: u32,
+    pub number_of_array_elements: u32,
+    pub number_of_faces: u32,
+    pub number_of_mipmap_levels: u32,
+    pub bytes_of_key_value_data: u32,
+}
+
+impl KtxHeader {
+    pub const SIZE: usize = 64;
+
+    /// Parses the header of a KTX file, starting with the identifier.
+    pub fn parse(data: &[u8]) -> Result<KtxHeader, &'static str> {
+        if data.len() < Self::SIZE || data[0..12] != KTX_MAGIC {
+            return Err("Not a KTX file!");
+        }
+        let swapped = match u32::from_le_bytes(data[12..16].try_into().unwrap()) {
+            KTX_ENDIANNESS => false,
+            v if v.swap_bytes() == KTX_ENDIANNESS => true,
+            _ => return Err("Invalid KTX endianness!"),
+        };
+        let read = |i: usize| read_u32(data, 16 + i * 4, swapped);
+        Ok(KtxHeader {
+            swapped,
+            gl_type: read(0),
+            gl_type_size: read(1),
+            gl_format: read(2),
+            gl_internal_format: read(3),
+            gl_base_internal_format: read(4),
+            pixel_width: read(5),
+            pixel_height: read(6),
+            pixel_depth: read(7),
+            number_of_array_elements: read(8),
+            number_of_faces: read(9),
+            number_of_mipmap_levels: read(10),
+            bytes_of_key_value_data: read(11),
+        })
+    }
+
+    /// The byte order of the multi-byte values in the file.
+    pub const fn endian(&self) -> Endian {
+        if self.swapped {
+            Endian::Big
+        } else {
+            Endian::Little
+        }
+    }
+
+    /// The format of the texture, None if there's no decoder for it.
+    pub fn format(&self) -> Option<TextureFormat> {
+        gl_format_to_texture_format(
+            self.gl_internal_format,
+            self.gl_format,
+            self.gl_type,
+            self.endian(),
+        )
+    }
+
+    /// The number of mip levels, clamped to the levels of a complete mip chain.
+    pub fn mip_levels(&self) -> usize {
+        let max_levels = max_mip_levels(
+            self.pixel_width as usize,
+            self.pixel_height as usize,
+            self.depth(),
+        );
+        (self.number_of_mipmap_levels.max(1) as usize).min(max_levels)
+    }
+
+    /// The number of array elements, 1 for textures that aren't arrays.
+    pub fn array_size(&self) -> usize {
+        self.number_of_array_elements.max(1) as usize
+    }
+
+    /// The number of faces per array element, 6 for cubemaps and 1 for other textures.
+    pub fn faces(&self) -> usize {
+        self.number_of_faces.max(1) as usize
+    }
+
+    /// The depth of volume textures, 1 for other textures.
+    pub fn depth(&self) -> usize {
+        self.pixel_depth.max(1) as usize
+    }
+
+    /// The offset of the first mip level from the start of the file.
+    pub const fn data_offset(&self) -> usize {
+        Self::SIZE + self.bytes_of_key_value_data as usize
+    }
+
+    // non-array cubemaps store the size of a single face, and pad each face to 4 bytes
+    const fn is_non_array_cubemap(&self) -> bool {
+        self.number_of_array_elements == 0 && self.number_of_faces == 6
+    }
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize, swapped: bool) -> u32 {
+    let v = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    if swapped {
+        v.swap_bytes()
+    } else {
+        v
+    }
+}
+
+/// A parsed KTX 1 file referencing the file data.
+#[derive(Clone, Copy, Debug)]
+pub struct KtxFile<'a> {
+    pub header: KtxHeader,
+    pub format: TextureFormat,
+    data: &'a [u8],
+}
+
+impl<'a> KtxFile<'a> {
+    /// Parses a KTX 1 file and checks that it contains all of its surfaces.
+    pub fn parse(data: &'a [u8]) -> Result<KtxFile<'a>, &'static str> {
+        let header = KtxHeader::parse(data)?;
+        let format = header.format().ok_or("Unsupported KTX format!")?;
+        if data.len() < header.data_offset() {
+            return Err("Not enough data for the KTX key/value data!");
+        }
+        let file = KtxFile {
+            header,
+            format,
+            data,
+        };
+        let mut offset = header.data_offset();
+        for level in 0..header.mip_levels() {
+            offset = file.next_level_offset(level, offset)?;
+        }
+        Ok(file)
+    }
+
+    /// Iterates over the key/value pairs, the key doesn't include its terminating 0.
+    pub fn key_values(&self) -> KtxKeyValues<'a> {
+        KtxKeyValues::new(
+            &self.data[KtxHeader::SIZE..self.header.data_offset()],
+            self.header.swapped,
+        )
+    }
+
+    /// The color space of the texture, [`ColorSpace::Srgb`] for the sRGB internal formats.
+    pub fn color_space(&self) -> ColorSpace {
+        from_gl_internal_format(self.header.gl_internal_format)
+            .map_or(ColorSpace::Linear, |descriptor| descriptor.color_space())
+    }
+
+    /// The flip that turns the stored image into a top-down, left to right image,
+    /// from the `KTXorientation` key, e.g. `S=r,T=u` for images stored bottom-up.
+    pub fn orientation(&self) -> Flip {
+        self.key_values()
+            .find(|(key, _)| *key == b"KTXorientation")
+            .map_or(Flip::NONE, |(_, value)| {
+                let axis = |name: &[u8]| {
+                    value
+                        .split(|&c| c == b',' || c == 0)
+                        .find_map(|item| item.strip_prefix(name)?.first().copied())
+                };
+                Flip::new(axis(b"S=") == Some(b'l'), axis(b"T=") == Some(b'u'))
+            })
+    }
+
+    /// Iterates over all surfaces in the order they are stored:
+    /// mip levels, then array elements, then faces.
+    pub fn surfaces(&self) -> KtxSurfaces<'_> {
+        KtxSurfaces {
+            file: self,
+            level: 0,
+            layer: 0,
+            face: 0,
+            level_offset: self.header.data_offset(),
+        }
+    }
+
+    /// Returns the surface of the given mip level, array element and face.
+    pub fn surface(&self, level: usize, layer: usize, face: usize) -> Option<Surface> {
+        if level >= self.header.mip_levels()
+            || layer >= self.header.array_size()
+            || face >= self.header.faces()
+        {
+            return None;
+        }
+        let mut offset = self.header.data_offset();
+        for level in 0..level {
+            offset = self.next_level_offset(level, offset).ok()?;
+        }
+        Some(self.make_surface(level, layer, face, offset))
+    }
+
+    /// Returns the data of a surface.
+    pub fn surface_data(&self, surface: &Surface) -> &'a [u8] {
+        surface.data(self.data)
+    }
+
+    /// Decodes a surface into an image of width * height * depth pixels,
+    /// the slices are mirrored to top-down, left to right images by the [`KtxFile::orientation()`]
+    /// and sRGB textures are decoded in the [`KtxFile::color_space()`].
+    pub fn decode_surface(&self, surface: &Surface, image: &mut [u32]) -> Result<(), &'static str> {
+        decode_surface_flipped(
+            self.format,
+            self.data,
+            surface,
+            self.color_space(),
+            self.orientation(),
+            image,
+        )
+    }
+
+    fn level_dimensions(&self, level: usize) -> (usize, usize, usize) {
+        (
+            (self.header.pixel_width as usize >> level).max(1),
+            (self.header.pixel_height as usize >> level).max(1),
+            (self.header.depth() >> level).max(1),
+        )
+    }
+
+    fn face_size(&self, level: usize) -> usize {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format.data_size(width, height) * depth
+    }
+
+    fn checked_face_size(&self, level: usize) -> Option<usize> {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format
+            .checked_data_size(width, height)?
+            .checked_mul(depth)
+    }
+
+    // validates the imageSize of a level and returns the offset of the next level
+    fn next_level_offset(&self, level: usize, offset: usize) -> Result<usize, &'static str> {
+        if self.data.len() < offset + 4 {
+            return Err("Not enough data to decode image!");
+        }
+        let image_size = read_u32(self.data, offset, self.header.swapped) as usize;
+        let face_size = self
+            .checked_face_size(level)
+            .ok_or("Invalid KTX image size!")?;
+        let (stored_size, padded_size) = if self.header.is_non_array_cubemap() {
+            if image_size < face_size {
+                return Err("Invalid KTX image size!");
+            }
+            (align4(image_size) * 5 + image_size, align4(image_size) * 6)
+        } else {
+            let level_size = face_size
+                .checked_mul(self.header.array_size())
+                .and_then(|size| size.checked_mul(self.header.faces()));
+            match level_size {
+                Some(level_size) if image_size >= level_size => {}
+                _ => return Err("Invalid KTX image size!"),
+            }
+            (image_size, align4(image_size))
+        };
+        // the padding after the last level may be missing
+        if self.data.len() < offset + 4 + stored_size {
+            return Err("Not enough data to decode image!");
+        }
+        let next_offset = offset + 4 + padded_size;
+        Ok(next_offset)
+    }
+
+    fn make_surface(
+        &self,
+        level: usize,
+        layer: usize,
+        face: usize,
+        level_offset: usize,
+    ) -> Surface {
+        let (width, height, depth) = self.level_dimensions(level);
+        let size = self.face_size(level);
+        let index = layer * self.header.faces() + face;
+        let offset = if self.header.is_non_array_cubemap() {
+            let image_size = read_u32(self.data, level_offset, self.header.swapped) as usize;
+            level_offset + 4 + index * align4(image_size)
+        } else {
+            level_offset + 4 + index * size
+        };
+        Surface {
+            level,
+            layer,
+            face,
+            width,
+            height,
+            depth,
+            offset,
+            size,
+        }
+    }
+}
+
+/// Iterator over the key/value pairs of a KTX file, see [`KtxFile::key_values()`].
+/// The KTX 2 key/value data uses the same layout.
+pub struct KtxKeyValues<'a> {
+    data: &'a [u8],
+    swapped: bool,
+}
+
+impl<'a> KtxKeyValues<'a> {
+    pub(crate) const fn new(data: &'a [u8], swapped: bool) -> KtxKeyValues<'a> {
+        KtxKeyValues { data, swapped }
+    }
+}
+
+impl<'a> Iterator for KtxKeyValues<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 4 {
+            return None;
+        }
+        let size = read_u32(self.data, 0, self.swapped) as usize;
+        let pair = self.data.get(4..4 + size)?;
+        self.data = self.data.get(align4(4 + size)..).unwrap_or(&[]);
+        let key_end = pair.iter().position(|&c| c == 0)?;
+        Some((&pair[..key_end], &pair[key_end + 1..]))
+    }
+}
+
+/// Iterator over the surfaces of a KTX file, see [`KtxFile::surfaces()`].
+pub struct KtxSurfaces<'a> {
+    file: &'a KtxFile<'a>,
+    level: usize,
+    layer: usize,
+    face: usize,
+    level_offset: usize,
+}
+
+impl Iterator for KtxSurfaces<'_> {
+    type Item = Surface;
+
+    fn next(&mut self) -> Option<Surface> {
+        let header = &self.file.header;
+        if self.level >= header.mip_levels() {
+            return None;
+        }
+        let surface = self
+            .file
+            .make_surface(self.level, self.layer, self.face, self.level_offset);
+        self.face += 1;
+        if self.face == header.faces() {
+            self.face = 0;
+            self.layer += 1;
+            if self.layer == header.array_size() {
+                self.layer = 0;
+                self.level_offset = self
+                    .file
+                    .next_level_offset(self.level, self.level_offset)
+                    .ok()?;
+                self.level += 1;
+            }
+        }
+        Some(surface)
+    }
+}
+
+/// Decodes a mip level of a KTX 1 file.
+/// The layer indexes the faces of all array elements, e.g. 7 is the 2nd face of the 2nd cubemap.
+/// The depth slices of volume textures are stacked vertically.
+#[cfg(feature = "alloc")]
+pub fn decode_ktx(data: &[u8], level: usize, layer: usize) -> Result<DecodedImage, &'static str> {
+    let file = KtxFile::parse(data)?;
+    let faces = file.header.faces();
+    let surface = file
+        .surface(level, layer / faces, layer % faces)
+        .ok_or("The surface doesn't exist!")?;
+    let mut image = alloc::vec![0; surface.width * surface.height * surface.depth];
+    file.decode_surface(&surface, &mut image)?;
+    Ok(DecodedImage {
+        width: surface.width,
+        height: surface.height * surface.depth,
+        image,
+    })
+}
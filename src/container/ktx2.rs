@@ -0,0 +1,588 @@
+use super::ktx::KtxKeyValues;
+use super::{decode_surface_flipped, max_mip_levels, Surface};
+use crate::api::from_vk_format;
+use crate::color::Flip;
+use crate::colorspace::ColorSpace;
+use crate::format::TextureFormat;
+use core::fmt;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use super::zlib::zlib_decompress;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub const KTX2_MAGIC: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+// data format descriptor values
+pub const KHR_DF_MODEL_RGBSDA: u8 = 1;
+pub const KHR_DF_MODEL_BC1A: u8 = 128;
+pub const KHR_DF_MODEL_BC2: u8 = 129;
+pub const KHR_DF_MODEL_BC3: u8 = 130;
+pub const KHR_DF_MODEL_BC4: u8 = 131;
+pub const KHR_DF_MODEL_BC5: u8 = 132;
+pub const KHR_DF_MODEL_BC6H: u8 = 133;
+pub const KHR_DF_MODEL_BC7: u8 = 134;
+pub const KHR_DF_MODEL_ETC1: u8 = 160;
+pub const KHR_DF_MODEL_ETC2: u8 = 161;
+pub const KHR_DF_MODEL_ASTC: u8 = 162;
+pub const KHR_DF_MODEL_ETC1S: u8 = 163;
+pub const KHR_DF_MODEL_PVRTC: u8 = 164;
+pub const KHR_DF_MODEL_PVRTC2: u8 = 165;
+pub const KHR_DF_MODEL_UASTC: u8 = 166;
+pub const KHR_DF_TRANSFER_LINEAR: u8 = 1;
+pub const KHR_DF_TRANSFER_SRGB: u8 = 2;
+pub const KHR_DF_FLAG_ALPHA_PREMULTIPLIED: u8 = 1;
+pub const KHR_DF_SAMPLE_DATATYPE_LINEAR: u8 = 0x10;
+pub const KHR_DF_SAMPLE_DATATYPE_EXPONENT: u8 = 0x20;
+pub const KHR_DF_SAMPLE_DATATYPE_SIGNED: u8 = 0x40;
+pub const KHR_DF_SAMPLE_DATATYPE_FLOAT: u8 = 0x80;
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+#[inline]
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// The supercompression scheme applied to the mip levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupercompressionScheme {
+    None,
+    BasisLZ,
+    Zstandard,
+    Zlib,
+    Other(u32),
+}
+
+impl From<u32> for SupercompressionScheme {
+    fn from(v: u32) -> Self {
+        match v {
+            0 => SupercompressionScheme::None,
+            1 => SupercompressionScheme::BasisLZ,
+            2 => SupercompressionScheme::Zstandard,
+            3 => SupercompressionScheme::Zlib,
+            v => SupercompressionScheme::Other(v),
+        }
+    }
+}
+
+/// Errors of the KTX 2 parser.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ktx2Error {
+    /// the file is malformed or truncated, or the image couldn't be decoded
+    Invalid(&'static str),
+    /// there's no decoder for the vkFormat
+    UnsupportedFormat(u32),
+    /// the levels use a supercompression scheme that can't be decompressed
+    UnsupportedSupercompression(SupercompressionScheme),
+}
+
+impl From<&'static str> for Ktx2Error {
+    fn from(msg: &'static str) -> Self {
+        Ktx2Error::Invalid(msg)
+    }
+}
+
+impl From<Ktx2Error> for &'static str {
+    fn from(err: Ktx2Error) -> Self {
+        match err {
+            Ktx2Error::Invalid(msg) => msg,
+            Ktx2Error::UnsupportedFormat(_) => "Unsupported KTX2 format!",
+            Ktx2Error::UnsupportedSupercompression(_) => "Unsupported KTX2 supercompression!",
+        }
+    }
+}
+
+impl fmt::Display for Ktx2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ktx2Error::Invalid(msg) => f.write_str(msg),
+            Ktx2Error::UnsupportedFormat(vk_format) => {
+                write!(f, "Unsupported KTX2 format {}!", vk_format)
+            }
+            Ktx2Error::UnsupportedSupercompression(scheme) => {
+                write!(f, "Unsupported KTX2 supercompression {:?}!", scheme)
+            }
+        }
+    }
+}
+
+/// Maps a VkFormat to a texture format.
+pub fn vk_format_to_texture_format(vk_format: u32) -> Option<TextureFormat> {
+    from_vk_format(vk_format).map(|descriptor| descriptor.format)
+}
+
+/// The header of a KTX 2 file, including the index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ktx2Header {
+    pub vk_format: u32,
+    pub type_size: u32,
+    pub pixel_width: u32,
+    pub pixel_height: u32,
+    pub pixel_depth: u32,
+    pub layer_count: u32,
+    pub face_count: u32,
+    pub level_count: u32,
+    pub supercompression_scheme: u32,
+    pub dfd_byte_offset: u32,
+    pub dfd_byte_length: u32,
+    pub kvd_byte_offset: u32,
+    pub kvd_byte_length: u32,
+    pub sgd_byte_offset: u64,
+    pub sgd_byte_length: u64,
+}
+
+impl Ktx2Header {
+    pub const SIZE: usize = 80;
+
+    /// Parses the header of a KTX 2 file, starting with the identifier.
+    pub fn parse(data: &[u8]) -> Result<Ktx2Header, Ktx2Error> {
+        if data.len() < Self::SIZE || data[0..12] != KTX2_MAGIC {
+            return Err(Ktx2Error::Invalid("Not a KTX2 file!"));
+        }
+        let read = |i: usize| read_u32(data, 12 + i * 4);
+        Ok(Ktx2Header {
+            vk_format: read(0),
+            type_size: read(1),
+            pixel_width: read(2),
+            pixel_height: read(3),
+            pixel_depth: read(4),
+            layer_count: read(5),
+            face_count: read(6),
+            level_count: read(7),
+            supercompression_scheme: read(8),
+            dfd_byte_offset: read(9),
+            dfd_byte_length: read(10),
+            kvd_byte_offset: read(11),
+            kvd_byte_length: read(12),
+            sgd_byte_offset: read_u64(data, 64),
+            sgd_byte_length: read_u64(data, 72),
+        })
+    }
+
+    pub fn supercompression(&self) -> SupercompressionScheme {
+        SupercompressionScheme::from(self.supercompression_scheme)
+    }
+
+    /// The number of mip levels, clamped to the levels of a complete mip chain.
+    pub fn mip_levels(&self) -> usize {
+        let max_levels = max_mip_levels(
+            self.pixel_width as usize,
+            self.pixel_height as usize,
+            self.depth(),
+        );
+        (self.level_count.max(1) as usize).min(max_levels)
+    }
+
+    /// The number of array elements, 1 for textures that aren't arrays.
+    pub fn array_size(&self) -> usize {
+        self.layer_count.max(1) as usize
+    }
+
+    /// The number of faces per array element, 6 for cubemaps and 1 for other textures.
+    pub fn faces(&self) -> usize {
+        self.face_count.max(1) as usize
+    }
+
+    /// The depth of volume textures, 1 for other textures.
+    pub fn depth(&self) -> usize {
+        self.pixel_depth.max(1) as usize
+    }
+}
+
+/// An entry of the level index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ktx2LevelIndex {
+    pub byte_offset: u64,
+    pub byte_length: u64,
+    pub uncompressed_byte_length: u64,
+}
+
+/// A sample of the basic data format descriptor, describing a channel or a part of it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DfdSample {
+    pub bit_offset: u16,
+    /// the number of bits, not decremented by 1 like in the file
+    pub bit_length: u8,
+    /// the channel id, e.g. 0 for red, 1 for green, 2 for blue and 15 for alpha in the RGBSDA model
+    pub channel_type: u8,
+    /// the KHR_DF_SAMPLE_DATATYPE_* qualifiers
+    pub qualifiers: u8,
+    pub sample_position: [u8; 4],
+    pub sample_lower: u32,
+    pub sample_upper: u32,
+}
+
+impl DfdSample {
+    pub const fn is_signed(&self) -> bool {
+        self.qualifiers & KHR_DF_SAMPLE_DATATYPE_SIGNED != 0
+    }
+
+    pub const fn is_float(&self) -> bool {
+        self.qualifiers & KHR_DF_SAMPLE_DATATYPE_FLOAT != 0
+    }
+
+    pub const fn is_linear(&self) -> bool {
+        self.qualifiers & KHR_DF_SAMPLE_DATATYPE_LINEAR != 0
+    }
+
+    pub const fn is_exponent(&self) -> bool {
+        self.qualifiers & KHR_DF_SAMPLE_DATATYPE_EXPONENT != 0
+    }
+}
+
+/// The basic data format descriptor block of a KTX 2 file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataFormatDescriptor<'a> {
+    pub vendor_id: u32,
+    pub descriptor_type: u32,
+    pub version_number: u16,
+    pub color_model: u8,
+    pub color_primaries: u8,
+    pub transfer_function: u8,
+    pub flags: u8,
+    /// the texel block dimensions, not decremented by 1 like in the file
+    pub texel_block_dimensions: [u8; 4],
+    pub bytes_planes: [u8; 8],
+    sample_data: &'a [u8],
+}
+
+impl<'a> DataFormatDescriptor<'a> {
+    /// Parses the first descriptor block of the data format descriptor, which starts with the total size.
+    pub fn parse(data: &'a [u8]) -> Result<DataFormatDescriptor<'a>, Ktx2Error> {
+        if data.len() < 28 {
+            return Err(Ktx2Error::Invalid("Not enough data for the DFD!"));
+        }
+        let block_size = (read_u32(data, 8) >> 16) as usize;
+        if block_size < 24 || data.len() < 4 + block_size {
+            return Err(Ktx2Error::Invalid("Invalid DFD block size!"));
+        }
+        let w0 = read_u32(data, 4);
+        let w2 = read_u32(data, 12);
+        let w3 = read_u32(data, 16).to_le_bytes();
+        Ok(DataFormatDescriptor {
+            vendor_id: w0 & 0x1ffff,
+            descriptor_type: w0 >> 17,
+            version_number: read_u32(data, 8) as u16,
+            color_model: w2 as u8,
+            color_primaries: (w2 >> 8) as u8,
+            transfer_function: (w2 >> 16) as u8,
+            flags: (w2 >> 24) as u8,
+            texel_block_dimensions: w3.map(|d| d + 1),
+            bytes_planes: data[20..28].try_into().unwrap(),
+            sample_data: &data[28..4 + block_size],
+        })
+    }
+
+    pub const fn is_srgb(&self) -> bool {
+        self.transfer_function == KHR_DF_TRANSFER_SRGB
+    }
+
+    pub const fn is_premultiplied(&self) -> bool {
+        self.flags & KHR_DF_FLAG_ALPHA_PREMULTIPLIED != 0
+    }
+
+    /// Iterates over the samples of the descriptor block.
+    pub fn samples(&self) -> impl Iterator<Item = DfdSample> + 'a {
+        self.sample_data.chunks_exact(16).map(|sample| {
+            let w0 = read_u32(sample, 0);
+            DfdSample {
+                bit_offset: w0 as u16,
+                bit_length: (w0 >> 16) as u8 + 1,
+                channel_type: (w0 >> 24) as u8 & 0xf,
+                qualifiers: (w0 >> 24) as u8 & 0xf0,
+                sample_position: sample[4..8].try_into().unwrap(),
+                sample_lower: read_u32(sample, 8),
+                sample_upper: read_u32(sample, 12),
+            }
+        })
+    }
+
+    /// Maps the color model of a block compressed format to a texture format,
+    /// used for files with an undefined vkFormat.
+    pub fn format(&self) -> Option<TextureFormat> {
+        let format = match self.color_model {
+            KHR_DF_MODEL_BC1A => TextureFormat::Bc1a,
+            KHR_DF_MODEL_BC2 => TextureFormat::Bc2,
+            KHR_DF_MODEL_BC3 => TextureFormat::Bc3,
+            KHR_DF_MODEL_BC4 => TextureFormat::Bc4,
+            KHR_DF_MODEL_BC5 => TextureFormat::Bc5,
+            KHR_DF_MODEL_BC6H if self.samples().any(|s| s.is_signed()) => TextureFormat::Bc6Signed,
+            KHR_DF_MODEL_BC6H => TextureFormat::Bc6Unsigned,
+            KHR_DF_MODEL_BC7 => TextureFormat::Bc7,
+            KHR_DF_MODEL_ETC1 => TextureFormat::Etc1,
+            KHR_DF_MODEL_ASTC if self.texel_block_dimensions[2] == 1 => TextureFormat::Astc(
+                self.texel_block_dimensions[0],
+                self.texel_block_dimensions[1],
+            ),
+            KHR_DF_MODEL_PVRTC if self.texel_block_dimensions[0] == 8 => TextureFormat::Pvrtc2bpp,
+            KHR_DF_MODEL_PVRTC => TextureFormat::Pvrtc4bpp,
+            _ => return None,
+        };
+        Some(format)
+    }
+}
+
+/// A parsed KTX 2 file referencing the file data.
+#[derive(Clone, Copy, Debug)]
+pub struct Ktx2File<'a> {
+    pub header: Ktx2Header,
+    pub format: TextureFormat,
+    pub dfd: DataFormatDescriptor<'a>,
+    data: &'a [u8],
+}
+
+impl<'a> Ktx2File<'a> {
+    /// Parses a KTX 2 file and checks that it contains all of its levels.
+    pub fn parse(data: &'a [u8]) -> Result<Ktx2File<'a>, Ktx2Error> {
+        let header = Ktx2Header::parse(data)?;
+        let dfd_data = header
+            .dfd_byte_offset
+            .checked_add(header.dfd_byte_length)
+            .and_then(|dfd_end| data.get(header.dfd_byte_offset as usize..dfd_end as usize))
+            .ok_or(Ktx2Error::Invalid("Not enough data for the DFD!"))?;
+        let dfd = DataFormatDescriptor::parse(dfd_data)?;
+        match header.kvd_byte_offset.checked_add(header.kvd_byte_length) {
+            Some(kvd_end) if kvd_end as usize <= data.len() => {}
+            _ => {
+                return Err(Ktx2Error::Invalid(
+                    "Not enough data for the key/value data!",
+                ))
+            }
+        }
+
+        let format = match header.vk_format {
+            0 => dfd.format(),
+            vk_format => vk_format_to_texture_format(vk_format),
+        }
+        .ok_or(Ktx2Error::UnsupportedFormat(header.vk_format))?;
+        let format = match format {
+            TextureFormat::Bc2 if dfd.is_premultiplied() => TextureFormat::Dxt2,
+            TextureFormat::Bc3 if dfd.is_premultiplied() => TextureFormat::Dxt4,
+            format => format,
+        };
+
+        let file = Ktx2File {
+            header,
+            format,
+            dfd,
+            data,
+        };
+        if data.len() < Ktx2Header::SIZE + header.mip_levels() * 24 {
+            return Err(Ktx2Error::Invalid("Not enough data for the level index!"));
+        }
+        for level in 0..header.mip_levels() {
+            let index = file.level_index(level);
+            match index.byte_offset.checked_add(index.byte_length) {
+                Some(level_end) if level_end <= data.len() as u64 => {}
+                _ => return Err(Ktx2Error::Invalid("Not enough data to decode image!")),
+            }
+            let uncompressed_length = match header.supercompression() {
+                SupercompressionScheme::None => index.byte_length,
+                _ => index.uncompressed_byte_length,
+            };
+            match file.checked_level_size(level) {
+                Some(level_size) if uncompressed_length >= level_size as u64 => {}
+                _ => return Err(Ktx2Error::Invalid("Invalid KTX2 level size!")),
+            }
+        }
+        Ok(file)
+    }
+
+    /// Returns the level index entry of a mip level.
+    pub fn level_index(&self, level: usize) -> Ktx2LevelIndex {
+        let offset = Ktx2Header::SIZE + level * 24;
+        Ktx2LevelIndex {
+            byte_offset: read_u64(self.data, offset),
+            byte_length: read_u64(self.data, offset + 8),
+            uncompressed_byte_length: read_u64(self.data, offset + 16),
+        }
+    }
+
+    /// Returns the data of a mip level as stored in the file, which might be supercompressed.
+    pub fn level_data(&self, level: usize) -> &'a [u8] {
+        let index = self.level_index(level);
+        &self.data[index.byte_offset as usize..(index.byte_offset + index.byte_length) as usize]
+    }
+
+    /// Returns the decompressed data of a mip level.
+    #[cfg(feature = "alloc")]
+    pub fn decompress_level(&self, level: usize) -> Result<Vec<u8>, Ktx2Error> {
+        let data = self.level_data(level);
+        match self.header.supercompression() {
+            SupercompressionScheme::None => Ok(data.to_vec()),
+            SupercompressionScheme::Zlib => {
+                let size = self.level_index(level).uncompressed_byte_length as usize;
+                let level_data = zlib_decompress(data, size)?;
+                if level_data.len() < self.level_size(level) {
+                    return Err(Ktx2Error::Invalid("Invalid KTX2 level size!"));
+                }
+                Ok(level_data)
+            }
+            scheme => Err(Ktx2Error::UnsupportedSupercompression(scheme)),
+        }
+    }
+
+    /// Iterates over the key/value pairs, the key doesn't include its terminating 0.
+    pub fn key_values(&self) -> KtxKeyValues<'a> {
+        let start = self.header.kvd_byte_offset as usize;
+        KtxKeyValues::new(
+            &self.data[start..start + self.header.kvd_byte_length as usize],
+            false,
+        )
+    }
+
+    /// The color space of the texture, from the transfer function of the data format descriptor.
+    pub const fn color_space(&self) -> ColorSpace {
+        if self.dfd.is_srgb() {
+            ColorSpace::Srgb
+        } else {
+            ColorSpace::Linear
+        }
+    }
+
+    /// The flip that turns the stored image into a top-down, left to right image,
+    /// from the `KTXorientation` key, e.g. `ru` for images stored bottom-up.
+    pub fn orientation(&self) -> Flip {
+        self.key_values()
+            .find(|(key, _)| *key == b"KTXorientation")
+            .map_or(Flip::NONE, |(_, value)| {
+                Flip::new(value.first() == Some(&b'l'), value.get(1) == Some(&b'u'))
+            })
+    }
+
+    /// Iterates over all surfaces, ordered by mip level, then array element, then face.
+    /// The offsets of supercompressed files are relative to the decompressed level data.
+    pub fn surfaces(&self) -> impl Iterator<Item = Surface> + '_ {
+        let (layers, faces) = (self.header.array_size(), self.header.faces());
+        (0..self.header.mip_levels()).flat_map(move |level| {
+            (0..layers * faces)
+                .map(move |index| self.make_surface(level, index / faces, index % faces))
+        })
+    }
+
+    /// Returns the surface of the given mip level, array element and face.
+    /// The offset of supercompressed files is relative to the decompressed level data.
+    pub fn surface(&self, level: usize, layer: usize, face: usize) -> Option<Surface> {
+        if level >= self.header.mip_levels()
+            || layer >= self.header.array_size()
+            || face >= self.header.faces()
+        {
+            return None;
+        }
+        Some(self.make_surface(level, layer, face))
+    }
+
+    /// Returns the data of a surface, supercompressed levels have to be decompressed with [`Ktx2File::decompress_level()`] instead.
+    pub fn surface_data(&self, surface: &Surface) -> Result<&'a [u8], Ktx2Error> {
+        match self.header.supercompression() {
+            SupercompressionScheme::None => Ok(surface.data(self.data)),
+            scheme => Err(Ktx2Error::UnsupportedSupercompression(scheme)),
+        }
+    }
+
+    /// Decodes a surface into an image of width * height * depth pixels,
+    /// supercompressed levels are decompressed first.
+    /// The slices are mirrored to top-down, left to right images by the [`Ktx2File::orientation()`]
+    /// and sRGB textures are decoded in the [`Ktx2File::color_space()`].
+    pub fn decode_surface(&self, surface: &Surface, image: &mut [u32]) -> Result<(), Ktx2Error> {
+        let (color_space, flip) = (self.color_space(), self.orientation());
+        match self.header.supercompression() {
+            SupercompressionScheme::None => Ok(decode_surface_flipped(
+                self.format,
+                self.data,
+                surface,
+                color_space,
+                flip,
+                image,
+            )?),
+            #[cfg(feature = "alloc")]
+            SupercompressionScheme::Zlib => {
+                let level_data = self.decompress_level(surface.level)?;
+                Ok(decode_surface_flipped(
+                    self.format,
+                    &level_data,
+                    surface,
+                    color_space,
+                    flip,
+                    image,
+                )?)
+            }
+            scheme => Err(Ktx2Error::UnsupportedSupercompression(scheme)),
+        }
+    }
+
+    fn level_dimensions(&self, level: usize) -> (usize, usize, usize) {
+        (
+            (self.header.pixel_width as usize >> level).max(1),
+            (self.header.pixel_height as usize >> level).max(1),
+            (self.header.depth() >> level).max(1),
+        )
+    }
+
+    fn face_size(&self, level: usize) -> usize {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format.data_size(width, height) * depth
+    }
+
+    fn level_size(&self, level: usize) -> usize {
+        self.face_size(level) * self.header.array_size() * self.header.faces()
+    }
+
+    fn checked_level_size(&self, level: usize) -> Option<usize> {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format
+            .checked_data_size(width, height)?
+            .checked_mul(depth)?
+            .checked_mul(self.header.array_size())?
+            .checked_mul(self.header.faces())
+    }
+
+    fn make_surface(&self, level: usize, layer: usize, face: usize) -> Surface {
+        let (width, height, depth) = self.level_dimensions(level);
+        let size = self.face_size(level);
+        let level_offset = match self.header.supercompression() {
+            SupercompressionScheme::None => self.level_index(level).byte_offset as usize,
+            _ => 0,
+        };
+        Surface {
+            level,
+            layer,
+            face,
+            width,
+            height,
+            depth,
+            offset: level_offset + (layer * self.header.faces() + face) * size,
+            size,
+        }
+    }
+}
+
+/// Decodes a mip level of a KTX 2 file.
+/// The layer indexes the faces of all array elements, e.g. 7 is the 2nd face of the 2nd cubemap.
+/// The depth slices of volume textures are stacked vertically.
+#[cfg(feature = "alloc")]
+pub fn decode_ktx2(data: &[u8], level: usize, layer: usize) -> Result<DecodedImage, Ktx2Error> {
+    let file = Ktx2File::parse(data)?;
+    let faces = file.header.faces();
+    let surface = file
+        .surface(level, layer / faces, layer % faces)
+        .ok_or(Ktx2Error::Invalid("The surface doesn't exist!"))?;
+    let mut image = alloc::vec![0; surface.width * surface.height * surface.depth];
+    file.decode_surface(&surface, &mut image)?;
+    Ok(DecodedImage {
+        width: surface.width,
+        height: surface.height * surface.depth,
+        image,
+    })
+}
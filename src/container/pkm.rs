@@ -0,0 +1,110 @@
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+pub const PKM_MAGIC: [u8; 4] = *b"PKM ";
+
+/// The 16 byte header of PKM files written by etc1tool and etcpack,
+/// the values are stored in big endian byte order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PkmHeader {
+    /// `*b"10"` for ETC1 files, `*b"20"` for ETC2 files
+    pub version: [u8; 2],
+    pub format: u16,
+    /// The width padded to whole blocks
+    pub extended_width: u16,
+    /// The height padded to whole blocks
+    pub extended_height: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl PkmHeader {
+    pub const SIZE: usize = 16;
+
+    /// Parses the header of a PKM file, starting with the magic.
+    pub fn parse(data: &[u8]) -> Result<PkmHeader, &'static str> {
+        if data.len() < Self::SIZE || data[0..4] != PKM_MAGIC {
+            return Err("Not a PKM file!");
+        }
+        let read = |offset: usize| u16::from_be_bytes([data[offset], data[offset + 1]]);
+        let header = PkmHeader {
+            version: [data[4], data[5]],
+            format: read(6),
+            extended_width: read(8),
+            extended_height: read(10),
+            width: read(12),
+            height: read(14),
+        };
+        if &header.version != b"10" && &header.version != b"20" {
+            return Err("Unsupported PKM version!");
+        }
+        if header.width == 0 || header.height == 0 {
+            return Err("Invalid PKM dimensions!");
+        }
+        Ok(header)
+    }
+
+    /// The format of the texture, None if there's no decoder for it.
+    pub const fn format(&self) -> Option<TextureFormat> {
+        let format = match self.format {
+            // ETC1_RGB_NO_MIPMAPS is the only format of version 1.0
+            0 => TextureFormat::Etc1,
+            1 | 9 => TextureFormat::Etc2Rgb,
+            // the old and the current RGBA formats
+            2 | 3 | 10 => TextureFormat::Etc2Rgba8,
+            4 | 11 => TextureFormat::Etc2Rgba1,
+            5 => TextureFormat::EacR,
+            6 => TextureFormat::EacRg,
+            7 => TextureFormat::EacRSigned,
+            8 => TextureFormat::EacRgSigned,
+            _ => return None,
+        };
+        if self.version[0] == b'1' && self.format != 0 {
+            return None;
+        }
+        Some(format)
+    }
+
+    /// The size of the encoded data following the header in bytes,
+    /// the extended size is at least the original size.
+    pub fn data_size(&self) -> Option<usize> {
+        self.format().map(|format| {
+            format.data_size(
+                self.extended_width.max(self.width) as usize,
+                self.extended_height.max(self.height) as usize,
+            )
+        })
+    }
+}
+
+/// Decodes a PKM file with ETC1, ETC2 or EAC data, the image is cropped to the original size.
+#[cfg(feature = "alloc")]
+pub fn decode_pkm(data: &[u8]) -> Result<DecodedImage, &'static str> {
+    let header = PkmHeader::parse(data)?;
+    let format = header.format().ok_or("Unsupported PKM format!")?;
+    let (width, height) = (header.width as usize, header.height as usize);
+    let extended_width = (header.extended_width as usize).max(width);
+    let extended_height = (header.extended_height as usize).max(height);
+    match header.data_size() {
+        Some(size) if size <= data.len() - PkmHeader::SIZE => {}
+        _ => return Err("Not enough data to decode image!"),
+    }
+    let decoded = format.decode_image(&data[PkmHeader::SIZE..], extended_width, extended_height)?;
+    let image = decoded
+        .image
+        .chunks_exact(extended_width)
+        .take(height)
+        .flat_map(|row| &row[..width])
+        .copied()
+        .collect();
+    Ok(DecodedImage {
+        width,
+        height,
+        image,
+    })
+}
@@ -0,0 +1,722 @@
+use super::{decode_surface_flipped, max_mip_levels, Surface};
+use crate::astc::{decode_astc_3d_flipped, decode_astc_3d_srgb_flipped};
+use crate::color::Flip;
+use crate::colorspace::ColorSpace;
+use crate::format::{TextureFormat, ASTC_BLOCK_SIZES};
+use crate::raw::{Endian, RawFormat};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+/// The version field of a PVR v3 header, "PVR\x03"
+pub const PVR3_MAGIC: u32 = 0x03525650;
+/// The tag at offset 44 of a legacy PVR v2 header, "PVR!"
+pub const PVR2_TAG: u32 = 0x21525650;
+
+// flags of the v3 header
+const PVR3_FLAG_PREMULTIPLIED: u32 = 0x02;
+// flags of the v2 header
+const PVR2_FLAG_TWIDDLE: u32 = 0x200;
+const PVR2_FLAG_CUBEMAP: u32 = 0x1000;
+const PVR2_FLAG_VOLUME: u32 = 0x4000;
+const PVR2_FLAG_VERTICAL_FLIP: u32 = 0x10000;
+
+/// The pixel format of a PVR file, a compressed format or the channel layout of an uncompressed format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PvrPixelFormat {
+    Pvrtc2bppRgb,
+    Pvrtc2bppRgba,
+    Pvrtc4bppRgb,
+    Pvrtc4bppRgba,
+    Pvrtc2_2bpp,
+    Pvrtc2_4bpp,
+    Etc1,
+    Dxt1,
+    Dxt2,
+    Dxt3,
+    Dxt4,
+    Dxt5,
+    Bc4,
+    Bc5,
+    Bc6,
+    Bc7,
+    Uyvy,
+    Yuy2,
+    Bw1bpp,
+    R9g9b9e5,
+    Rgbg8888,
+    Grgb8888,
+    Etc2Rgb,
+    Etc2Rgba,
+    Etc2RgbA1,
+    EacR11,
+    EacRg11,
+    /// ASTC with the given block width, height and depth
+    Astc(u8, u8, u8),
+    /// A compressed format without a name in this enum
+    Other(u32),
+    /// The channel names in memory order (e.g. `*b"rgba"`) and the bits per channel, 0 for unused channels.
+    /// Packed formats list their channels from the most to the least significant bits.
+    Channels([u8; 4], [u8; 4]),
+}
+
+impl From<u64> for PvrPixelFormat {
+    fn from(v: u64) -> PvrPixelFormat {
+        if v >> 32 != 0 {
+            let bytes = v.to_le_bytes();
+            return PvrPixelFormat::Channels(
+                bytes[0..4].try_into().unwrap(),
+                bytes[4..8].try_into().unwrap(),
+            );
+        }
+        match v as u32 {
+            0 => PvrPixelFormat::Pvrtc2bppRgb,
+            1 => PvrPixelFormat::Pvrtc2bppRgba,
+            2 => PvrPixelFormat::Pvrtc4bppRgb,
+            3 => PvrPixelFormat::Pvrtc4bppRgba,
+            4 => PvrPixelFormat::Pvrtc2_2bpp,
+            5 => PvrPixelFormat::Pvrtc2_4bpp,
+            6 => PvrPixelFormat::Etc1,
+            7 => PvrPixelFormat::Dxt1,
+            8 => PvrPixelFormat::Dxt2,
+            9 => PvrPixelFormat::Dxt3,
+            10 => PvrPixelFormat::Dxt4,
+            11 => PvrPixelFormat::Dxt5,
+            12 => PvrPixelFormat::Bc4,
+            13 => PvrPixelFormat::Bc5,
+            14 => PvrPixelFormat::Bc6,
+            15 => PvrPixelFormat::Bc7,
+            16 => PvrPixelFormat::Uyvy,
+            17 => PvrPixelFormat::Yuy2,
+            18 => PvrPixelFormat::Bw1bpp,
+            19 => PvrPixelFormat::R9g9b9e5,
+            20 => PvrPixelFormat::Rgbg8888,
+            21 => PvrPixelFormat::Grgb8888,
+            22 => PvrPixelFormat::Etc2Rgb,
+            23 => PvrPixelFormat::Etc2Rgba,
+            24 => PvrPixelFormat::Etc2RgbA1,
+            25 => PvrPixelFormat::EacR11,
+            26 => PvrPixelFormat::EacRg11,
+            id @ 27..=40 => {
+                let (bw, bh) = ASTC_BLOCK_SIZES[id as usize - 27];
+                PvrPixelFormat::Astc(bw, bh, 1)
+            }
+            id @ 41..=50 => {
+                const SIZES: [(u8, u8, u8); 10] = [
+                    (3, 3, 3),
+                    (4, 3, 3),
+                    (4, 4, 3),
+                    (4, 4, 4),
+                    (5, 4, 4),
+                    (5, 5, 4),
+                    (5, 5, 5),
+                    (6, 5, 5),
+                    (6, 6, 5),
+                    (6, 6, 6),
+                ];
+                let (bw, bh, bd) = SIZES[id as usize - 41];
+                PvrPixelFormat::Astc(bw, bh, bd)
+            }
+            id => PvrPixelFormat::Other(id),
+        }
+    }
+}
+
+impl PvrPixelFormat {
+    /// Maps the pixel format to a texture format, None if there's no decoder for it.
+    /// The channel type decides between the signed and unsigned variants.
+    pub fn format(&self, channel_type: u32) -> Option<TextureFormat> {
+        // the signed normalized and signed integer channel types are odd, besides the floats
+        let signed = (channel_type < 12 && channel_type % 2 == 1) || channel_type == 12;
+        let format = match *self {
+            PvrPixelFormat::Pvrtc2bppRgb | PvrPixelFormat::Pvrtc2bppRgba => {
+                TextureFormat::Pvrtc2bpp
+            }
+            PvrPixelFormat::Pvrtc4bppRgb | PvrPixelFormat::Pvrtc4bppRgba => {
+                TextureFormat::Pvrtc4bpp
+            }
+            PvrPixelFormat::Etc1 => TextureFormat::Etc1,
+            PvrPixelFormat::Dxt1 => TextureFormat::Bc1a,
+            PvrPixelFormat::Dxt2 => TextureFormat::Dxt2,
+            PvrPixelFormat::Dxt3 => TextureFormat::Bc2,
+            PvrPixelFormat::Dxt4 => TextureFormat::Dxt4,
+            PvrPixelFormat::Dxt5 => TextureFormat::Bc3,
+            PvrPixelFormat::Bc4 => TextureFormat::Bc4,
+            PvrPixelFormat::Bc5 => TextureFormat::Bc5,
+            PvrPixelFormat::Bc6 if signed => TextureFormat::Bc6Signed,
+            PvrPixelFormat::Bc6 => TextureFormat::Bc6Unsigned,
+            PvrPixelFormat::Bc7 => TextureFormat::Bc7,
+            PvrPixelFormat::Etc2Rgb => TextureFormat::Etc2Rgb,
+            PvrPixelFormat::Etc2Rgba => TextureFormat::Etc2Rgba8,
+            PvrPixelFormat::Etc2RgbA1 => TextureFormat::Etc2Rgba1,
+            PvrPixelFormat::EacR11 if signed => TextureFormat::EacRSigned,
+            PvrPixelFormat::EacR11 => TextureFormat::EacR,
+            PvrPixelFormat::EacRg11 if signed => TextureFormat::EacRgSigned,
+            PvrPixelFormat::EacRg11 => TextureFormat::EacRg,
+            PvrPixelFormat::Astc(bw, bh, 1) => TextureFormat::Astc(bw, bh),
+            PvrPixelFormat::Channels(channels, bits) => {
+                // the float channel types
+                let float = channel_type >= 12;
+                let raw = match (&channels, bits, float) {
+                    (b"rgba", [8, 8, 8, 8], false) => RawFormat::Rgba32,
+                    (b"bgra", [8, 8, 8, 8], false) => RawFormat::Bgra32,
+                    (b"argb", [8, 8, 8, 8], false) => RawFormat::Argb32,
+                    (b"bgrx", [8, 8, 8, 8], false) => RawFormat::Bgrx32,
+                    (b"rgb\0", [8, 8, 8, 0], false) => RawFormat::Rgb24,
+                    (b"bgr\0", [8, 8, 8, 0], false) => RawFormat::Bgr24,
+                    (b"rg\0\0", [8, 8, 0, 0], false) => RawFormat::Rg16,
+                    (b"r\0\0\0", [8, 0, 0, 0], false) => RawFormat::R8,
+                    (b"a\0\0\0", [8, 0, 0, 0], false) => RawFormat::Alpha8,
+                    (b"l\0\0\0", [8, 0, 0, 0], false) => RawFormat::L8,
+                    (b"r\0\0\0", [16, 0, 0, 0], false) => RawFormat::R16,
+                    (b"rgb\0", [5, 6, 5, 0], false) => RawFormat::Rgb565,
+                    (b"rgba", [4, 4, 4, 4], false) => RawFormat::Rgba4444,
+                    (b"argb", [4, 4, 4, 4], false) => RawFormat::Argb4444,
+                    (b"rgba", [5, 5, 5, 1], false) => RawFormat::Rgba5551,
+                    (b"argb", [1, 5, 5, 5], false) => RawFormat::Argb1555,
+                    (b"r\0\0\0", [16, 0, 0, 0], true) => RawFormat::RHalf,
+                    (b"rg\0\0", [16, 16, 0, 0], true) => RawFormat::RgHalf,
+                    (b"rgba", [16, 16, 16, 16], true) => RawFormat::RgbaHalf,
+                    (b"r\0\0\0", [32, 0, 0, 0], true) => RawFormat::RFloat,
+                    (b"rg\0\0", [32, 32, 0, 0], true) => RawFormat::RgFloat,
+                    (b"rgba", [32, 32, 32, 32], true) => RawFormat::RgbaFloat,
+                    _ => return None,
+                };
+                TextureFormat::Raw(raw, Endian::Little)
+            }
+            _ => return None,
+        };
+        Some(format)
+    }
+}
+
+/// Maps the pixel type of a legacy PVR v2 header to the equivalent v3 pixel format.
+pub fn pvr2_pixel_type_to_pixel_format(pixel_type: u32) -> Option<PvrPixelFormat> {
+    const fn channels(channels: &[u8; 4], bits: [u8; 4]) -> PvrPixelFormat {
+        PvrPixelFormat::Channels(*channels, bits)
+    }
+    let format = match pixel_type {
+        0x00 => channels(b"argb", [4, 4, 4, 4]),
+        0x01 => channels(b"argb", [1, 5, 5, 5]),
+        0x02 | 0x13 => channels(b"rgb\0", [5, 6, 5, 0]),
+        0x0C | 0x18 => PvrPixelFormat::Pvrtc2bppRgba,
+        0x0D | 0x19 => PvrPixelFormat::Pvrtc4bppRgba,
+        0x10 => channels(b"rgba", [4, 4, 4, 4]),
+        0x11 => channels(b"rgba", [5, 5, 5, 1]),
+        0x12 => channels(b"rgba", [8, 8, 8, 8]),
+        0x15 => channels(b"rgb\0", [8, 8, 8, 0]),
+        0x16 => channels(b"l\0\0\0", [8, 0, 0, 0]),
+        0x1A => channels(b"bgra", [8, 8, 8, 8]),
+        0x1B => channels(b"a\0\0\0", [8, 0, 0, 0]),
+        0x1C => PvrPixelFormat::Pvrtc2_4bpp,
+        0x1D => PvrPixelFormat::Pvrtc2_2bpp,
+        0x20 => PvrPixelFormat::Dxt1,
+        0x21 => PvrPixelFormat::Dxt2,
+        0x22 => PvrPixelFormat::Dxt3,
+        0x23 => PvrPixelFormat::Dxt4,
+        0x24 => PvrPixelFormat::Dxt5,
+        0x36 => PvrPixelFormat::Etc1,
+        _ => return None,
+    };
+    Some(format)
+}
+
+/// The header of a PVR v3 file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pvr3Header {
+    pub flags: u32,
+    pub pixel_format: u64,
+    /// 0 for linear RGB, 1 for sRGB
+    pub colour_space: u32,
+    pub channel_type: u32,
+    pub height: u32,
+    pub width: u32,
+    pub depth: u32,
+    pub num_surfaces: u32,
+    pub num_faces: u32,
+    pub mip_map_count: u32,
+    pub meta_data_size: u32,
+}
+
+impl Pvr3Header {
+    pub const SIZE: usize = 52;
+
+    /// Parses the header of a little endian PVR v3 file, starting with the version.
+    pub fn parse(data: &[u8]) -> Result<Pvr3Header, &'static str> {
+        if data.len() < Self::SIZE {
+            return Err("Not a PVR file!");
+        }
+        match read_u32(data, 0) {
+            PVR3_MAGIC => {}
+            v if v.swap_bytes() == PVR3_MAGIC => {
+                return Err("Big endian PVR files aren't supported!")
+            }
+            _ => return Err("Not a PVR file!"),
+        }
+        let read = |offset: usize| read_u32(data, offset);
+        Ok(Pvr3Header {
+            flags: read(4),
+            pixel_format: read(8) as u64 | (read(12) as u64) << 32,
+            colour_space: read(16),
+            channel_type: read(20),
+            height: read(24),
+            width: read(28),
+            depth: read(32),
+            num_surfaces: read(36),
+            num_faces: read(40),
+            mip_map_count: read(44),
+            meta_data_size: read(48),
+        })
+    }
+}
+
+/// The header of a legacy PVR v2 file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pvr2Header {
+    pub header_length: u32,
+    pub height: u32,
+    pub width: u32,
+    /// The number of mip levels besides the base level
+    pub mip_map_count: u32,
+    /// The pixel type in the lowest byte and the flags
+    pub flags: u32,
+    pub data_length: u32,
+    pub bpp: u32,
+    pub bitmask_red: u32,
+    pub bitmask_green: u32,
+    pub bitmask_blue: u32,
+    pub bitmask_alpha: u32,
+    pub pvr_tag: u32,
+    pub num_surfaces: u32,
+}
+
+impl Pvr2Header {
+    pub const SIZE: usize = 52;
+
+    /// Parses the header of a legacy PVR v2 file, starting with the header length.
+    pub fn parse(data: &[u8]) -> Result<Pvr2Header, &'static str> {
+        if data.len() < Self::SIZE || read_u32(data, 44) != PVR2_TAG {
+            return Err("Not a PVR file!");
+        }
+        let read = |i: usize| read_u32(data, i * 4);
+        let header = Pvr2Header {
+            header_length: read(0),
+            height: read(1),
+            width: read(2),
+            mip_map_count: read(3),
+            flags: read(4),
+            data_length: read(5),
+            bpp: read(6),
+            bitmask_red: read(7),
+            bitmask_green: read(8),
+            bitmask_blue: read(9),
+            bitmask_alpha: read(10),
+            pvr_tag: read(11),
+            num_surfaces: read(12),
+        };
+        if (header.header_length as usize) < Self::SIZE {
+            return Err("Invalid PVR header length!");
+        }
+        Ok(header)
+    }
+
+    /// The pixel type in the lowest byte of the flags.
+    pub const fn pixel_type(&self) -> u32 {
+        self.flags & 0xff
+    }
+}
+
+/// The header of a PVR file in either version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PvrHeader {
+    V2(Pvr2Header),
+    V3(Pvr3Header),
+}
+
+impl PvrHeader {
+    /// Parses the header of a PVR v3 or legacy v2 file.
+    pub fn parse(data: &[u8]) -> Result<PvrHeader, &'static str> {
+        match Pvr3Header::parse(data) {
+            Ok(header) => Ok(PvrHeader::V3(header)),
+            Err(_) => Pvr2Header::parse(data).map(PvrHeader::V2),
+        }
+    }
+
+    /// The pixel format of the texture, None if a v2 pixel type is unknown.
+    pub fn pixel_format(&self) -> Option<PvrPixelFormat> {
+        match self {
+            PvrHeader::V2(header) => pvr2_pixel_type_to_pixel_format(header.pixel_type()),
+            PvrHeader::V3(header) => Some(PvrPixelFormat::from(header.pixel_format)),
+        }
+    }
+
+    /// The format of the texture, None if there's no decoder for it.
+    pub fn format(&self) -> Option<TextureFormat> {
+        let channel_type = match self {
+            PvrHeader::V2(_) => 0,
+            PvrHeader::V3(header) => header.channel_type,
+        };
+        let format = self.pixel_format()?.format(channel_type)?;
+        Some(match format {
+            TextureFormat::Bc2 if self.is_premultiplied() => TextureFormat::Dxt2,
+            TextureFormat::Bc3 if self.is_premultiplied() => TextureFormat::Dxt4,
+            format => format,
+        })
+    }
+
+    pub const fn width(&self) -> usize {
+        match self {
+            PvrHeader::V2(header) => header.width as usize,
+            PvrHeader::V3(header) => header.width as usize,
+        }
+    }
+
+    pub const fn height(&self) -> usize {
+        match self {
+            PvrHeader::V2(header) => header.height as usize,
+            PvrHeader::V3(header) => header.height as usize,
+        }
+    }
+
+    /// The depth of volume textures, 1 for other textures.
+    pub fn depth(&self) -> usize {
+        match self {
+            PvrHeader::V2(_) => 1,
+            PvrHeader::V3(header) => header.depth.max(1) as usize,
+        }
+    }
+
+    /// The number of mip levels, clamped to the levels of a complete mip chain.
+    pub fn mip_levels(&self) -> usize {
+        let mip_levels = match self {
+            PvrHeader::V2(header) => header.mip_map_count as usize + 1,
+            PvrHeader::V3(header) => header.mip_map_count.max(1) as usize,
+        };
+        mip_levels.min(max_mip_levels(self.width(), self.height(), self.depth()))
+    }
+
+    /// The number of array elements, 1 for textures that aren't arrays.
+    /// The slices of legacy v2 volume textures are reported as array elements.
+    pub fn array_size(&self) -> usize {
+        match self {
+            PvrHeader::V2(header) => (header.num_surfaces.max(1) as usize / self.faces()).max(1),
+            PvrHeader::V3(header) => header.num_surfaces.max(1) as usize,
+        }
+    }
+
+    /// The number of faces per array element, 6 for cubemaps and 1 for other textures.
+    pub fn faces(&self) -> usize {
+        match self {
+            PvrHeader::V2(header) if header.flags & PVR2_FLAG_CUBEMAP != 0 => 6,
+            PvrHeader::V2(_) => 1,
+            PvrHeader::V3(header) => header.num_faces.max(1) as usize,
+        }
+    }
+
+    /// true if the color is premultiplied with the alpha
+    pub const fn is_premultiplied(&self) -> bool {
+        match self {
+            PvrHeader::V2(_) => false,
+            PvrHeader::V3(header) => header.flags & PVR3_FLAG_PREMULTIPLIED != 0,
+        }
+    }
+
+    /// true if the color is stored in the sRGB colour space
+    pub const fn is_srgb(&self) -> bool {
+        match self {
+            PvrHeader::V2(_) => false,
+            PvrHeader::V3(header) => header.colour_space == 1,
+        }
+    }
+
+    /// true if a legacy v2 file stores its uncompressed pixels in twiddled (morton) order
+    pub fn is_twiddled(&self) -> bool {
+        match self {
+            PvrHeader::V2(header) => {
+                header.flags & PVR2_FLAG_TWIDDLE != 0
+                    && !matches!(
+                        self.pixel_format(),
+                        Some(
+                            PvrPixelFormat::Pvrtc2bppRgba
+                                | PvrPixelFormat::Pvrtc4bppRgba
+                                | PvrPixelFormat::Pvrtc2_2bpp
+                                | PvrPixelFormat::Pvrtc2_4bpp
+                        )
+                    )
+            }
+            PvrHeader::V3(_) => false,
+        }
+    }
+
+    /// true if a legacy v2 file is a volume texture, its slices are stored as array elements.
+    pub const fn is_volume(&self) -> bool {
+        match self {
+            PvrHeader::V2(header) => header.flags & PVR2_FLAG_VOLUME != 0,
+            PvrHeader::V3(header) => header.depth > 1,
+        }
+    }
+
+    /// The offset of the first surface from the start of the file.
+    pub const fn data_offset(&self) -> usize {
+        match self {
+            PvrHeader::V2(header) => header.header_length as usize,
+            PvrHeader::V3(header) => Pvr3Header::SIZE + header.meta_data_size as usize,
+        }
+    }
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+/// A parsed PVR v3 or legacy v2 file referencing the file data.
+#[derive(Clone, Copy, Debug)]
+pub struct PvrFile<'a> {
+    pub header: PvrHeader,
+    pub format: TextureFormat,
+    data: &'a [u8],
+}
+
+impl<'a> PvrFile<'a> {
+    /// Parses a PVR file and checks that it contains all of its surfaces.
+    /// 3D ASTC textures use the format of their 2D block size, see [`PvrFile::block_depth()`].
+    pub fn parse(data: &'a [u8]) -> Result<PvrFile<'a>, &'static str> {
+        let header = PvrHeader::parse(data)?;
+        let format = match header.pixel_format() {
+            Some(PvrPixelFormat::Astc(bw, bh, _)) => TextureFormat::Astc(bw, bh),
+            _ => header.format().ok_or("Unsupported PVR format!")?,
+        };
+        if header.is_twiddled() {
+            return Err("Twiddled uncompressed PVR data isn't supported!");
+        }
+        let file = PvrFile {
+            header,
+            format,
+            data,
+        };
+        let size = (0..header.mip_levels())
+            .try_fold(0usize, |size, level| {
+                size.checked_add(file.checked_face_size(level)?)
+            })
+            .and_then(|size| size.checked_mul(header.faces()))
+            .and_then(|size| size.checked_mul(header.array_size()))
+            .and_then(|size| size.checked_add(header.data_offset()));
+        match size {
+            Some(size) if size <= data.len() => Ok(file),
+            _ => Err("Not enough data to decode image!"),
+        }
+    }
+
+    /// The block depth of 3D ASTC textures, 1 for all other formats.
+    pub fn block_depth(&self) -> usize {
+        match self.header.pixel_format() {
+            Some(PvrPixelFormat::Astc(_, _, bd)) => bd as usize,
+            _ => 1,
+        }
+    }
+
+    /// Iterates over the metadata blocks of a v3 file as (fourcc, key, data).
+    pub fn meta_data(&self) -> PvrMetaData<'a> {
+        let data = match self.header {
+            PvrHeader::V2(_) => &[][..],
+            PvrHeader::V3(_) => &self.data[Pvr3Header::SIZE..self.header.data_offset()],
+        };
+        PvrMetaData { data }
+    }
+
+    /// The color space of the texture, from the colour space field of v3 files.
+    pub const fn color_space(&self) -> ColorSpace {
+        if self.header.is_srgb() {
+            ColorSpace::Srgb
+        } else {
+            ColorSpace::Linear
+        }
+    }
+
+    /// The flip that turns the stored image into a top-down, left to right image,
+    /// from the orientation metadata of v3 files or the vertical flip flag of legacy v2 files.
+    pub fn orientation(&self) -> Flip {
+        if let PvrHeader::V2(header) = self.header {
+            return Flip::new(false, header.flags & PVR2_FLAG_VERTICAL_FLIP != 0);
+        }
+        // key 3 holds one byte per axis, nonzero for x to the left and y upwards
+        self.meta_data()
+            .find(|&(fourcc, key, data)| fourcc == PVR3_MAGIC && key == 3 && data.len() >= 2)
+            .map_or(Flip::NONE, |(_, _, data)| {
+                Flip::new(data[0] != 0, data[1] != 0)
+            })
+    }
+
+    /// Iterates over all surfaces in the order they are stored:
+    /// mip levels, then array elements, then faces for v3 files,
+    /// array elements, then faces, then mip levels for legacy v2 files.
+    pub fn surfaces(&self) -> PvrSurfaces<'_> {
+        PvrSurfaces {
+            file: self,
+            index: 0,
+        }
+    }
+
+    /// Returns the surface of the given mip level, array element and face.
+    pub fn surface(&self, level: usize, layer: usize, face: usize) -> Option<Surface> {
+        let header = &self.header;
+        if level >= header.mip_levels() || layer >= header.array_size() || face >= header.faces() {
+            return None;
+        }
+        let index = layer * header.faces() + face;
+        let level_sizes = (0..level).map(|level| self.face_size(level));
+        let offset = match header {
+            PvrHeader::V2(_) => {
+                let element_size = (0..header.mip_levels())
+                    .map(|level| self.face_size(level))
+                    .sum::<usize>();
+                index * element_size + level_sizes.sum::<usize>()
+            }
+            PvrHeader::V3(_) => {
+                let faces = header.array_size() * header.faces();
+                level_sizes.sum::<usize>() * faces + index * self.face_size(level)
+            }
+        };
+        let (width, height, depth) = self.level_dimensions(level);
+        Some(Surface {
+            level,
+            layer,
+            face,
+            width,
+            height,
+            depth,
+            offset: header.data_offset() + offset,
+            size: self.face_size(level),
+        })
+    }
+
+    /// Returns the data of a surface.
+    pub fn surface_data(&self, surface: &Surface) -> &'a [u8] {
+        surface.data(self.data)
+    }
+
+    /// Decodes a surface into an image of width * height * depth pixels,
+    /// the slices are mirrored to top-down, left to right images by the [`PvrFile::orientation()`]
+    /// and sRGB textures are decoded in the [`PvrFile::color_space()`].
+    pub fn decode_surface(&self, surface: &Surface, image: &mut [u32]) -> Result<(), &'static str> {
+        let (color_space, flip) = (self.color_space(), self.orientation());
+        match (self.format, self.block_depth()) {
+            (TextureFormat::Astc(bw, bh), block_depth) if block_depth > 1 => {
+                let decode = match color_space {
+                    ColorSpace::Linear => decode_astc_3d_flipped,
+                    ColorSpace::Srgb => decode_astc_3d_srgb_flipped,
+                };
+                decode(
+                    self.surface_data(surface),
+                    surface.width,
+                    surface.height,
+                    surface.depth,
+                    bw as usize,
+                    bh as usize,
+                    block_depth,
+                    image,
+                    flip,
+                )
+            }
+            _ => decode_surface_flipped(self.format, self.data, surface, color_space, flip, image),
+        }
+    }
+
+    fn level_dimensions(&self, level: usize) -> (usize, usize, usize) {
+        (
+            (self.header.width() >> level).max(1),
+            (self.header.height() >> level).max(1),
+            (self.header.depth() >> level).max(1),
+        )
+    }
+
+    fn face_size(&self, level: usize) -> usize {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format.data_size(width, height) * depth.div_ceil(self.block_depth())
+    }
+
+    fn checked_face_size(&self, level: usize) -> Option<usize> {
+        let (width, height, depth) = self.level_dimensions(level);
+        self.format
+            .checked_data_size(width, height)?
+            .checked_mul(depth.div_ceil(self.block_depth()))
+    }
+}
+
+/// Iterator over the metadata blocks of a PVR v3 file, see [`PvrFile::meta_data()`].
+pub struct PvrMetaData<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for PvrMetaData<'a> {
+    type Item = (u32, u32, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 12 {
+            return None;
+        }
+        let fourcc = read_u32(self.data, 0);
+        let key = read_u32(self.data, 4);
+        let size = read_u32(self.data, 8) as usize;
+        let value = self.data.get(12..12 + size)?;
+        self.data = &self.data[12 + size..];
+        Some((fourcc, key, value))
+    }
+}
+
+/// Iterator over the surfaces of a PVR file, see [`PvrFile::surfaces()`].
+pub struct PvrSurfaces<'a> {
+    file: &'a PvrFile<'a>,
+    index: usize,
+}
+
+impl Iterator for PvrSurfaces<'_> {
+    type Item = Surface;
+
+    fn next(&mut self) -> Option<Surface> {
+        let header = &self.file.header;
+        let faces = header.faces();
+        let layers = header.array_size();
+        let levels = header.mip_levels();
+        let (level, layer, face) = match header {
+            PvrHeader::V2(_) => (
+                self.index % levels,
+                self.index / levels / faces,
+                self.index / levels % faces,
+            ),
+            PvrHeader::V3(_) => (
+                self.index / faces / layers,
+                self.index / faces % layers,
+                self.index % faces,
+            ),
+        };
+        if level >= levels || layer >= layers {
+            return None;
+        }
+        self.index += 1;
+        self.file.surface(level, layer, face)
+    }
+}
+
+/// Decodes a mip level of a PVR v3 or legacy v2 file.
+/// The layer indexes the faces of all array elements, e.g. 7 is the 2nd face of the 2nd cubemap.
+/// The depth slices of volume textures are stacked vertically.
+#[cfg(feature = "alloc")]
+pub fn decode_pvr(data: &[u8], level: usize, layer: usize) -> Result<DecodedImage, &'static str> {
+    let file = PvrFile::parse(data)?;
+    let faces = file.header.faces();
+    let surface = file
+        .surface(level, layer / faces, layer % faces)
+        .ok_or("The surface doesn't exist!")?;
+    let mut image = alloc::vec![0; surface.width * surface.height * surface.depth];
+    file.decode_surface(&surface, &mut image)?;
+    Ok(DecodedImage {
+        width: surface.width,
+        height: surface.height * surface.depth,
+        image,
+    })
+}
@@ -0,0 +1,270 @@
+// zlib (RFC 1950) stream decompression with a canonical huffman inflater (RFC 1951)
+extern crate alloc;
+use alloc::vec::Vec;
+use core::result::Result;
+
+const ERR_EOF: &str = "Unexpected end of the zlib stream!";
+const ERR_CODE: &str = "Invalid deflate code!";
+const ERR_SIZE: &str = "The zlib stream is larger than expected!";
+
+// the size given by the files is only trusted up to this multiple of the compressed size
+const MAX_RESERVE_RATIO: usize = 16;
+
+static LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+static LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+static DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+static DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+static CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct InflateReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl InflateReader<'_> {
+    #[inline]
+    fn bits(&mut self, num_bits: u32) -> Result<u32, &'static str> {
+        while self.bit_count < num_bits {
+            let byte = *self.data.get(self.pos).ok_or(ERR_EOF)?;
+            self.pos += 1;
+            self.bit_buf |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+        let v = self.bit_buf & ((1 << num_bits) - 1);
+        self.bit_buf >>= num_bits;
+        self.bit_count -= num_bits;
+        Ok(v)
+    }
+
+    // drops the remaining bits of the current byte
+    #[inline]
+    fn align_byte(&mut self) {
+        self.bit_buf = 0;
+        self.bit_count = 0;
+    }
+}
+
+struct Huffman {
+    counts: [u16; 16],
+    symbols: [u16; 288],
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut huffman = Huffman {
+            counts: [0; 16],
+            symbols: [0; 288],
+        };
+        lengths
+            .iter()
+            .for_each(|&len| huffman.counts[len as usize] += 1);
+        huffman.counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        (1..15).for_each(|len| offsets[len + 1] = offsets[len] + huffman.counts[len]);
+        lengths.iter().enumerate().for_each(|(symbol, &len)| {
+            if len != 0 {
+                huffman.symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        });
+        huffman
+    }
+
+    fn decode(&self, reader: &mut InflateReader) -> Result<u16, &'static str> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(ERR_CODE)
+    }
+}
+
+fn inflate_block(
+    reader: &mut InflateReader,
+    out: &mut Vec<u8>,
+    max_size: usize,
+    lit: &Huffman,
+    dist: &Huffman,
+) -> Result<(), &'static str> {
+    loop {
+        let symbol = lit.decode(reader)? as usize;
+        match symbol {
+            0..=255 => {
+                if out.len() >= max_size {
+                    return Err(ERR_SIZE);
+                }
+                out.push(symbol as u8)
+            }
+            256 => return Ok(()),
+            257..=285 => {
+                let i = symbol - 257;
+                let length =
+                    LENGTH_BASE[i] as usize + reader.bits(LENGTH_EXTRA[i] as u32)? as usize;
+                let d = dist.decode(reader)? as usize;
+                if d >= 30 {
+                    return Err(ERR_CODE);
+                }
+                let distance = DIST_BASE[d] as usize + reader.bits(DIST_EXTRA[d] as u32)? as usize;
+                if distance > out.len() {
+                    return Err("Invalid deflate distance!");
+                }
+                if out.len() + length > max_size {
+                    return Err(ERR_SIZE);
+                }
+                let start = out.len() - distance;
+                (0..length).for_each(|i| out.push(out[start + i]));
+            }
+            _ => return Err(ERR_CODE),
+        }
+    }
+}
+
+fn read_dynamic_tables(reader: &mut InflateReader) -> Result<(Huffman, Huffman), &'static str> {
+    let hlit = reader.bits(5)? as usize + 257;
+    let hdist = reader.bits(5)? as usize + 1;
+    let hclen = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &i in &CODE_LENGTH_ORDER[..hclen] {
+        code_lengths[i] = reader.bits(3)? as u8;
+    }
+    let code_huffman = Huffman::new(&code_lengths);
+
+    let mut lengths = [0u8; 320];
+    let mut i = 0;
+    while i < hlit + hdist {
+        let (value, repeat) = match code_huffman.decode(reader)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 if i > 0 => (lengths[i - 1], 3 + reader.bits(2)? as usize),
+            17 => (0, 3 + reader.bits(3)? as usize),
+            18 => (0, 11 + reader.bits(7)? as usize),
+            _ => return Err(ERR_CODE),
+        };
+        if i + repeat > hlit + hdist {
+            return Err(ERR_CODE);
+        }
+        lengths[i..i + repeat].fill(value);
+        i += repeat;
+    }
+    Ok((
+        Huffman::new(&lengths[..hlit]),
+        Huffman::new(&lengths[hlit..hlit + hdist]),
+    ))
+}
+
+fn inflate(
+    reader: &mut InflateReader,
+    out: &mut Vec<u8>,
+    max_size: usize,
+) -> Result<(), &'static str> {
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align_byte();
+                let header = reader.data.get(reader.pos..reader.pos + 4).ok_or(ERR_EOF)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                if len != !u16::from_le_bytes([header[2], header[3]]) {
+                    return Err("Invalid stored deflate block!");
+                }
+                reader.pos += 4;
+                let block = reader
+                    .data
+                    .get(reader.pos..reader.pos + len as usize)
+                    .ok_or(ERR_EOF)?;
+                if out.len() + block.len() > max_size {
+                    return Err(ERR_SIZE);
+                }
+                out.extend_from_slice(block);
+                reader.pos += len as usize;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let lit = Huffman::new(&lengths);
+                let dist = Huffman::new(&[5; 30]);
+                inflate_block(reader, out, max_size, &lit, &dist)?;
+            }
+            2 => {
+                let (lit, dist) = read_dynamic_tables(reader)?;
+                inflate_block(reader, out, max_size, &lit, &dist)?;
+            }
+            _ => return Err("Invalid deflate block type!"),
+        }
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    data.chunks(5552).for_each(|chunk| {
+        chunk.iter().for_each(|&v| {
+            a += v as u32;
+            b += a;
+        });
+        a %= 65521;
+        b %= 65521;
+    });
+    b << 16 | a
+}
+
+/// Decompresses a zlib stream, streams larger than max_size are rejected.
+// u16::is_multiple_of needs Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn zlib_decompress(data: &[u8], max_size: usize) -> Result<Vec<u8>, &'static str> {
+    if data.len() < 6 {
+        return Err(ERR_EOF);
+    }
+    let (cmf, flg) = (data[0], data[1]);
+    if cmf & 0x0f != 8 || ((cmf as u16) << 8 | flg as u16) % 31 != 0 {
+        return Err("Invalid zlib header!");
+    }
+    if flg & 0x20 != 0 {
+        return Err("zlib preset dictionaries aren't supported!");
+    }
+
+    let mut out = Vec::with_capacity(max_size.min(data.len().saturating_mul(MAX_RESERVE_RATIO)));
+    let mut reader = InflateReader {
+        data,
+        pos: 2,
+        bit_buf: 0,
+        bit_count: 0,
+    };
+    inflate(&mut reader, &mut out, max_size)?;
+
+    let checksum = data.get(reader.pos..reader.pos + 4).ok_or(ERR_EOF)?;
+    if u32::from_be_bytes(checksum.try_into().unwrap()) != adler32(&out) {
+        return Err("zlib checksum mismatch!");
+    }
+    Ok(out)
+}
@@ -3,7 +3,7 @@ use crate::crunch::crn_decomp::CrnHeader;
 // Supported compressed pixel formats.
 // Basically all the standard DX9 formats, with some swizzled DXT5 formats
 // (most of them supported by ATI's Compressonator), along with some ATI/X360 GPU specific formats.
-#[derive(PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 #[repr(u32)]
 pub enum CrnFormat {
     Invalid = 4294967295, // u32 -1,
@@ -39,6 +39,7 @@ pub enum CrnFormat {
     Total,
 }
 
+#[derive(Clone, Copy, Debug)]
 #[repr(C)]
 pub struct CrnTextureInfo {
     pub struct_size: u32,
@@ -5,7 +5,8 @@ pub(crate) mod crn_symbol_codec;
 pub(crate) mod crn_unpacker;
 pub(crate) mod crn_utils;
 use super::crnlib::{CrnFormat, CrnTextureInfo};
-use crate::bcn;
+use crate::color::Flip;
+use crate::format::TextureFormat;
 use core::cmp::max;
 extern crate alloc;
 
@@ -47,22 +48,32 @@ pub fn decode_crunch(
     width: usize,
     height: usize,
     image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_crunch_flipped(data, width, height, image, Flip::NONE)
+}
+
+/// Decodes a crunch texture into an image mirrored by flip.
+pub fn decode_crunch_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    flip: Flip,
 ) -> Result<(), &'static str> {
     let handler = crunch_unpack_level(data, data.len() as u32, 0)?;
-    match handler.format {
-        CrnFormat::Dxt1 => bcn::decode_bc1(&handler.dxt_data, width, height, image),
+    let format = match handler.format {
+        CrnFormat::Dxt1 => TextureFormat::Bc1,
 
         CrnFormat::CCrnfmtDxt5
         | CrnFormat::Dxt5CcxY
         | CrnFormat::Dxt5XGbr
         | CrnFormat::Dxt5Agbr
-        | CrnFormat::Dxt5XGxR => bcn::decode_bc3(&handler.dxt_data, width, height, image),
+        | CrnFormat::Dxt5XGxR => TextureFormat::Bc3,
 
-        CrnFormat::Dxt5a => bcn::decode_bc4(&handler.dxt_data, width, height, image),
+        CrnFormat::Dxt5a => TextureFormat::Bc4,
 
-        CrnFormat::DxnXy | CrnFormat::DxnYx => {
-            bcn::decode_bc5(&handler.dxt_data, width, height, image)
-        }
-        _ => Err("Invalid crunch format."),
-    }
+        CrnFormat::DxnXy | CrnFormat::DxnYx => TextureFormat::Bc5,
+        _ => return Err("Invalid crunch format."),
+    };
+    format.decode_flipped(&handler.dxt_data, width, height, flip, image)
 }
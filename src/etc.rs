@@ -4,6 +4,7 @@ pub(crate) mod consts;
 pub(crate) mod eac;
 pub(crate) mod etc1;
 pub(crate) mod etc2;
+pub(crate) mod etc3ds;
 
 pub use eac::{
     decode_eac_block, decode_eac_signed_block, decode_eacr_block, decode_eacr_signed_block,
@@ -13,6 +14,10 @@ pub use etc1::decode_etc1_block;
 pub use etc2::{
     decode_etc2_a8_block, decode_etc2_rgb_block, decode_etc2_rgba1_block, decode_etc2_rgba8_block,
 };
+pub use etc3ds::{
+    decode_etc1_3ds, decode_etc1_3ds_block, decode_etc1_3ds_flipped, decode_etc1a4_3ds,
+    decode_etc1a4_3ds_block, decode_etc1a4_3ds_flipped,
+};
 
 block_decoder!("etc1", 4, 4, 8, decode_etc1_block);
 block_decoder!("etc2_rgb", 4, 4, 8, decode_etc2_rgb_block);
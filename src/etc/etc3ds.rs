@@ -0,0 +1,113 @@
+use crate::color::{color, copy_block_buffer, Flip};
+use crate::etc::etc1::decode_etc1_block;
+use core::result::Result;
+
+/// Decodes a 3DS ETC1 block, which is a little endian ETC1 block, into a 4x4 buffer.
+#[inline]
+pub fn decode_etc1_3ds_block(data: &[u8], outbuf: &mut [u32]) {
+    let mut block: [u8; 8] = data[..8].try_into().unwrap();
+    block.reverse();
+    decode_etc1_block(&block, outbuf);
+}
+
+/// Decodes a 3DS ETC1A4 block, 8 bytes of 4 bit alpha followed by a 3DS ETC1 block, into a 4x4 buffer.
+#[inline]
+pub fn decode_etc1a4_3ds_block(data: &[u8], outbuf: &mut [u32]) {
+    decode_etc1_3ds_block(&data[8..], outbuf);
+    // the alpha values are stored column by column, starting with the low nibble
+    let alpha = u64::from_le_bytes(data[..8].try_into().unwrap());
+    outbuf[..16].iter_mut().enumerate().for_each(|(i, p)| {
+        let (x, y) = (i % 4, i / 4);
+        let a = (alpha >> (4 * (x * 4 + y)) & 0xf) as u8 * 0x11;
+        let [b, g, r, _] = p.to_le_bytes();
+        *p = color(r, g, b, a);
+    });
+}
+
+// The 3DS stores the blocks in 8x8 tiles of 4 blocks in Z order, the tiles start at the bottom of the image.
+fn decode_3ds_tiled(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    raw_block_size: usize,
+    flip: Flip,
+    decode_block: fn(&[u8], &mut [u32]),
+) -> Result<(), &'static str> {
+    let flip = Flip::VERTICAL.combine(flip);
+    let num_tiles_x = width.div_ceil(8);
+    let num_tiles_y = height.div_ceil(8);
+    if data.len() < num_tiles_x * num_tiles_y * 4 * raw_block_size {
+        return Err("Not enough data to decode image!");
+    }
+    if image.len() < width * height {
+        return Err("Image buffer is too small!");
+    }
+
+    let mut buffer = [0u32; 16];
+    data.chunks_exact(raw_block_size)
+        .take(num_tiles_x * num_tiles_y * 4)
+        .enumerate()
+        .for_each(|(i, block)| {
+            let tile = i / 4;
+            let bx = (tile % num_tiles_x) * 2 + (i & 1);
+            let by = (tile / num_tiles_x) * 2 + (i >> 1 & 1);
+            // the tiles are padded to 8x8 pixels
+            if bx * 4 >= width || by * 4 >= height {
+                return;
+            }
+            decode_block(block, &mut buffer);
+            copy_block_buffer(bx, by, width, height, 4, 4, &buffer, image, flip);
+        });
+    Ok(())
+}
+
+/// Decodes a 3DS ETC1 texture into an image, the tiling and the vertical flip are undone.
+pub fn decode_etc1_3ds(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_etc1_3ds_flipped(data, width, height, image, Flip::NONE)
+}
+
+/// Decodes a 3DS ETC1 texture into an image mirrored by flip, after the tiling and the vertical flip are undone.
+pub fn decode_etc1_3ds_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_3ds_tiled(data, width, height, image, 8, flip, decode_etc1_3ds_block)
+}
+
+/// Decodes a 3DS ETC1A4 texture into an image, the tiling and the vertical flip are undone.
+pub fn decode_etc1a4_3ds(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_etc1a4_3ds_flipped(data, width, height, image, Flip::NONE)
+}
+
+/// Decodes a 3DS ETC1A4 texture into an image mirrored by flip, after the tiling and the vertical flip are undone.
+pub fn decode_etc1a4_3ds_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_3ds_tiled(
+        data,
+        width,
+        height,
+        image,
+        16,
+        flip,
+        decode_etc1a4_3ds_block,
+    )
+}
@@ -0,0 +1,461 @@
+use crate::color::{decode_blocks, Flip};
+use crate::colorspace::{convert_color_space, ColorSpace};
+use crate::raw::{decode_raw, decode_raw_pixel, Endian, RawFormat};
+use crate::{astc, atc, bcn, etc, fxt1, pvrtc};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// The 2D ASTC block sizes in the order used by the graphics APIs.
+pub(crate) const ASTC_BLOCK_SIZES: [(u8, u8); 14] = [
+    (4, 4),
+    (5, 4),
+    (5, 5),
+    (6, 5),
+    (6, 6),
+    (8, 5),
+    (8, 6),
+    (8, 8),
+    (10, 5),
+    (10, 6),
+    (10, 8),
+    (10, 10),
+    (12, 10),
+    (12, 12),
+];
+
+/// The numeric interpretation of the channels of a format in the graphics APIs.
+/// The decoders output the stored values, [`FormatDescriptor::decode()`] converts the sRGB variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumericFormat {
+    Unorm,
+    Snorm,
+    Srgb,
+    Ufloat,
+    Sfloat,
+    Uint,
+    Sint,
+}
+
+/// A texture format with the numeric interpretation of its channels,
+/// describes the format enums of the graphics APIs, see [`crate::api`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatDescriptor {
+    pub format: TextureFormat,
+    pub numeric_format: NumericFormat,
+}
+
+impl FormatDescriptor {
+    pub const fn new(format: TextureFormat, numeric_format: NumericFormat) -> FormatDescriptor {
+        FormatDescriptor {
+            format,
+            numeric_format,
+        }
+    }
+
+    /// The color space of the stored color channels.
+    pub const fn color_space(&self) -> ColorSpace {
+        match self.numeric_format {
+            NumericFormat::Srgb => ColorSpace::Srgb,
+            _ => ColorSpace::Linear,
+        }
+    }
+
+    /// Decodes a texture of this format into an image with the colors in the given color space,
+    /// e.g. [`ColorSpace::Linear`] for compositing and mip generation.
+    pub fn decode(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        color_space: ColorSpace,
+        image: &mut [u32],
+    ) -> Result<(), &'static str> {
+        self.format
+            .decode_with_color_space(data, width, height, self.color_space(), image)?;
+        convert_color_space(
+            &mut image[..width * height],
+            self.color_space(),
+            color_space,
+        );
+        Ok(())
+    }
+
+    /// Decodes a texture of this format into an image with the colors in the given color space,
+    /// mirrored by flip.
+    pub fn decode_flipped(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        color_space: ColorSpace,
+        flip: Flip,
+        image: &mut [u32],
+    ) -> Result<(), &'static str> {
+        self.format.decode_with_color_space_flipped(
+            data,
+            width,
+            height,
+            self.color_space(),
+            flip,
+            image,
+        )?;
+        convert_color_space(
+            &mut image[..width * height],
+            self.color_space(),
+            color_space,
+        );
+        Ok(())
+    }
+
+    /// Decodes a texture of this format into a newly allocated image with the colors in the given color space.
+    #[cfg(feature = "alloc")]
+    pub fn decode_image(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        color_space: ColorSpace,
+    ) -> Result<DecodedImage, &'static str> {
+        let mut image = alloc::vec![0; width * height];
+        self.decode(data, width, height, color_space, &mut image)?;
+        Ok(DecodedImage {
+            width,
+            height,
+            image,
+        })
+    }
+}
+
+/// The texture formats the decoders of this crate can handle,
+/// used to describe the content of a texture container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFormat {
+    AtcRgb4,
+    AtcRgba8,
+    AtcRgba8Explicit,
+    /// ASTC with the given block width and height
+    Astc(u8, u8),
+    Bc1,
+    Bc1a,
+    Bc2,
+    Bc3,
+    Bc4,
+    Bc5,
+    Bc6Signed,
+    Bc6Unsigned,
+    Bc7,
+    Dxt2,
+    Dxt4,
+    Etc1,
+    Etc2Rgb,
+    Etc2Rgba1,
+    Etc2Rgba8,
+    EacR,
+    EacRSigned,
+    EacRg,
+    EacRgSigned,
+    Fxt1,
+    Pvrtc2bpp,
+    Pvrtc4bpp,
+    Raw(RawFormat, Endian),
+}
+
+impl TextureFormat {
+    /// The width and height of a block in pixels, (1, 1) for uncompressed formats.
+    pub const fn block_size(&self) -> (usize, usize) {
+        match self {
+            TextureFormat::Astc(bw, bh) => (*bw as usize, *bh as usize),
+            TextureFormat::Fxt1 | TextureFormat::Pvrtc2bpp => (8, 4),
+            TextureFormat::Raw(..) => (1, 1),
+            _ => (4, 4),
+        }
+    }
+
+    /// true if the graphics APIs have an sRGB variant of the format
+    pub const fn has_srgb_variant(&self) -> bool {
+        match self {
+            TextureFormat::Astc(..)
+            | TextureFormat::Bc1
+            | TextureFormat::Bc1a
+            | TextureFormat::Bc2
+            | TextureFormat::Bc3
+            | TextureFormat::Bc7
+            | TextureFormat::Dxt2
+            | TextureFormat::Dxt4
+            | TextureFormat::Etc1
+            | TextureFormat::Etc2Rgb
+            | TextureFormat::Etc2Rgba1
+            | TextureFormat::Etc2Rgba8
+            | TextureFormat::Pvrtc2bpp
+            | TextureFormat::Pvrtc4bpp => true,
+            TextureFormat::Raw(format, _) => matches!(
+                format,
+                RawFormat::Rgb24
+                    | RawFormat::Bgr24
+                    | RawFormat::Rgba32
+                    | RawFormat::Argb32
+                    | RawFormat::Bgra32
+                    | RawFormat::Bgrx32
+            ),
+            _ => false,
+        }
+    }
+
+    /// The size of a block in bytes.
+    pub const fn block_bytes(&self) -> usize {
+        match self {
+            TextureFormat::AtcRgb4
+            | TextureFormat::Bc1
+            | TextureFormat::Bc1a
+            | TextureFormat::Bc4
+            | TextureFormat::Etc1
+            | TextureFormat::Etc2Rgb
+            | TextureFormat::Etc2Rgba1
+            | TextureFormat::EacR
+            | TextureFormat::EacRSigned
+            | TextureFormat::Pvrtc2bpp
+            | TextureFormat::Pvrtc4bpp => 8,
+            TextureFormat::Raw(format, _) => format.bytes_per_pixel(),
+            _ => 16,
+        }
+    }
+
+    /// The size of the encoded data of a width * height texture in bytes.
+    pub fn data_size(&self, width: usize, height: usize) -> usize {
+        let (num_blocks_x, num_blocks_y) = self.num_blocks(width, height);
+        num_blocks_x * num_blocks_y * self.block_bytes()
+    }
+
+    /// The size of the encoded data of a width * height texture in bytes, None if it overflows.
+    pub fn checked_data_size(&self, width: usize, height: usize) -> Option<usize> {
+        let (num_blocks_x, num_blocks_y) = self.num_blocks(width, height);
+        num_blocks_x
+            .checked_mul(num_blocks_y)?
+            .checked_mul(self.block_bytes())
+    }
+
+    fn num_blocks(&self, width: usize, height: usize) -> (usize, usize) {
+        let (bw, bh) = self.block_size();
+        match self {
+            // PVRTC textures are at least 2x2 blocks big
+            TextureFormat::Pvrtc2bpp | TextureFormat::Pvrtc4bpp => {
+                (width.div_ceil(bw).max(2), height.div_ceil(bh).max(2))
+            }
+            _ => (width.div_ceil(bw), height.div_ceil(bh)),
+        }
+    }
+
+    /// Decodes a texture of this format into an image
+    pub fn decode(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        image: &mut [u32],
+    ) -> Result<(), &'static str> {
+        match *self {
+            TextureFormat::AtcRgb4 => atc::decode_atc_rgb4(data, width, height, image),
+            TextureFormat::AtcRgba8 => atc::decode_atc_rgba8(data, width, height, image),
+            TextureFormat::AtcRgba8Explicit => {
+                atc::decode_atc_rgba8_explicit(data, width, height, image)
+            }
+            TextureFormat::Astc(bw, bh) => {
+                astc::decode_astc(data, width, height, bw as usize, bh as usize, image)
+            }
+            TextureFormat::Bc1 => bcn::decode_bc1(data, width, height, image),
+            TextureFormat::Bc1a => bcn::decode_bc1a(data, width, height, image),
+            TextureFormat::Bc2 => bcn::decode_bc2(data, width, height, image),
+            TextureFormat::Bc3 => bcn::decode_bc3(data, width, height, image),
+            TextureFormat::Bc4 => bcn::decode_bc4(data, width, height, image),
+            TextureFormat::Bc5 => bcn::decode_bc5(data, width, height, image),
+            TextureFormat::Bc6Signed => bcn::decode_bc6_signed(data, width, height, image),
+            TextureFormat::Bc6Unsigned => bcn::decode_bc6_unsigned(data, width, height, image),
+            TextureFormat::Bc7 => bcn::decode_bc7(data, width, height, image),
+            TextureFormat::Dxt2 => bcn::decode_dxt2(data, width, height, image),
+            TextureFormat::Dxt4 => bcn::decode_dxt4(data, width, height, image),
+            TextureFormat::Etc1 => etc::decode_etc1(data, width, height, image),
+            TextureFormat::Etc2Rgb => etc::decode_etc2_rgb(data, width, height, image),
+            TextureFormat::Etc2Rgba1 => etc::decode_etc2_rgba1(data, width, height, image),
+            TextureFormat::Etc2Rgba8 => etc::decode_etc2_rgba8(data, width, height, image),
+            TextureFormat::EacR => etc::decode_eacr(data, width, height, image),
+            TextureFormat::EacRSigned => etc::decode_eacr_signed(data, width, height, image),
+            TextureFormat::EacRg => etc::decode_eacrg(data, width, height, image),
+            TextureFormat::EacRgSigned => etc::decode_eacrg_signed(data, width, height, image),
+            TextureFormat::Fxt1 => fxt1::decode_fxt1(data, width, height, image),
+            TextureFormat::Pvrtc2bpp => pvrtc::decode_pvrtc_2bpp(data, width, height, image),
+            TextureFormat::Pvrtc4bpp => pvrtc::decode_pvrtc_4bpp(data, width, height, image),
+            TextureFormat::Raw(format, endian) => {
+                decode_raw(data, width, height, image, format, endian)
+            }
+        }
+    }
+
+    /// Decodes a texture of this format into an image mirrored by flip while the blocks are written,
+    /// e.g. [`Flip::VERTICAL`] for textures with a bottom-left origin.
+    pub fn decode_flipped(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        flip: Flip,
+        image: &mut [u32],
+    ) -> Result<(), &'static str> {
+        let decode_block: fn(&[u8], &mut [u32]) = match *self {
+            TextureFormat::AtcRgb4 => atc::decode_atc_rgb4_block,
+            TextureFormat::AtcRgba8 => atc::decode_atc_rgba8_block,
+            TextureFormat::AtcRgba8Explicit => atc::decode_atc_rgba8_explicit_block,
+            TextureFormat::Astc(bw, bh) => {
+                return astc::decode_astc_flipped(
+                    data,
+                    width,
+                    height,
+                    bw as usize,
+                    bh as usize,
+                    image,
+                    flip,
+                )
+            }
+            TextureFormat::Bc1 => bcn::decode_bc1_block,
+            TextureFormat::Bc1a => bcn::decode_bc1a_block,
+            TextureFormat::Bc2 => bcn::decode_bc2_block,
+            TextureFormat::Bc3 => bcn::decode_bc3_block,
+            TextureFormat::Bc4 => bcn::decode_bc4_block,
+            TextureFormat::Bc5 => bcn::decode_bc5_block,
+            TextureFormat::Bc6Signed => bcn::decode_bc6_block_signed,
+            TextureFormat::Bc6Unsigned => bcn::decode_bc6_block_unsigned,
+            TextureFormat::Bc7 => bcn::decode_bc7_block,
+            TextureFormat::Dxt2 => bcn::decode_dxt2_block,
+            TextureFormat::Dxt4 => bcn::decode_dxt4_block,
+            TextureFormat::Etc1 => etc::decode_etc1_block,
+            TextureFormat::Etc2Rgb => etc::decode_etc2_rgb_block,
+            TextureFormat::Etc2Rgba1 => etc::decode_etc2_rgba1_block,
+            TextureFormat::Etc2Rgba8 => etc::decode_etc2_rgba8_block,
+            TextureFormat::EacR => etc::decode_eacr_block,
+            TextureFormat::EacRSigned => etc::decode_eacr_signed_block,
+            TextureFormat::EacRg => etc::decode_eacrg_block,
+            TextureFormat::EacRgSigned => etc::decode_eacrg_signed_block,
+            TextureFormat::Fxt1 => fxt1::decode_fxt1_block,
+            TextureFormat::Pvrtc2bpp => {
+                return pvrtc::decode_pvrtc_flipped(data, width, height, image, true, flip)
+            }
+            TextureFormat::Pvrtc4bpp => {
+                return pvrtc::decode_pvrtc_flipped(data, width, height, image, false, flip)
+            }
+            // pixels are decoded as 1x1 blocks
+            TextureFormat::Raw(format, endian) => {
+                return decode_blocks(
+                    data,
+                    width,
+                    height,
+                    1,
+                    1,
+                    format.bytes_per_pixel(),
+                    image,
+                    flip,
+                    |pixel, outbuf| outbuf[0] = decode_raw_pixel(pixel, format, endian),
+                )
+            }
+        };
+        let (bw, bh) = self.block_size();
+        decode_blocks(
+            data,
+            width,
+            height,
+            bw,
+            bh,
+            self.block_bytes(),
+            image,
+            flip,
+            decode_block,
+        )
+    }
+
+    /// Decodes a texture whose colors are stored in the given color space into an image,
+    /// the image receives the stored values. ASTC uses the sRGB decode mode for sRGB data.
+    pub fn decode_with_color_space(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        color_space: ColorSpace,
+        image: &mut [u32],
+    ) -> Result<(), &'static str> {
+        match (*self, color_space) {
+            (TextureFormat::Astc(bw, bh), ColorSpace::Srgb) => {
+                astc::decode_astc_srgb(data, width, height, bw as usize, bh as usize, image)
+            }
+            _ => self.decode(data, width, height, image),
+        }
+    }
+
+    /// Decodes a texture whose colors are stored in the given color space into an image mirrored by flip,
+    /// see [`TextureFormat::decode_with_color_space()`].
+    pub fn decode_with_color_space_flipped(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        color_space: ColorSpace,
+        flip: Flip,
+        image: &mut [u32],
+    ) -> Result<(), &'static str> {
+        match (*self, color_space) {
+            (TextureFormat::Astc(bw, bh), ColorSpace::Srgb) => astc::decode_astc_srgb_flipped(
+                data,
+                width,
+                height,
+                bw as usize,
+                bh as usize,
+                image,
+                flip,
+            ),
+            _ => self.decode_flipped(data, width, height, flip, image),
+        }
+    }
+
+    /// Decodes a texture of this format into a newly allocated image
+    #[cfg(feature = "alloc")]
+    pub fn decode_image(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+    ) -> Result<DecodedImage, &'static str> {
+        let mut image = alloc::vec![0; width * height];
+        self.decode(data, width, height, &mut image)?;
+        Ok(DecodedImage {
+            width,
+            height,
+            image,
+        })
+    }
+
+    /// Decodes a texture of this format into a newly allocated image mirrored by flip
+    #[cfg(feature = "alloc")]
+    pub fn decode_image_flipped(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        flip: Flip,
+    ) -> Result<DecodedImage, &'static str> {
+        let mut image = alloc::vec![0; width * height];
+        self.decode_flipped(data, width, height, flip, &mut image)?;
+        Ok(DecodedImage {
+            width,
+            height,
+            image,
+        })
+    }
+}
+
+/// A decoded image, the pixels are stored row by row in the same format as the image buffers of the decode functions.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedImage {
+    pub width: usize,
+    pub height: usize,
+    pub image: alloc::vec::Vec<u32>,
+}
@@ -0,0 +1,142 @@
+//! Decoding of the FXT1 format of 3dfx (GL_3DFX_texture_compression_FXT1).
+//!
+//! The 128 bit blocks cover 8x4 pixels, the top bits select one of the CC_HI, CC_CHROMA,
+//! CC_MIXED and CC_ALPHA modes. Except for CC_HI the block is split into two 4x4 halves.
+
+use crate::color::color;
+use crate::macros::block_decoder;
+use core::result::Result;
+
+#[inline]
+const fn bits(block: u128, offset: u32, count: u32) -> u32 {
+    ((block >> offset) as u32) & ((1 << count) - 1)
+}
+
+#[inline]
+const fn expand5(v: u32) -> u8 {
+    ((v * 255 + 15) / 31) as u8
+}
+
+#[inline]
+const fn expand6(v: u32) -> u8 {
+    ((v * 255 + 31) / 63) as u8
+}
+
+/// Reads a RGB555 color, stored as blue in the lowest bits.
+#[inline]
+const fn rgb555(block: u128, offset: u32) -> [u8; 3] {
+    [
+        expand5(bits(block, offset + 10, 5)),
+        expand5(bits(block, offset + 5, 5)),
+        expand5(bits(block, offset, 5)),
+    ]
+}
+
+/// Reads a RGB565 color with 5 bits of green in the block and the lowest bit of green given separately.
+#[inline]
+const fn rgb565(block: u128, offset: u32, green_lsb: u32) -> [u8; 3] {
+    [
+        expand5(bits(block, offset + 10, 5)),
+        expand6(bits(block, offset + 5, 5) << 1 | green_lsb),
+        expand5(bits(block, offset, 5)),
+    ]
+}
+
+#[inline]
+fn lerp(n: u32, t: u32, c0: [u8; 4], c1: [u8; 4]) -> u32 {
+    let mix = |a: u8, b: u8| (((n - t) * a as u32 + t * b as u32 + n / 2) / n) as u8;
+    color(
+        mix(c0[0], c1[0]),
+        mix(c0[1], c1[1]),
+        mix(c0[2], c1[2]),
+        mix(c0[3], c1[3]),
+    )
+}
+
+#[inline]
+const fn opaque(c: [u8; 3]) -> [u8; 4] {
+    [c[0], c[1], c[2], 255]
+}
+
+#[inline]
+const fn to_color(c: [u8; 4]) -> u32 {
+    color(c[0], c[1], c[2], c[3])
+}
+
+// Decodes the texel t of a block, 0-15 are the pixels of the left 4x4 half, 16-31 of the right half.
+#[inline]
+fn decode_texel(block: u128, mode: u32, t: u32) -> u32 {
+    let (half, i) = (t >> 4, t & 15);
+    match mode {
+        // CC_HI: 3 bit indices into 7 interpolated RGB555 colors and transparent black
+        0 | 1 => {
+            let index = bits(block, t * 3, 3);
+            let (c0, c1) = (opaque(rgb555(block, 96)), opaque(rgb555(block, 111)));
+            match index {
+                7 => 0,
+                _ => lerp(6, index, c0, c1),
+            }
+        }
+        // CC_CHROMA: 2 bit indices into 4 RGB555 colors
+        2 => {
+            let index = bits(block, half * 32 + i * 2, 2);
+            to_color(opaque(rgb555(block, 64 + index * 15)))
+        }
+        // CC_ALPHA: 2 bit indices into ARGB5555 colors
+        3 => {
+            let index = bits(block, half * 32 + i * 2, 2);
+            let argb = |c: u32| {
+                let [r, g, b] = rgb555(block, 64 + c * 15);
+                [r, g, b, expand5(bits(block, 109 + c * 5, 5))]
+            };
+            if bits(block, 124, 1) != 0 {
+                // interpolated between color 0 or 2 and color 1
+                lerp(3, index, argb(half * 2), argb(1))
+            } else if index == 3 {
+                0
+            } else {
+                to_color(argb(index))
+            }
+        }
+        // CC_MIXED: 2 bit indices into colors interpolated between two RGB565 colors per half
+        _ => {
+            let index = bits(block, half * 32 + i * 2, 2);
+            let (offset, green_lsb, select_lsb) = match half {
+                0 => (64, bits(block, 125, 1), bits(block, 1, 1)),
+                _ => (94, bits(block, 126, 1), bits(block, 33, 1)),
+            };
+            let c1 = opaque(rgb565(block, offset + 15, green_lsb));
+            if bits(block, 124, 1) != 0 {
+                // 1 bit alpha, the green of the first color is expanded from 5 bits
+                let c0 = opaque(rgb555(block, offset));
+                match index {
+                    0 => to_color(c0),
+                    1 => {
+                        let mix = |a: u8, b: u8| ((a as u32 + b as u32) / 2) as u8;
+                        color(mix(c0[0], c1[0]), mix(c0[1], c1[1]), mix(c0[2], c1[2]), 255)
+                    }
+                    2 => to_color(c1),
+                    _ => 0,
+                }
+            } else {
+                let c0 = opaque(rgb565(block, offset, green_lsb ^ select_lsb));
+                lerp(3, index, c0, c1)
+            }
+        }
+    }
+}
+
+/// Decodes a 8x4 FXT1 block into a buffer.
+#[inline]
+pub fn decode_fxt1_block(data: &[u8], outbuf: &mut [u32]) {
+    let block = u128::from_le_bytes(data[0..16].try_into().unwrap());
+    let mode = bits(block, 125, 3);
+    (0..4).for_each(|y| {
+        (0..8).for_each(|x| {
+            let t = (x & 3) + (x & 4) * 4 + y * 4;
+            outbuf[y as usize * 8 + x as usize] = decode_texel(block, mode, t);
+        });
+    });
+}
+
+block_decoder!("fxt1", 8, 4, 16, decode_fxt1_block);
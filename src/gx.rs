@@ -0,0 +1,301 @@
+//! Decoding of the GX texture formats of the GameCube and Wii.
+//!
+//! The textures are stored in tiles of 32 bytes (64 bytes for RGBA8), which cover 4x4, 8x4 or 8x8 pixels,
+//! the tiles and the multi-byte values are stored in big endian order.
+
+use crate::bcn::decode_bc1a_block;
+use crate::color::{color, copy_block_buffer, rgb565_le, Flip};
+use crate::palette::{decode_palette_color, PaletteFormat};
+use core::result::Result;
+
+/// The GX texture formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GxFormat {
+    /// 4 bit intensity, copied to all channels
+    I4,
+    /// 8 bit intensity, copied to all channels
+    I8,
+    /// 4 bit alpha and 4 bit intensity
+    Ia4,
+    /// 8 bit alpha and 8 bit intensity
+    Ia8,
+    Rgb565,
+    /// opaque RGB555 if the top bit is set, otherwise ARGB3444
+    Rgb5a3,
+    /// 32 bit color, stored as alpha/red and green/blue halves of a tile
+    Rgba8,
+    /// 4 bit palette indices
+    C4,
+    /// 8 bit palette indices
+    C8,
+    /// 14 bit palette indices
+    C14x2,
+    /// DXT1 in tiles of 2x2 blocks
+    Cmpr,
+}
+
+impl GxFormat {
+    /// Maps a `GXTexFmt` value to the format.
+    pub const fn from_u32(value: u32) -> Option<GxFormat> {
+        let format = match value {
+            0 => GxFormat::I4,
+            1 => GxFormat::I8,
+            2 => GxFormat::Ia4,
+            3 => GxFormat::Ia8,
+            4 => GxFormat::Rgb565,
+            5 => GxFormat::Rgb5a3,
+            6 => GxFormat::Rgba8,
+            8 => GxFormat::C4,
+            9 => GxFormat::C8,
+            10 => GxFormat::C14x2,
+            14 => GxFormat::Cmpr,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// The width and height of a tile in pixels.
+    pub const fn tile_size(&self) -> (usize, usize) {
+        match self {
+            GxFormat::I4 | GxFormat::C4 | GxFormat::Cmpr => (8, 8),
+            GxFormat::I8 | GxFormat::Ia4 | GxFormat::C8 => (8, 4),
+            _ => (4, 4),
+        }
+    }
+
+    /// The size of a tile in bytes.
+    pub const fn tile_bytes(&self) -> usize {
+        match self {
+            GxFormat::Rgba8 => 64,
+            _ => 32,
+        }
+    }
+
+    /// true for the formats which index a palette (TLUT)
+    pub const fn is_indexed(&self) -> bool {
+        matches!(self, GxFormat::C4 | GxFormat::C8 | GxFormat::C14x2)
+    }
+
+    /// The size of the data of a width * height texture in bytes, padded to whole tiles.
+    pub const fn data_size(&self, width: usize, height: usize) -> usize {
+        let (tile_width, tile_height) = self.tile_size();
+        width.div_ceil(tile_width) * height.div_ceil(tile_height) * self.tile_bytes()
+    }
+}
+
+/// The formats of the palette (TLUT) entries, stored in big endian order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GxTlutFormat {
+    /// 8 bit alpha and 8 bit intensity
+    Ia8,
+    Rgb565,
+    /// opaque RGB555 if the top bit is set, otherwise ARGB3444
+    Rgb5a3,
+}
+
+impl GxTlutFormat {
+    /// Maps a `GXTlutFmt` value to the format.
+    pub const fn from_u32(value: u32) -> Option<GxTlutFormat> {
+        match value {
+            0 => Some(GxTlutFormat::Ia8),
+            1 => Some(GxTlutFormat::Rgb565),
+            2 => Some(GxTlutFormat::Rgb5a3),
+            _ => None,
+        }
+    }
+}
+
+#[inline]
+fn decode_16bit_color(data: &[u8], format: GxTlutFormat) -> u32 {
+    match format {
+        GxTlutFormat::Ia8 => color(data[1], data[1], data[1], data[0]),
+        GxTlutFormat::Rgb565 => {
+            let (r, g, b) = rgb565_le(u16::from_be_bytes([data[0], data[1]]));
+            color(r, g, b, 255)
+        }
+        GxTlutFormat::Rgb5a3 => decode_palette_color(data, PaletteFormat::Rgb5a3),
+    }
+}
+
+/// Decodes a 4x4 block of a CMPR texture into a buffer.
+/// The block is a DXT1 block with big endian endpoints and the indices of each row in reversed order.
+#[inline]
+pub fn decode_gx_cmpr_block(data: &[u8], outbuf: &mut [u32]) {
+    let mut block = [0u8; 8];
+    block[0..4].copy_from_slice(&[data[1], data[0], data[3], data[2]]);
+    (4..8).for_each(|i| {
+        let v = data[i];
+        block[i] = v >> 6 | (v >> 2 & 0x0c) | (v << 2 & 0x30) | v << 6;
+    });
+    decode_bc1a_block(&block, outbuf);
+}
+
+// Decodes a tile into a buffer of the tile size, index_color maps the palette indices to colors.
+fn decode_tile(
+    format: GxFormat,
+    tile: &[u8],
+    outbuf: &mut [u32; 64],
+    index_color: impl Fn(usize) -> u32,
+) {
+    match format {
+        GxFormat::I4 => (0..64).for_each(|i| {
+            let v = (tile[i >> 1] >> (if i & 1 == 0 { 4 } else { 0 }) & 0xf) * 0x11;
+            outbuf[i] = color(v, v, v, v);
+        }),
+        GxFormat::I8 => (0..32).for_each(|i| outbuf[i] = color(tile[i], tile[i], tile[i], tile[i])),
+        GxFormat::Ia4 => (0..32).for_each(|i| {
+            let (a, v) = ((tile[i] >> 4) * 0x11, (tile[i] & 0xf) * 0x11);
+            outbuf[i] = color(v, v, v, a);
+        }),
+        GxFormat::Ia8 => (0..16).for_each(|i| {
+            outbuf[i] = decode_16bit_color(&tile[i * 2..], GxTlutFormat::Ia8);
+        }),
+        GxFormat::Rgb565 => (0..16).for_each(|i| {
+            outbuf[i] = decode_16bit_color(&tile[i * 2..], GxTlutFormat::Rgb565);
+        }),
+        GxFormat::Rgb5a3 => (0..16).for_each(|i| {
+            outbuf[i] = decode_16bit_color(&tile[i * 2..], GxTlutFormat::Rgb5a3);
+        }),
+        GxFormat::Rgba8 => (0..16).for_each(|i| {
+            let (ar, gb) = (&tile[i * 2..], &tile[32 + i * 2..]);
+            outbuf[i] = color(ar[1], gb[0], gb[1], ar[0]);
+        }),
+        GxFormat::C4 => (0..64).for_each(|i| {
+            outbuf[i] =
+                index_color((tile[i >> 1] >> (if i & 1 == 0 { 4 } else { 0 }) & 0xf) as usize);
+        }),
+        GxFormat::C8 => (0..32).for_each(|i| outbuf[i] = index_color(tile[i] as usize)),
+        GxFormat::C14x2 => (0..16).for_each(|i| {
+            outbuf[i] =
+                index_color((u16::from_be_bytes([tile[i * 2], tile[i * 2 + 1]]) & 0x3fff) as usize);
+        }),
+        GxFormat::Cmpr => {
+            let mut buffer = [0u32; 16];
+            (0..4).for_each(|b| {
+                decode_gx_cmpr_block(&tile[b * 8..], &mut buffer);
+                let (x0, y0) = ((b & 1) * 4, (b >> 1) * 4);
+                (0..4).for_each(|y| {
+                    let offset = (y0 + y) * 8 + x0;
+                    outbuf[offset..offset + 4].copy_from_slice(&buffer[y * 4..y * 4 + 4]);
+                });
+            });
+        }
+    }
+}
+
+fn decode_tiled(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    format: GxFormat,
+    flip: Flip,
+    index_color: impl Fn(usize) -> u32,
+) -> Result<(), &'static str> {
+    if data.len() < format.data_size(width, height) {
+        return Err("Not enough data to decode image!");
+    }
+    if image.len() < width * height {
+        return Err("Image buffer is too small!");
+    }
+
+    let (tile_width, tile_height) = format.tile_size();
+    let num_tiles_x = width.div_ceil(tile_width);
+    let mut buffer = [0u32; 64];
+    data.chunks_exact(format.tile_bytes())
+        .take(num_tiles_x * height.div_ceil(tile_height))
+        .enumerate()
+        .for_each(|(i, tile)| {
+            decode_tile(format, tile, &mut buffer, &index_color);
+            copy_block_buffer(
+                i % num_tiles_x,
+                i / num_tiles_x,
+                width,
+                height,
+                tile_width,
+                tile_height,
+                &buffer,
+                image,
+                flip,
+            );
+        });
+    Ok(())
+}
+
+/// Decodes a GX texture into an image, the indexed formats require [`decode_gx_indexed()`].
+pub fn decode_gx(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    format: GxFormat,
+) -> Result<(), &'static str> {
+    decode_gx_flipped(data, width, height, image, format, Flip::NONE)
+}
+
+/// Decodes a GX texture into an image mirrored by flip.
+pub fn decode_gx_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    format: GxFormat,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    if format.is_indexed() {
+        return Err("Indexed GX formats require a palette!");
+    }
+    decode_tiled(data, width, height, image, format, flip, |_| 0)
+}
+
+/// Decodes a C4, C8 or C14X2 texture with its palette (TLUT) into an image.
+/// Indices outside of the palette are decoded as transparent black.
+pub fn decode_gx_indexed(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    format: GxFormat,
+    palette: &[u8],
+    tlut_format: GxTlutFormat,
+) -> Result<(), &'static str> {
+    decode_gx_indexed_flipped(
+        data,
+        width,
+        height,
+        image,
+        format,
+        palette,
+        tlut_format,
+        Flip::NONE,
+    )
+}
+
+/// Decodes a C4, C8 or C14X2 texture with its palette (TLUT) into an image mirrored by flip.
+#[allow(clippy::too_many_arguments)]
+pub fn decode_gx_indexed_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    format: GxFormat,
+    palette: &[u8],
+    tlut_format: GxTlutFormat,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    if !format.is_indexed() {
+        return Err("The GX format isn't indexed!");
+    }
+    decode_tiled(
+        data,
+        width,
+        height,
+        image,
+        format,
+        flip,
+        |index| match palette.get(index * 2..index * 2 + 2) {
+            Some(entry) => decode_16bit_color(entry, tlut_format),
+            None => 0,
+        },
+    )
+}
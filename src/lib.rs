@@ -3,7 +3,11 @@
 //! - [ASTC - Adaptive Scalable Texture Compression](https://en.wikipedia.org/wiki/Adaptive_Scalable_Texture_Compression)
 //! - [BCn - Block Compression](https://en.wikipedia.org/wiki/S3_Texture_Compression)
 //! - [ETC - Ericsson Texture Compression](https://en.wikipedia.org/wiki/Ericsson_Texture_Compression)
+//! - [FXT1 - 3dfx Texture Compression](https://registry.khronos.org/OpenGL/extensions/3DFX/3DFX_texture_compression_FXT1.txt)
 //! - [PVRTC - PowerVR Texture Compression](https://en.wikipedia.org/wiki/PVRTC)
+//! - uncompressed and packed pixel formats, e.g. RGB565, RGBA4444, RGBA32 or RGBAHalf
+//! - YUV - packed (YUY2, UYVY) and planar (NV12, I420)
+//! - palettized textures with 4 and 8 bit indices
 //! - [Crunch](https://github.com/BinomialLLC/crunch) & [Unity's Crunch](https://github.com/Unity-Technologies/crunch)
 //!
 //! ## Functions
@@ -20,23 +24,36 @@
 //!     // image: the buffer to write the decoded image to, expected to be block_size in size
 //! ```
 //! The exceptions are:
-//! - ASTC: the (block) decode function takes the block size as an additional parameter
+//! - ASTC: the (block) decode function takes the block size as an additional parameter, the 3D variants additionally take the depth of the texture and block
 //! - BC6: there are two additional decode functions for the signed and unsigned variants
+//! - DXT2 & DXT4: the color is un-premultiplied by the alpha, the premultiplied variants keep the stored color
 //! - PVRTC: the decode function takes the block size as an additional parameter, and there are two additional decode functions for the 2bpp and 4bpp variants
+//! - 3DS ETC1 & ETC1A4: the blocks are little endian and the decode functions undo the 8x8 tiling and the vertical flip of the 3DS
+//! - Raw: the decode function takes the pixel format and the byte order as additional parameters, there's no block decode function
+//! - YUV: the decode functions take the color matrix and the value range as additional parameters, there's no block decode function
+//! - Palette: the decode functions take the palette, its format and the index layout as additional parameters, there's no block decode function
 //! - Crunch & Unity's Crunch: The texture's dimensions and metadata are stored in the file itself, one's must parse the header with crnd_get_texture_info() from crn_texture_info struct first, then pass the metadata to the decoder as in the format. There's no block decomp. function.
-//! 
+//!
 //! To make these excetions easier to use, there are helper functions to enable decode functions with identical arguments and returns.
-//! 
+//!
 //! Here is a list of the formats and their corresponding functions:
 //! - ATC
 //!   - [`decode_atc_rgb4()`]
 //!   - [`decode_atc_rgb4_block()`]
 //!   - [`decode_atc_rgba8()`]
 //!   - [`decode_atc_rgba8_block()`]
+//!   - [`decode_atc_rgba8_explicit()`]
+//!   - [`decode_atc_rgba8_explicit_block()`]
 //! - ASTC
 //!   - [`decode_astc()`]
 //!   - [`decode_astc_block()`]
+//!   - [`decode_astc_srgb()`]
+//!   - [`decode_astc_srgb_block()`]
 //!   - various decode_astc_(block_)_x_y functions, where x and y are the block size
+//!   - [`decode_astc_3d()`]
+//!   - [`decode_astc_3d_srgb()`]
+//!   - [`decode_astc_3d_block()`]
+//!   - various decode_astc_x_y_z functions for 3D textures, where x, y and z are the block size
 //! - BCn
 //!   - [`decode_bc1()`]
 //!   - [`decode_bc1_block()`]
@@ -54,6 +71,14 @@
 //!   - [`decode_bc6_block_unsigned()`]
 //!   - [`decode_bc7()`]
 //!   - [`decode_bc7_block()`]
+//!   - [`decode_dxt2()`]
+//!   - [`decode_dxt2_block()`]
+//!   - [`decode_dxt2_premultiplied()`]
+//!   - [`decode_dxt2_premultiplied_block()`]
+//!   - [`decode_dxt4()`]
+//!   - [`decode_dxt4_block()`]
+//!   - [`decode_dxt4_premultiplied()`]
+//!   - [`decode_dxt4_premultiplied_block()`]
 //! - ETC
 //!   - [`decode_etc1()`]
 //!   - [`decode_etc1_block()`]
@@ -69,14 +94,88 @@
 //!   - [`decode_eacr_signed_block()`]
 //!   - [`decode_eacrg()`]
 //!   - [`decode_eacrg_block()`]
+//!   - [`decode_etc1_3ds()`]
+//!   - [`decode_etc1_3ds_block()`]
+//!   - [`decode_etc1_3ds_flipped()`]
+//!   - [`decode_etc1a4_3ds()`]
+//!   - [`decode_etc1a4_3ds_block()`]
+//!   - [`decode_etc1a4_3ds_flipped()`]
+//! - FXT1
+//!   - [`decode_fxt1()`]
+//!   - [`decode_fxt1_block()`]
 //! - PVRTC
 //!   - [`decode_pvrtc()`]
 //!   - [`decode_pvrtc_2bpp()`]
 //!   - [`decode_pvrtc_4bpp()`]
+//! - Raw
+//!   - [`decode_raw()`]
+//!   - [`decode_raw_pixel()`]
+//! - YUV
+//!   - [`decode_yuy2()`]
+//!   - [`decode_uyvy()`]
+//!   - [`decode_nv12()`]
+//!   - [`decode_i420()`]
+//! - Palette
+//!   - [`decode_p4()`]
+//!   - [`decode_p8()`]
+//!   - [`decode_palette_color()`]
 //! - Crunch
 //!   - [`decode_crunch()`]
 //! - Unity Crunch
 //!   - [`decode_unity_crunch()`]
+//! - GameCube & Wii: the [`gx`] module decodes the GX texture formats
+//!   - [`gx::decode_gx()`]
+//!   - [`gx::decode_gx_indexed()`]
+//!   - [`gx::decode_gx_cmpr_block()`]
+//!
+//! ## Containers
+//! The [`container`] module parses texture container files and maps their formats to a [`TextureFormat`],
+//! which can decode the texture data with [`TextureFormat::decode()`].
+//! - DDS: [`container::dds`], [`container::dds::decode_dds()`]
+//! - KTX 1: [`container::ktx`], [`container::ktx::decode_ktx()`]
+//! - KTX 2: [`container::ktx2`], [`container::ktx2::decode_ktx2()`], zlib supercompressed levels require alloc
+//! - PVR v2 and v3: [`container::pvr`], [`container::pvr::decode_pvr()`]
+//! - .astc: [`container::astc`], [`container::astc::decode_astc_file()`]
+//! - PKM: [`container::pkm`], [`container::pkm::decode_pkm()`]
+//! - Basis Universal: [`container::basis`], only the header
+//! - GNF: [`container::gnf`], [`container::gnf::decode_gnf()`], textures in the linear, 1D and 2D thin tile modes of the PlayStation 4
+//!
+//! [`container::detect()`] recognizes the containers and crunch files by their magic bytes,
+//! [`container::decode_any()`] decodes the first image of a detected file.
+//!
+//! ## Graphics API formats
+//! The [`api`] module maps the format enums of DXGI, Vulkan, OpenGL and Metal to [`FormatDescriptor`]s and back.
+//!
+//! ## Color spaces
+//! The decoders output the stored values. [`FormatDescriptor::decode()`] decodes the sRGB formats,
+//! including the sRGB decode mode of ASTC, and converts the colors to the requested [`ColorSpace`].
+//! [`srgb_to_linear()`], [`linear_to_srgb()`] and [`convert_color_space()`] convert decoded colors.
+//! The decoders of the [`container`] module decode the sRGB formats of the file with the sRGB decode mode,
+//! e.g. [`container::ktx2::Ktx2File::color_space()`].
+//!
+//! ## Flipping
+//! [`TextureFormat::decode_flipped()`] mirrors the image vertically and/or horizontally by a [`Flip`]
+//! while the blocks are written, e.g. for textures with a bottom-left origin,
+//! [`gx::decode_gx_flipped()`] and [`gx::decode_gx_indexed_flipped()`] do the same for the GX formats.
+//! The other decoders and the console layouts have `_flipped` variants too, e.g. [`decode_astc_srgb_flipped()`],
+//! [`decode_nv12_flipped()`], [`decode_p8_flipped()`], [`decode_crunch_flipped()`] and [`swizzle::tegra::decode_tegra_flipped()`].
+//! The KTX, KTX2 and PVR decoders of the [`container`] module honor the orientation stored in the file
+//! and return top-down images, e.g. [`container::ktx::KtxFile::orientation()`].
+//!
+//! ## Console layouts
+//! The [`swizzle`] module converts the tiled and swizzled layouts of game consoles to the linear block order of the decoders.
+//! - Nintendo Switch: [`swizzle::tegra`], [`swizzle::tegra::decode_tegra()`]
+//! - PlayStation 4: [`swizzle::gnm`], [`swizzle::gnm::GnmSurface::decode_mip()`]
+//! - Xbox 360: [`swizzle::xenos`], [`swizzle::xenos::decode_xenos()`]
+//! - PS Vita: [`swizzle::vita`], [`swizzle::vita::decode_vita()`]
+//! - PSP: [`swizzle::psp`], [`swizzle::psp::deswizzle_psp()`]
+//! - Wii U: [`swizzle::gx2`], [`swizzle::gx2::Gx2Surface::decode_mip()`]
+//!
+//! ## Engines
+//! - Unity: [`unity`], [`unity::decode_unity_texture()`] decodes Texture2D data by its `TextureFormat` id,
+//!   [`unity::decode_unity_texture_flipped()`] returns the bottom-up textures of Unity top-down,
+//!   [`unity::decode_unity_split_alpha()`] combines a texture with its separate alpha texture
+//! - Unreal Engine: [`unreal`], [`unreal::decode_unreal_texture()`] decodes texture data by its `EPixelFormat`
 //!
 #![no_std]
 
@@ -88,20 +187,40 @@ mod macros;
 mod astc;
 mod atc;
 mod bcn;
+mod colorspace;
 mod crnlib;
 #[cfg(feature = "alloc")]
 mod crunch;
 mod etc;
+mod format;
+mod fxt1;
+mod palette;
 mod pvrtc;
+mod raw;
 #[cfg(feature = "alloc")]
 mod unitycrunch;
+mod yuv;
+
+pub mod api;
+pub mod container;
+pub mod gx;
+pub mod swizzle;
+pub mod unity;
+pub mod unreal;
 
 // import decode functions
 pub use astc::*;
 pub use atc::*;
 pub use bcn::*;
+pub use color::Flip;
+pub use colorspace::*;
 pub use crnlib::CrnTextureInfo;
-pub use crunch::decode_crunch;
+pub use crunch::{decode_crunch, decode_crunch_flipped};
 pub use etc::*;
+pub use format::*;
+pub use fxt1::*;
+pub use palette::*;
 pub use pvrtc::*;
-pub use unitycrunch::decode_unity_crunch;
+pub use raw::*;
+pub use unitycrunch::{decode_unity_crunch, decode_unity_crunch_flipped};
+pub use yuv::*;
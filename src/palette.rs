@@ -0,0 +1,225 @@
+use crate::color::{color, rgb565_le, Flip};
+use core::result::Result;
+
+/// Formats of the palette entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// 4 bytes in r, g, b, a order
+    Rgba8888,
+    /// little endian, red in the highest 5 bits
+    Rgb565,
+    /// big endian GameCube/Wii format, opaque RGB555 if the top bit is set, otherwise ARGB3444
+    Rgb5a3,
+    /// little endian, red in the lowest 5 bits and alpha in the top bit, as used by the PS2, PSP and DS
+    Rgba5551,
+}
+
+impl PaletteFormat {
+    pub const fn bytes_per_entry(&self) -> usize {
+        match self {
+            PaletteFormat::Rgba8888 => 4,
+            PaletteFormat::Rgb565 | PaletteFormat::Rgb5a3 | PaletteFormat::Rgba5551 => 2,
+        }
+    }
+}
+
+/// Order of the two 4 bit indices within a byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NibbleOrder {
+    /// the first pixel is stored in the low nibble (PS2, PSP, DS)
+    LowFirst,
+    /// the first pixel is stored in the high nibble (GameCube, Wii)
+    HighFirst,
+}
+
+#[inline]
+const fn expand_5bit(v: u16) -> u8 {
+    let v = (v & 0x1f) as u8;
+    v << 3 | v >> 2
+}
+
+#[inline]
+const fn expand_4bit(v: u16) -> u8 {
+    (v & 0xf) as u8 * 0x11
+}
+
+/// Decodes a single palette entry.
+#[inline]
+pub fn decode_palette_color(data: &[u8], format: PaletteFormat) -> u32 {
+    match format {
+        PaletteFormat::Rgba8888 => color(data[0], data[1], data[2], data[3]),
+        PaletteFormat::Rgb565 => {
+            let (r, g, b) = rgb565_le(u16::from_le_bytes([data[0], data[1]]));
+            color(r, g, b, 255)
+        }
+        PaletteFormat::Rgb5a3 => {
+            let v = u16::from_be_bytes([data[0], data[1]]);
+            if v & 0x8000 != 0 {
+                color(
+                    expand_5bit(v >> 10),
+                    expand_5bit(v >> 5),
+                    expand_5bit(v),
+                    255,
+                )
+            } else {
+                let a = (v >> 12 & 7) as u8;
+                color(
+                    expand_4bit(v >> 8),
+                    expand_4bit(v >> 4),
+                    expand_4bit(v),
+                    a << 5 | a << 2 | a >> 1,
+                )
+            }
+        }
+        PaletteFormat::Rgba5551 => {
+            let v = u16::from_le_bytes([data[0], data[1]]);
+            color(
+                expand_5bit(v),
+                expand_5bit(v >> 5),
+                expand_5bit(v >> 10),
+                if v & 0x8000 != 0 { 255 } else { 0 },
+            )
+        }
+    }
+}
+
+// The PS2 CSM1 storage mode swaps the entries 8-15 and 16-23 of every 32 entries of a 256 color palette.
+// Entries whose swapped source lies beyond a truncated palette stay transparent black.
+#[inline]
+const fn csm1_index(i: usize) -> usize {
+    (i & !0x18) | (i & 0x08) << 1 | (i & 0x10) >> 1
+}
+
+fn load_palette(palette: &[u8], format: PaletteFormat, ps2_csm1: bool, colors: &mut [u32; 256]) {
+    let bpe = format.bytes_per_entry();
+    let num_colors = (palette.len() / bpe).min(256);
+    (0..num_colors).for_each(|i| {
+        let src = if ps2_csm1 && num_colors > 16 {
+            csm1_index(i)
+        } else {
+            i
+        };
+        if src < num_colors {
+            colors[i] = decode_palette_color(&palette[src * bpe..], format);
+        }
+    });
+}
+
+/// Decodes a texture with 4 bit indices into a palette of up to 16 colors into an image.
+/// The indices of the pixels are stored continuously, indices outside of the palette are decoded as transparent black.
+pub fn decode_p4(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    palette: &[u8],
+    palette_format: PaletteFormat,
+    nibble_order: NibbleOrder,
+) -> Result<(), &'static str> {
+    decode_p4_flipped(
+        data,
+        width,
+        height,
+        image,
+        palette,
+        palette_format,
+        nibble_order,
+        Flip::NONE,
+    )
+}
+
+/// Decodes a texture with 4 bit indices into a palette of up to 16 colors into an image mirrored by flip.
+#[allow(clippy::too_many_arguments)]
+pub fn decode_p4_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    palette: &[u8],
+    palette_format: PaletteFormat,
+    nibble_order: NibbleOrder,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    let num_pixels = width * height;
+    if data.len() < num_pixels.div_ceil(2) {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < num_pixels {
+        return Err("Image buffer is too small!");
+    }
+
+    let mut colors: [u32; 256] = [0; 256];
+    load_palette(palette, palette_format, false, &mut colors);
+
+    let (first_shift, second_shift) = match nibble_order {
+        NibbleOrder::LowFirst => (0, 4),
+        NibbleOrder::HighFirst => (4, 0),
+    };
+    (0..num_pixels).for_each(|i| {
+        let shift = if i & 1 == 0 {
+            first_shift
+        } else {
+            second_shift
+        };
+        image[flip.pixel_index(i % width, i / width, width, height)] =
+            colors[(data[i >> 1] >> shift & 0xf) as usize];
+    });
+    Ok(())
+}
+
+/// Decodes a texture with 8 bit indices into a palette of up to 256 colors into an image.
+/// Indices outside of the palette are decoded as transparent black.
+pub fn decode_p8(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    palette: &[u8],
+    palette_format: PaletteFormat,
+    ps2_csm1: bool,
+) -> Result<(), &'static str> {
+    decode_p8_flipped(
+        data,
+        width,
+        height,
+        image,
+        palette,
+        palette_format,
+        ps2_csm1,
+        Flip::NONE,
+    )
+}
+
+/// Decodes a texture with 8 bit indices into a palette of up to 256 colors into an image mirrored by flip.
+#[allow(clippy::too_many_arguments)]
+pub fn decode_p8_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    palette: &[u8],
+    palette_format: PaletteFormat,
+    ps2_csm1: bool,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    let num_pixels = width * height;
+    if data.len() < num_pixels {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < num_pixels {
+        return Err("Image buffer is too small!");
+    }
+
+    let mut colors: [u32; 256] = [0; 256];
+    load_palette(palette, palette_format, ps2_csm1, &mut colors);
+
+    data[..num_pixels]
+        .iter()
+        .enumerate()
+        .for_each(|(i, index)| {
+            image[flip.pixel_index(i % width, i / width, width, height)] = colors[*index as usize];
+        });
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use crate::color::{color, copy_block_buffer};
+use crate::color::{color, copy_block_buffer, Flip};
+use crate::swizzle::morton_index;
 use core::result::Result;
 
 #[derive(Clone, Copy)]
@@ -61,20 +62,6 @@ impl PVRTCTexelInfo {
 static PVRTC1_STANDARD_WEIGHT: [i8; 4] = [0, 3, 5, 8];
 static PVRTC1_PUNCHTHROUGH_WEIGHT: [i8; 4] = [0, 4, 4, 8];
 
-#[inline]
-const fn morton_index(x: usize, y: usize, min_dim: usize) -> usize {
-    let mut offset: usize = 0;
-    let mut shift: usize = 0;
-    let mut mask: usize = 1;
-    while mask < min_dim {
-        offset |= ((y & mask) | ((x & mask) << 1)) << shift;
-        mask <<= 1;
-        shift += 1;
-    }
-    offset |= ((x | y) >> shift) << (shift * 2);
-    offset
-}
-
 fn get_texel_colors(data: &[u8], info: &mut PVRTCTexelInfo) {
     let ca: u16 = u16::from_le_bytes(data[4..6].try_into().unwrap());
     let cb: u16 = u16::from_le_bytes(data[6..8].try_into().unwrap());
@@ -368,13 +355,24 @@ fn applicate_color_2bpp(_data: &[u8], info: &mut [PVRTCTexelInfo; 9], buf: &mut
     }
 }
 
-#[cfg(feature = "alloc")]
 pub fn decode_pvrtc(
     data: &[u8],
     width: usize,
     height: usize,
     image: &mut [u32],
     is2bpp: bool,
+) -> Result<(), &'static str> {
+    decode_pvrtc_flipped(data, width, height, image, is2bpp, Flip::NONE)
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_pvrtc_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    is2bpp: bool,
+    flip: Flip,
 ) -> Result<(), &'static str> {
     extern crate alloc;
     use alloc::vec::Vec;
@@ -383,7 +381,6 @@ pub fn decode_pvrtc(
     let num_blocks_x: usize = width.div_ceil(block_width);
     let num_blocks_y: usize = height.div_ceil(4);
     let num_blocks: usize = num_blocks_x * num_blocks_y;
-    let min_num_blocks: usize = num_blocks_x.min(num_blocks_y);
 
     if data.len() < num_blocks * block_width {
         return Err("The data buffer is too small!");
@@ -434,39 +431,36 @@ pub fn decode_pvrtc(
             let mut c: usize = 0;
             for cy in 0..3 {
                 for cx in 0..3 {
-                    local_info[c] = texel_info[morton_index(pos_x[cx], pos_y[cy], min_num_blocks)];
+                    local_info[c] =
+                        texel_info[morton_index(pos_x[cx], pos_y[cy], num_blocks_x, num_blocks_y)];
                     c += 1;
                 }
             }
 
             applicate_color_func(
-                &data[morton_index(bx, by, min_num_blocks) * 8..],
+                &data[morton_index(bx, by, num_blocks_x, num_blocks_y) * 8..],
                 &mut local_info,
                 &mut buffer,
             );
-            copy_block_buffer(bx, by, width, height, block_width, 4, &buffer, image);
+            copy_block_buffer(bx, by, width, height, block_width, 4, &buffer, image, flip);
         }
     }
     Ok(())
 }
 
 #[cfg(not(feature = "alloc"))]
-pub fn decode_pvrtc(
+pub(crate) fn decode_pvrtc_flipped(
     data: &[u8],
     w: usize,
     h: usize,
     image: &mut [u32],
     is2bpp: bool,
+    flip: Flip,
 ) -> Result<(), &'static str> {
     let bw: usize = if is2bpp { 8 } else { 4 };
     let num_blocks_x: usize = if is2bpp { (w + 7) / 8 } else { (w + 3) / 4 };
     let num_blocks_y: usize = (h + 3) / 4;
     let num_blocks: usize = num_blocks_x * num_blocks_y;
-    let min_num_blocks: usize = if num_blocks_x <= num_blocks_y {
-        num_blocks_x
-    } else {
-        num_blocks_y
-    };
 
     if data.len() < num_blocks * block_width {
         return Err("The data buffer is too small!");
@@ -508,7 +502,7 @@ pub fn decode_pvrtc(
             (0..3).for_each(|cy| {
                 (0..3).for_each(|cx| {
                     let texel_info_offset: usize =
-                        morton_index(pos_x[cx], pos_y[cy], min_num_blocks) * 8;
+                        morton_index(pos_x[cx], pos_y[cy], num_blocks_x, num_blocks_y) * 8;
                     get_texel_colors(&data[texel_info_offset..], &mut local_info[c]);
                     get_texel_weights_func(&data[texel_info_offset..], &mut local_info[c]);
                     c += 1;
@@ -516,11 +510,11 @@ pub fn decode_pvrtc(
             });
 
             applicate_color_func(
-                &data[morton_index(bx, by, min_num_blocks) * 8..],
+                &data[morton_index(bx, by, num_blocks_x, num_blocks_y) * 8..],
                 &mut local_info,
                 &mut buffer,
             );
-            copy_block_buffer(bx, by, w, h, bw, 4, &buffer, image);
+            copy_block_buffer(bx, by, w, h, bw, 4, &buffer, image, flip);
         }
     }
 }
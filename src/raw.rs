@@ -0,0 +1,239 @@
+use crate::color::{color, f32_to_u8, rgb565_le};
+use crate::f16::fp16_ieee_to_fp32_value;
+use core::result::Result;
+
+/// Byte order of the multi-byte values of a pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Uncompressed and packed pixel formats.
+///
+/// The names describe the order of the channels in memory for byte formats,
+/// and from the most to the least significant bits for packed formats.
+/// Channels missing in a format are decoded as 0, alpha as 255.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RawFormat {
+    /// 8 bit alpha, the color is white
+    Alpha8,
+    R8,
+    /// 8 bit luminance, copied to red, green and blue
+    L8,
+    /// 16 bit red
+    R16,
+    /// 16 bit luminance, copied to red, green and blue
+    L16,
+    /// 8 bit red and 8 bit green
+    Rg16,
+    Rgb24,
+    Bgr24,
+    Rgba32,
+    Argb32,
+    Bgra32,
+    /// 32 bit blue, green, red, with the 4th byte ignored
+    Bgrx32,
+    Rgb565,
+    Rgba4444,
+    Argb4444,
+    Rgba5551,
+    Argb1555,
+    /// 32 bit value with red in the lowest 10 bits and alpha in the highest 2 bits
+    Rgb10A2,
+    RHalf,
+    RgHalf,
+    RgbaHalf,
+    RFloat,
+    RgFloat,
+    RgbaFloat,
+}
+
+impl RawFormat {
+    pub const fn bytes_per_pixel(&self) -> usize {
+        match self {
+            RawFormat::Alpha8 | RawFormat::R8 | RawFormat::L8 => 1,
+            RawFormat::R16
+            | RawFormat::L16
+            | RawFormat::Rg16
+            | RawFormat::Rgb565
+            | RawFormat::Rgba4444
+            | RawFormat::Argb4444
+            | RawFormat::Rgba5551
+            | RawFormat::Argb1555
+            | RawFormat::RHalf => 2,
+            RawFormat::Rgb24 | RawFormat::Bgr24 => 3,
+            RawFormat::Rgba32
+            | RawFormat::Argb32
+            | RawFormat::Bgra32
+            | RawFormat::Bgrx32
+            | RawFormat::Rgb10A2
+            | RawFormat::RgHalf
+            | RawFormat::RFloat => 4,
+            RawFormat::RgbaHalf | RawFormat::RgFloat => 8,
+            RawFormat::RgbaFloat => 16,
+        }
+    }
+}
+
+#[inline]
+fn read_u16(data: &[u8], endian: Endian) -> u16 {
+    match endian {
+        Endian::Little => u16::from_le_bytes([data[0], data[1]]),
+        Endian::Big => u16::from_be_bytes([data[0], data[1]]),
+    }
+}
+
+#[inline]
+fn read_u32(data: &[u8], endian: Endian) -> u32 {
+    let bytes: [u8; 4] = data[0..4].try_into().unwrap();
+    match endian {
+        Endian::Little => u32::from_le_bytes(bytes),
+        Endian::Big => u32::from_be_bytes(bytes),
+    }
+}
+
+#[inline]
+fn read_half(data: &[u8], endian: Endian) -> u8 {
+    f32_to_u8(fp16_ieee_to_fp32_value(read_u16(data, endian)))
+}
+
+#[inline]
+fn read_float(data: &[u8], endian: Endian) -> u8 {
+    f32_to_u8(f32::from_bits(read_u32(data, endian)))
+}
+
+#[inline]
+const fn expand_nibble(v: u16) -> u8 {
+    (v as u8 & 0xf) * 0x11
+}
+
+#[inline]
+const fn expand_5bit(v: u16) -> u8 {
+    let v = (v & 0x1f) as u8;
+    v << 3 | v >> 2
+}
+
+/// Decodes a single pixel of the given format.
+#[inline]
+pub fn decode_raw_pixel(data: &[u8], format: RawFormat, endian: Endian) -> u32 {
+    match format {
+        RawFormat::Alpha8 => color(255, 255, 255, data[0]),
+        RawFormat::R8 => color(data[0], 0, 0, 255),
+        RawFormat::L8 => color(data[0], data[0], data[0], 255),
+        RawFormat::R16 => color((read_u16(data, endian) >> 8) as u8, 0, 0, 255),
+        RawFormat::L16 => {
+            let l = (read_u16(data, endian) >> 8) as u8;
+            color(l, l, l, 255)
+        }
+        RawFormat::Rg16 => color(data[0], data[1], 0, 255),
+        RawFormat::Rgb24 => color(data[0], data[1], data[2], 255),
+        RawFormat::Bgr24 => color(data[2], data[1], data[0], 255),
+        RawFormat::Rgba32 => color(data[0], data[1], data[2], data[3]),
+        RawFormat::Argb32 => color(data[1], data[2], data[3], data[0]),
+        RawFormat::Bgra32 => color(data[2], data[1], data[0], data[3]),
+        RawFormat::Bgrx32 => color(data[2], data[1], data[0], 255),
+        RawFormat::Rgb565 => {
+            let (r, g, b) = rgb565_le(read_u16(data, endian));
+            color(r, g, b, 255)
+        }
+        RawFormat::Rgba4444 => {
+            let v = read_u16(data, endian);
+            color(
+                expand_nibble(v >> 12),
+                expand_nibble(v >> 8),
+                expand_nibble(v >> 4),
+                expand_nibble(v),
+            )
+        }
+        RawFormat::Argb4444 => {
+            let v = read_u16(data, endian);
+            color(
+                expand_nibble(v >> 8),
+                expand_nibble(v >> 4),
+                expand_nibble(v),
+                expand_nibble(v >> 12),
+            )
+        }
+        RawFormat::Rgba5551 => {
+            let v = read_u16(data, endian);
+            color(
+                expand_5bit(v >> 11),
+                expand_5bit(v >> 6),
+                expand_5bit(v >> 1),
+                if v & 1 != 0 { 255 } else { 0 },
+            )
+        }
+        RawFormat::Argb1555 => {
+            let v = read_u16(data, endian);
+            color(
+                expand_5bit(v >> 10),
+                expand_5bit(v >> 5),
+                expand_5bit(v),
+                if v & 0x8000 != 0 { 255 } else { 0 },
+            )
+        }
+        RawFormat::Rgb10A2 => {
+            let v = read_u32(data, endian);
+            color(
+                (v >> 2 & 0xff) as u8,
+                (v >> 12 & 0xff) as u8,
+                (v >> 22 & 0xff) as u8,
+                (v >> 30) as u8 * 0x55,
+            )
+        }
+        RawFormat::RHalf => color(read_half(data, endian), 0, 0, 255),
+        RawFormat::RgHalf => color(
+            read_half(data, endian),
+            read_half(&data[2..], endian),
+            0,
+            255,
+        ),
+        RawFormat::RgbaHalf => color(
+            read_half(data, endian),
+            read_half(&data[2..], endian),
+            read_half(&data[4..], endian),
+            read_half(&data[6..], endian),
+        ),
+        RawFormat::RFloat => color(read_float(data, endian), 0, 0, 255),
+        RawFormat::RgFloat => color(
+            read_float(data, endian),
+            read_float(&data[4..], endian),
+            0,
+            255,
+        ),
+        RawFormat::RgbaFloat => color(
+            read_float(data, endian),
+            read_float(&data[4..], endian),
+            read_float(&data[8..], endian),
+            read_float(&data[12..], endian),
+        ),
+    }
+}
+
+/// Decodes an uncompressed texture of the given pixel format into an image
+pub fn decode_raw(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    format: RawFormat,
+    endian: Endian,
+) -> Result<(), &'static str> {
+    let bpp = format.bytes_per_pixel();
+    let num_pixels = width * height;
+
+    if data.len() < num_pixels * bpp {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < num_pixels {
+        return Err("Image buffer is too small!");
+    }
+
+    image[..num_pixels]
+        .iter_mut()
+        .zip(data.chunks_exact(bpp))
+        .for_each(|(p, pixel)| *p = decode_raw_pixel(pixel, format, endian));
+    Ok(())
+}
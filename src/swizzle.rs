@@ -0,0 +1,37 @@
+//! Conversion of the tiled and swizzled texture layouts of game consoles to the linear block order of the decoders.
+//!
+//! The functions work on the encoded data, blocks of compressed formats and pixels of uncompressed formats
+//! are moved as a whole, the result can be decoded with the normal decode functions.
+
+use crate::format::TextureFormat;
+
+pub mod gnm;
+pub mod gx2;
+pub mod psp;
+pub mod tegra;
+pub mod vita;
+pub mod xenos;
+
+/// The number of blocks of a width * height texture in x and y direction.
+#[inline]
+pub(crate) fn num_blocks(format: TextureFormat, width: usize, height: usize) -> (usize, usize) {
+    let (bw, bh) = format.block_size();
+    (width.div_ceil(bw).max(1), height.div_ceil(bh).max(1))
+}
+
+/// The index of (x, y) in the Morton order of a width * height grid, with y in the lowest bit.
+/// The bits beyond the smaller of the power of two dimensions are taken from the larger dimension as they are.
+#[inline]
+pub const fn morton_index(x: usize, y: usize, width: usize, height: usize) -> usize {
+    let min_dim = if width < height { width } else { height };
+    let mut offset: usize = 0;
+    let mut shift: usize = 0;
+    let mut mask: usize = 1;
+    while mask < min_dim {
+        offset |= ((y & mask) | ((x & mask) << 1)) << shift;
+        mask <<= 1;
+        shift += 1;
+    }
+    offset |= ((x | y) >> shift) << (shift * 2);
+    offset
+}
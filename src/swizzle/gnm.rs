@@ -0,0 +1,415 @@
+//! The tiled layouts of the GCN GPU of the PlayStation 4, as used by GNM and computed by AMD's
+//! address library (addrlib) for the pipe config P8_32x32_16x16 and a pipe interleave of 256 bytes.
+//!
+//! The 1D tile modes store the elements (blocks or pixels) in micro tiles of 8x8 elements,
+//! the tiles follow each other in rows. The 2D modes additionally distribute the micro tiles
+//! over 8 pipes and up to 16 banks, mip levels smaller than a macro tile fall back to 1D tiling.
+//!
+//! The PlayStation 5 uses the swizzle modes of RDNA instead, which aren't supported.
+
+use super::num_blocks;
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::color::Flip;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+const MICRO_TILE_SIZE: usize = 8;
+const PIPE_INTERLEAVE_BYTES: usize = 256;
+const NUM_PIPES: usize = 8;
+const PIPE_BITS: usize = 3;
+
+/// The supported GNM tile modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GnmTileMode {
+    /// Rows of elements without padding
+    LinearGeneral,
+    /// Rows of elements with the pitch padded to 64 bytes
+    LinearAligned,
+    /// Micro tiles in the displayable element order
+    Display1dThin,
+    /// Micro tiles in Morton order
+    Thin1dThin,
+    /// Micro tiles in the depth element order, which is Morton order
+    Depth1dThin,
+    /// Macro tiles of micro tiles in the displayable element order
+    Display2dThin,
+    /// Macro tiles of micro tiles in Morton order
+    Thin2dThin,
+}
+
+impl GnmTileMode {
+    /// Maps the tiling index of a texture descriptor to the tile mode, None for unsupported tile modes.
+    pub const fn from_tiling_index(tiling_index: u8) -> Option<GnmTileMode> {
+        match tiling_index {
+            5 => Some(GnmTileMode::Depth1dThin),
+            8 => Some(GnmTileMode::LinearAligned),
+            9 => Some(GnmTileMode::Display1dThin),
+            10 => Some(GnmTileMode::Display2dThin),
+            13 => Some(GnmTileMode::Thin1dThin),
+            14 => Some(GnmTileMode::Thin2dThin),
+            31 => Some(GnmTileMode::LinearGeneral),
+            _ => None,
+        }
+    }
+
+    pub const fn is_linear(&self) -> bool {
+        matches!(
+            self,
+            GnmTileMode::LinearGeneral | GnmTileMode::LinearAligned
+        )
+    }
+
+    pub const fn is_macro_tiled(&self) -> bool {
+        matches!(self, GnmTileMode::Display2dThin | GnmTileMode::Thin2dThin)
+    }
+
+    /// The 1D tile mode with the same element order, used for levels smaller than a macro tile.
+    pub const fn micro_tiled(&self) -> GnmTileMode {
+        match self {
+            GnmTileMode::Display2dThin => GnmTileMode::Display1dThin,
+            GnmTileMode::Thin2dThin => GnmTileMode::Thin1dThin,
+            _ => *self,
+        }
+    }
+
+    /// The alignment of the pitch in elements.
+    pub const fn pitch_alignment(&self, bytes_per_element: usize) -> usize {
+        match self {
+            GnmTileMode::LinearGeneral => 1,
+            GnmTileMode::LinearAligned => max(8, 64 / bytes_per_element),
+            GnmTileMode::Display2dThin | GnmTileMode::Thin2dThin => {
+                macro_tile_size(bytes_per_element).0
+            }
+            // a row of micro tiles spans at least the pipe interleave
+            _ => max(
+                MICRO_TILE_SIZE,
+                PIPE_INTERLEAVE_BYTES / (MICRO_TILE_SIZE * bytes_per_element),
+            ),
+        }
+    }
+
+    /// The alignment of the height in elements.
+    pub const fn height_alignment(&self, bytes_per_element: usize) -> usize {
+        match self {
+            GnmTileMode::LinearGeneral | GnmTileMode::LinearAligned => 1,
+            GnmTileMode::Display2dThin | GnmTileMode::Thin2dThin => {
+                macro_tile_size(bytes_per_element).1
+            }
+            _ => MICRO_TILE_SIZE,
+        }
+    }
+}
+
+/// The bank width, bank height, macro tile aspect ratio and number of banks of single sampled
+/// thin surfaces, from the macro tile mode table of the PlayStation 4.
+const fn macro_tile_mode(bytes_per_element: usize) -> (usize, usize, usize, usize) {
+    match bytes_per_element {
+        1 => (1, 4, 2, 16),
+        2 => (1, 2, 2, 16),
+        4 | 8 => (1, 1, 2, 16),
+        _ => (1, 1, 1, 8),
+    }
+}
+
+/// The width and height of a macro tile in elements.
+pub const fn macro_tile_size(bytes_per_element: usize) -> (usize, usize) {
+    let (bank_width, bank_height, aspect_ratio, num_banks) = macro_tile_mode(bytes_per_element);
+    (
+        MICRO_TILE_SIZE * bank_width * NUM_PIPES * aspect_ratio,
+        MICRO_TILE_SIZE * bank_height * num_banks / aspect_ratio,
+    )
+}
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The index of the element at (x, y) of a micro tile.
+#[inline]
+pub const fn micro_tile_index(
+    tile_mode: GnmTileMode,
+    x: usize,
+    y: usize,
+    bytes_per_element: usize,
+) -> usize {
+    let (x0, x1, x2) = (x & 1, (x >> 1) & 1, (x >> 2) & 1);
+    let (y0, y1, y2) = (y & 1, (y >> 1) & 1, (y >> 2) & 1);
+    // the bits of the index from the lowest to the highest
+    let bits = match tile_mode {
+        GnmTileMode::Display1dThin | GnmTileMode::Display2dThin => match bytes_per_element {
+            1 => [x0, x1, x2, y1, y0, y2],
+            2 => [x0, x1, x2, y0, y1, y2],
+            4 => [x0, x1, y0, x2, y1, y2],
+            8 => [x0, y0, x1, x2, y1, y2],
+            _ => [y0, x0, x1, x2, y1, y2],
+        },
+        _ => [x0, y0, x1, y1, x2, y2],
+    };
+    bits[0] | bits[1] << 1 | bits[2] << 2 | bits[3] << 3 | bits[4] << 4 | bits[5] << 5
+}
+
+const fn bit(value: usize, index: usize) -> usize {
+    (value >> index) & 1
+}
+
+/// The byte offset of the element at (x, y) of an array element of a tiled surface,
+/// pitch is the padded width in elements.
+pub const fn element_offset(
+    tile_mode: GnmTileMode,
+    x: usize,
+    y: usize,
+    layer: usize,
+    pitch: usize,
+    bytes_per_element: usize,
+) -> usize {
+    if tile_mode.is_linear() {
+        return (y * pitch + x) * bytes_per_element;
+    }
+    let micro_tile_bytes = MICRO_TILE_SIZE * MICRO_TILE_SIZE * bytes_per_element;
+    let pixel_offset = micro_tile_index(
+        tile_mode,
+        x % MICRO_TILE_SIZE,
+        y % MICRO_TILE_SIZE,
+        bytes_per_element,
+    ) * bytes_per_element;
+    if !tile_mode.is_macro_tiled() {
+        let tile = (y / MICRO_TILE_SIZE) * (pitch / MICRO_TILE_SIZE) + x / MICRO_TILE_SIZE;
+        return tile * micro_tile_bytes + pixel_offset;
+    }
+    let (bank_width, bank_height, _, num_banks) = macro_tile_mode(bytes_per_element);
+    let pipe = (bit(x, 3) ^ bit(y, 3) ^ bit(x, 4))
+        | (bit(x, 4) ^ bit(y, 4)) << 1
+        | (bit(x, 5) ^ bit(y, 5)) << 2;
+    let tx = x / MICRO_TILE_SIZE / (bank_width * NUM_PIPES);
+    let ty = y / MICRO_TILE_SIZE / bank_height;
+    let bank = if num_banks == 16 {
+        (bit(tx, 0) ^ bit(ty, 3))
+            | (bit(tx, 1) ^ bit(ty, 2) ^ bit(ty, 3)) << 1
+            | (bit(tx, 2) ^ bit(ty, 1)) << 2
+            | (bit(tx, 3) ^ bit(ty, 0)) << 3
+    } else {
+        (bit(tx, 0) ^ bit(ty, 2))
+            | (bit(tx, 1) ^ bit(ty, 1) ^ bit(ty, 2)) << 1
+            | (bit(tx, 2) ^ bit(ty, 0)) << 2
+    };
+    // the banks are rotated for each array element
+    let bank = (bank ^ ((num_banks / 2 - 1) * layer)) & (num_banks - 1);
+    let bank_bits = num_banks.trailing_zeros() as usize;
+
+    let (macro_tile_pitch, macro_tile_height) = macro_tile_size(bytes_per_element);
+    let macro_tile_bytes = macro_tile_pitch * macro_tile_height * bytes_per_element;
+    let macro_tile = (y / macro_tile_height) * (pitch / macro_tile_pitch) + x / macro_tile_pitch;
+    let tile_row = (y / MICRO_TILE_SIZE) % bank_height;
+    let tile_column = (x / MICRO_TILE_SIZE / NUM_PIPES) % bank_width;
+    let tile_offset = (tile_row * bank_width + tile_column) * micro_tile_bytes;
+
+    // the offset within a pipe and bank, the pipe and bank bits are inserted above the pipe interleave bits
+    let total_offset =
+        ((macro_tile * macro_tile_bytes) >> (PIPE_BITS + bank_bits)) + tile_offset + pixel_offset;
+    (total_offset % PIPE_INTERLEAVE_BYTES)
+        | pipe << 8
+        | bank << (8 + PIPE_BITS)
+        | (total_offset / PIPE_INTERLEAVE_BYTES) << (8 + PIPE_BITS + bank_bits)
+}
+
+/// Converts an array element of a tiled surface to rows of elements.
+/// width and height are in elements, pitch is the padded width in elements.
+#[allow(clippy::too_many_arguments)]
+pub fn untile(
+    tile_mode: GnmTileMode,
+    width: usize,
+    height: usize,
+    pitch: usize,
+    layer: usize,
+    bytes_per_element: usize,
+    data: &[u8],
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    let padded_height = height.next_multiple_of(tile_mode.height_alignment(bytes_per_element));
+    if pitch < width || data.len() < pitch * padded_height * bytes_per_element {
+        return Err("Not enough data to untile image!");
+    }
+    if linear.len() < width * height * bytes_per_element {
+        return Err("Linear buffer is too small!");
+    }
+    if tile_mode.is_linear() {
+        let row_size = width * bytes_per_element;
+        let pitch_size = pitch * bytes_per_element;
+        linear
+            .chunks_exact_mut(row_size)
+            .zip(data.chunks(pitch_size))
+            .take(height)
+            .for_each(|(dst, src)| dst.copy_from_slice(&src[..row_size]));
+        return Ok(());
+    }
+    for (i, element) in linear
+        .chunks_exact_mut(bytes_per_element)
+        .take(width * height)
+        .enumerate()
+    {
+        let offset = element_offset(
+            tile_mode,
+            i % width,
+            i / width,
+            layer,
+            pitch,
+            bytes_per_element,
+        );
+        element.copy_from_slice(
+            data.get(offset..offset + bytes_per_element)
+                .ok_or("Not enough data to untile image!")?,
+        );
+    }
+    Ok(())
+}
+
+/// The size of a mip level in elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GnmMipLayout {
+    /// The tile mode of the level, 2D modes fall back to 1D tiling for levels smaller than a macro tile
+    pub tile_mode: GnmTileMode,
+    pub width: usize,
+    pub height: usize,
+    /// The padded width
+    pub pitch: usize,
+    /// The padded height
+    pub padded_height: usize,
+    /// The size of an array element of the level in bytes
+    pub size: usize,
+}
+
+/// A tiled GNM texture, as described by its texture descriptor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GnmSurface {
+    pub format: TextureFormat,
+    pub tile_mode: GnmTileMode,
+    pub width: usize,
+    pub height: usize,
+    /// The pitch of the base level in pixels, 0 to derive it from the width
+    pub pitch: usize,
+    pub array_size: usize,
+    /// true if the dimensions of the mip levels are derived from the next power of two
+    pub pow2_pad: bool,
+}
+
+impl GnmSurface {
+    /// The layout of a mip level.
+    pub fn mip_layout(&self, level: u32) -> GnmMipLayout {
+        let bytes_per_element = self.format.block_bytes();
+        let (base_width, base_height) = if self.pow2_pad {
+            (
+                self.width.next_power_of_two(),
+                self.height.next_power_of_two(),
+            )
+        } else {
+            (self.width, self.height)
+        };
+        let (width, height) = num_blocks(
+            self.format,
+            (self.width >> level).max(1),
+            (self.height >> level).max(1),
+        );
+        let (padded_width, padded_height) = num_blocks(
+            self.format,
+            (base_width >> level).max(1),
+            (base_height >> level).max(1),
+        );
+        let mut tile_mode = self.tile_mode;
+        if tile_mode.is_macro_tiled() {
+            let (macro_tile_pitch, macro_tile_height) = macro_tile_size(bytes_per_element);
+            if padded_width < macro_tile_pitch || padded_height < macro_tile_height {
+                tile_mode = tile_mode.micro_tiled();
+            }
+        }
+        let mut pitch = padded_width.next_multiple_of(tile_mode.pitch_alignment(bytes_per_element));
+        if level == 0 {
+            pitch = pitch.max(self.pitch.div_ceil(self.format.block_size().0));
+        }
+        let padded_height =
+            padded_height.next_multiple_of(tile_mode.height_alignment(bytes_per_element));
+        let mut size = pitch * padded_height * bytes_per_element;
+        if tile_mode != GnmTileMode::LinearGeneral {
+            size = size.next_multiple_of(PIPE_INTERLEAVE_BYTES);
+        }
+        GnmMipLayout {
+            tile_mode,
+            width,
+            height,
+            pitch,
+            padded_height,
+            size,
+        }
+    }
+
+    /// The offset of an array element of a mip level, the mip levels contain all array elements.
+    pub fn surface_offset(&self, level: u32, layer: usize) -> usize {
+        (0..level)
+            .map(|level| self.mip_layout(level).size * self.array_size)
+            .sum::<usize>()
+            + layer * self.mip_layout(level).size
+    }
+
+    /// Untiles an array element of a mip level,
+    /// linear receives the blocks of the level in the layout [`TextureFormat::decode()`] expects.
+    pub fn untile_mip(
+        &self,
+        data: &[u8],
+        level: u32,
+        layer: usize,
+        linear: &mut [u8],
+    ) -> Result<(), &'static str> {
+        let layout = self.mip_layout(level);
+        let offset = self.surface_offset(level, layer);
+        untile(
+            layout.tile_mode,
+            layout.width,
+            layout.height,
+            layout.pitch,
+            layer,
+            self.format.block_bytes(),
+            data.get(offset..)
+                .ok_or("Not enough data to untile image!")?,
+            linear,
+        )
+    }
+
+    /// Untiles and decodes an array element of a mip level.
+    #[cfg(feature = "alloc")]
+    pub fn decode_mip(
+        &self,
+        data: &[u8],
+        level: u32,
+        layer: usize,
+    ) -> Result<DecodedImage, &'static str> {
+        self.decode_mip_flipped(data, level, layer, Flip::NONE)
+    }
+
+    /// Untiles and decodes an array element of a mip level into an image mirrored by flip.
+    #[cfg(feature = "alloc")]
+    pub fn decode_mip_flipped(
+        &self,
+        data: &[u8],
+        level: u32,
+        layer: usize,
+        flip: Flip,
+    ) -> Result<DecodedImage, &'static str> {
+        let layout = self.mip_layout(level);
+        let mut linear = alloc::vec![0; layout.width * layout.height * self.format.block_bytes()];
+        self.untile_mip(data, level, layer, &mut linear)?;
+        self.format.decode_image_flipped(
+            &linear,
+            (self.width >> level).max(1),
+            (self.height >> level).max(1),
+            flip,
+        )
+    }
+}
@@ -0,0 +1,339 @@
+//! The tiled layouts of the GX2 API of the Wii U, as computed by AMD's address library (addrlib)
+//! for the Latte GPU with 2 pipes and 4 banks.
+//!
+//! The elements (blocks or pixels) are stored in micro tiles of 8x8 elements,
+//! the macro tiled modes additionally distribute the micro tiles over the pipes and banks.
+
+use super::num_blocks;
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::color::Flip;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+const NUM_PIPES: usize = 2;
+const NUM_BANKS: usize = 4;
+const PIPE_INTERLEAVE_BYTES: usize = 256;
+const ROW_SIZE: usize = 2048;
+const SWAP_SIZE: usize = 256;
+const MICRO_TILE_PIXELS: usize = 64;
+
+const BANK_SWAP_ORDER: [usize; 4] = [0, 1, 3, 2];
+
+/// The `GX2TileMode`s of thin surfaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gx2TileMode {
+    LinearAligned = 1,
+    Tiled1dThin1 = 2,
+    Tiled2dThin1 = 4,
+    Tiled2dThin2 = 5,
+    Tiled2dThin4 = 6,
+    Tiled2bThin1 = 8,
+    Tiled2bThin2 = 9,
+    Tiled2bThin4 = 10,
+    Tiled3dThin1 = 12,
+    Tiled3bThin1 = 14,
+    LinearSpecial = 16,
+}
+
+impl Gx2TileMode {
+    /// Maps a `GX2TileMode` value to the tile mode, None for thick and unknown tile modes.
+    /// The default mode 0 is linear aligned.
+    pub const fn from_u32(value: u32) -> Option<Gx2TileMode> {
+        let tile_mode = match value {
+            0 | 1 => Gx2TileMode::LinearAligned,
+            2 => Gx2TileMode::Tiled1dThin1,
+            4 => Gx2TileMode::Tiled2dThin1,
+            5 => Gx2TileMode::Tiled2dThin2,
+            6 => Gx2TileMode::Tiled2dThin4,
+            8 => Gx2TileMode::Tiled2bThin1,
+            9 => Gx2TileMode::Tiled2bThin2,
+            10 => Gx2TileMode::Tiled2bThin4,
+            12 => Gx2TileMode::Tiled3dThin1,
+            14 => Gx2TileMode::Tiled3bThin1,
+            16 => Gx2TileMode::LinearSpecial,
+            _ => return None,
+        };
+        Some(tile_mode)
+    }
+
+    pub const fn is_linear(&self) -> bool {
+        matches!(
+            self,
+            Gx2TileMode::LinearAligned | Gx2TileMode::LinearSpecial
+        )
+    }
+
+    pub const fn is_macro_tiled(&self) -> bool {
+        !self.is_linear() && !matches!(self, Gx2TileMode::Tiled1dThin1)
+    }
+
+    const fn is_bank_swapped(&self) -> bool {
+        matches!(
+            self,
+            Gx2TileMode::Tiled2bThin1
+                | Gx2TileMode::Tiled2bThin2
+                | Gx2TileMode::Tiled2bThin4
+                | Gx2TileMode::Tiled3bThin1
+        )
+    }
+
+    const fn macro_tile_aspect_ratio(&self) -> usize {
+        match self {
+            Gx2TileMode::Tiled2dThin2 | Gx2TileMode::Tiled2bThin2 => 2,
+            Gx2TileMode::Tiled2dThin4 | Gx2TileMode::Tiled2bThin4 => 4,
+            _ => 1,
+        }
+    }
+
+    /// The width and height of a macro tile in elements.
+    pub const fn macro_tile_size(&self) -> (usize, usize) {
+        let aspect_ratio = self.macro_tile_aspect_ratio();
+        (8 * NUM_BANKS / aspect_ratio, 8 * NUM_PIPES * aspect_ratio)
+    }
+
+    /// The alignment of the pitch and the height in elements of a surface with the given width in elements.
+    pub const fn alignment(&self, bits_per_element: usize, width: usize) -> (usize, usize) {
+        match self {
+            Gx2TileMode::LinearSpecial => (1, 1),
+            Gx2TileMode::LinearAligned => (max(64, 2048 / bits_per_element), 1),
+            Gx2TileMode::Tiled1dThin1 => (max(8, PIPE_INTERLEAVE_BYTES / bits_per_element), 8),
+            _ => {
+                let (tile_width, tile_height) = self.macro_tile_size();
+                let pitch_alignment = max(
+                    tile_width,
+                    tile_width * (PIPE_INTERLEAVE_BYTES / bits_per_element / 8),
+                );
+                let bank_swapped_width = self.bank_swapped_width(bits_per_element, width);
+                (max(pitch_alignment, bank_swapped_width), tile_height)
+            }
+        }
+    }
+
+    const fn bank_swapped_width(&self, bits_per_element: usize, pitch: usize) -> usize {
+        if !self.is_bank_swapped() {
+            return 0;
+        }
+        // a tile slice of a single sampled surface doesn't exceed the split size
+        let bytes_per_tile_slice = 8 * bits_per_element;
+        let swap_tiles = max(1, (SWAP_SIZE >> 1) / bits_per_element);
+        let swap_width = swap_tiles * 8 * NUM_BANKS;
+        let height_bytes = self.macro_tile_aspect_ratio() * NUM_PIPES * bits_per_element;
+        let swap_max = NUM_PIPES * NUM_BANKS * ROW_SIZE / height_bytes;
+        let swap_min = PIPE_INTERLEAVE_BYTES * 8 * NUM_BANKS / bytes_per_tile_slice;
+        let mut bank_swap_width = min(swap_max, max(swap_min, swap_width));
+        while bank_swap_width >= 2 * pitch {
+            bank_swap_width >>= 1;
+        }
+        bank_swap_width
+    }
+}
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+const fn min(a: usize, b: usize) -> usize {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The index of the element at (x, y) of a micro tile.
+#[inline]
+pub const fn micro_tile_index(x: usize, y: usize, bits_per_element: usize) -> usize {
+    let (x0, x1, x2) = (x & 1, (x >> 1) & 1, (x >> 2) & 1);
+    let (y0, y1, y2) = (y & 1, (y >> 1) & 1, (y >> 2) & 1);
+    // the bits of the index from the lowest to the highest
+    let bits = match bits_per_element {
+        16 => [x0, x1, x2, y0, y1, y2],
+        32 => [x0, x1, y0, x2, y1, y2],
+        64 => [x0, y0, x1, x2, y1, y2],
+        128 => [y0, x0, x1, x2, y1, y2],
+        _ => [x0, x1, x2, y1, y0, y2],
+    };
+    bits[0] | bits[1] << 1 | bits[2] << 2 | bits[3] << 3 | bits[4] << 4 | bits[5] << 5
+}
+
+/// The byte offset of the element at (x, y) in a tiled surface, pitch is the padded width in elements.
+/// pipe_swizzle and bank_swizzle are bits 8 and 9-10 of the swizzle value of the surface.
+pub const fn element_offset(
+    x: usize,
+    y: usize,
+    bits_per_element: usize,
+    pitch: usize,
+    tile_mode: Gx2TileMode,
+    pipe_swizzle: usize,
+    bank_swizzle: usize,
+) -> usize {
+    if tile_mode.is_linear() {
+        return (y * pitch + x) * bits_per_element / 8;
+    }
+    let pixel_offset = bits_per_element * micro_tile_index(x % 8, y % 8, bits_per_element) / 8;
+    if !tile_mode.is_macro_tiled() {
+        let micro_tile_bytes = MICRO_TILE_PIXELS * bits_per_element / 8;
+        return pixel_offset + micro_tile_bytes * ((x >> 3) + (y >> 3) * (pitch >> 3));
+    }
+    let pipe = ((y >> 3) ^ (x >> 3)) & 1;
+    let bank =
+        (((y / (16 * NUM_PIPES)) ^ (x >> 3)) & 1) | (((y / (8 * NUM_PIPES)) ^ (x >> 4)) & 1) << 1;
+    let swizzle = pipe_swizzle + NUM_PIPES * bank_swizzle;
+    let bank_pipe = ((pipe + NUM_PIPES * bank) ^ swizzle) % (NUM_PIPES * NUM_BANKS);
+    let (pipe, mut bank) = (bank_pipe % NUM_PIPES, bank_pipe / NUM_PIPES);
+
+    let (macro_tile_pitch, macro_tile_height) = tile_mode.macro_tile_size();
+    let macro_tiles_per_row = pitch / macro_tile_pitch;
+    let macro_tile_bytes = bits_per_element * macro_tile_height * macro_tile_pitch / 8;
+    let (macro_tile_x, macro_tile_y) = (x / macro_tile_pitch, y / macro_tile_height);
+    let macro_tile_offset = (macro_tile_x + macro_tiles_per_row * macro_tile_y) * macro_tile_bytes;
+    if tile_mode.is_bank_swapped() {
+        let bank_swap_width = tile_mode.bank_swapped_width(bits_per_element, pitch);
+        let swap_index = macro_tile_pitch * macro_tile_x / bank_swap_width;
+        bank ^= BANK_SWAP_ORDER[swap_index & (NUM_BANKS - 1)];
+    }
+
+    // the pipe and bank bits are inserted above the pipe interleave bits
+    let group_mask = PIPE_INTERLEAVE_BYTES - 1;
+    let num_swizzle_bits = 3;
+    let total_offset = pixel_offset + (macro_tile_offset >> num_swizzle_bits);
+    ((total_offset & !group_mask) << num_swizzle_bits)
+        | (bank << 9)
+        | (pipe << 8)
+        | (total_offset & group_mask)
+}
+
+/// The layout of a mip level in elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gx2MipLayout {
+    /// The tile mode of the level, macro tiled modes fall back to 1D tiling for levels smaller than a macro tile
+    pub tile_mode: Gx2TileMode,
+    pub width: usize,
+    pub height: usize,
+    /// The padded width
+    pub pitch: usize,
+    /// The padded height
+    pub padded_height: usize,
+    /// The size of the level in bytes
+    pub size: usize,
+}
+
+/// A tiled GX2 surface, as described by a `GX2Surface`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gx2Surface {
+    pub format: TextureFormat,
+    pub tile_mode: Gx2TileMode,
+    pub width: usize,
+    pub height: usize,
+    /// The pitch of the base level in elements, 0 to derive it from the width
+    pub pitch: usize,
+    /// The swizzle value of the surface, containing the pipe and bank swizzle
+    pub swizzle: u32,
+}
+
+impl Gx2Surface {
+    /// The layout of a mip level.
+    pub fn mip_layout(&self, level: u32) -> Gx2MipLayout {
+        let bits_per_element = self.format.block_bytes() * 8;
+        let (width, height) = num_blocks(
+            self.format,
+            (self.width >> level).max(1),
+            (self.height >> level).max(1),
+        );
+        let mut tile_mode = self.tile_mode;
+        if tile_mode.is_macro_tiled() {
+            let (macro_tile_width, macro_tile_height) = tile_mode.macro_tile_size();
+            if width < macro_tile_width || height < macro_tile_height {
+                tile_mode = Gx2TileMode::Tiled1dThin1;
+            }
+        }
+        let (pitch_alignment, height_alignment) = tile_mode.alignment(bits_per_element, width);
+        let mut pitch = width.next_multiple_of(pitch_alignment);
+        if level == 0 && self.pitch > pitch {
+            pitch = self.pitch;
+        }
+        let padded_height = height.next_multiple_of(height_alignment);
+        Gx2MipLayout {
+            tile_mode,
+            width,
+            height,
+            pitch,
+            padded_height,
+            size: pitch * padded_height * bits_per_element / 8,
+        }
+    }
+
+    /// Untiles a mip level, data starts at the level, e.g. at its offset in the mip data of a GTX file.
+    /// linear receives the blocks of the level in the layout [`TextureFormat::decode()`] expects.
+    pub fn untile_mip(
+        &self,
+        data: &[u8],
+        level: u32,
+        linear: &mut [u8],
+    ) -> Result<(), &'static str> {
+        let layout = self.mip_layout(level);
+        let bytes_per_element = self.format.block_bytes();
+        if data.len() < layout.size {
+            return Err("Not enough data to untile image!");
+        }
+        if linear.len() < layout.width * layout.height * bytes_per_element {
+            return Err("Linear buffer is too small!");
+        }
+        let pipe_swizzle = (self.swizzle >> 8 & 1) as usize;
+        let bank_swizzle = (self.swizzle >> 9 & 3) as usize;
+        for (i, element) in linear
+            .chunks_exact_mut(bytes_per_element)
+            .take(layout.width * layout.height)
+            .enumerate()
+        {
+            let offset = element_offset(
+                i % layout.width,
+                i / layout.width,
+                bytes_per_element * 8,
+                layout.pitch,
+                layout.tile_mode,
+                pipe_swizzle,
+                bank_swizzle,
+            );
+            element.copy_from_slice(
+                data.get(offset..offset + bytes_per_element)
+                    .ok_or("Not enough data to untile image!")?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Untiles and decodes a mip level, data starts at the level.
+    #[cfg(feature = "alloc")]
+    pub fn decode_mip(&self, data: &[u8], level: u32) -> Result<DecodedImage, &'static str> {
+        self.decode_mip_flipped(data, level, Flip::NONE)
+    }
+
+    /// Untiles and decodes a mip level into an image mirrored by flip, data starts at the level.
+    #[cfg(feature = "alloc")]
+    pub fn decode_mip_flipped(
+        &self,
+        data: &[u8],
+        level: u32,
+        flip: Flip,
+    ) -> Result<DecodedImage, &'static str> {
+        let layout = self.mip_layout(level);
+        let mut linear = alloc::vec![0; layout.width * layout.height * self.format.block_bytes()];
+        self.untile_mip(data, level, &mut linear)?;
+        self.format.decode_image_flipped(
+            &linear,
+            (self.width >> level).max(1),
+            (self.height >> level).max(1),
+            flip,
+        )
+    }
+}
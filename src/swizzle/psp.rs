@@ -0,0 +1,51 @@
+//! The swizzled layout of the PSP, the rows of bytes are stored in tiles of 16 bytes x 8 rows.
+//!
+//! The swizzle works on bytes regardless of the pixel format, the deswizzled data
+//! can be decoded with [`crate::decode_p4()`], [`crate::decode_p8()`] or [`crate::decode_raw()`].
+
+use core::result::Result;
+
+const TILE_WIDTH: usize = 16;
+const TILE_HEIGHT: usize = 8;
+
+/// The size of a row in bytes and the padded size of the swizzled texture in bytes.
+pub const fn swizzled_size(width: usize, height: usize, bits_per_pixel: usize) -> (usize, usize) {
+    let row_size = (width * bits_per_pixel).div_ceil(8);
+    (
+        row_size,
+        row_size.next_multiple_of(TILE_WIDTH) * height.next_multiple_of(TILE_HEIGHT),
+    )
+}
+
+/// Converts swizzled PSP texture data to rows of pixels,
+/// bits_per_pixel is 4 or 8 for indexed textures, 16 or 32 for the others.
+pub fn deswizzle_psp(
+    width: usize,
+    height: usize,
+    bits_per_pixel: usize,
+    data: &[u8],
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    let (row_size, size) = swizzled_size(width, height, bits_per_pixel);
+    if data.len() < size {
+        return Err("Not enough data to deswizzle image!");
+    }
+    if linear.len() < row_size * height {
+        return Err("Linear buffer is too small!");
+    }
+    let tiles_per_row = row_size.div_ceil(TILE_WIDTH);
+    linear
+        .chunks_exact_mut(row_size)
+        .take(height)
+        .enumerate()
+        .for_each(|(y, row)| {
+            row.chunks_mut(TILE_WIDTH)
+                .enumerate()
+                .for_each(|(tile_x, run)| {
+                    let tile = (y / TILE_HEIGHT) * tiles_per_row + tile_x;
+                    let offset = (tile * TILE_HEIGHT + y % TILE_HEIGHT) * TILE_WIDTH;
+                    run.copy_from_slice(&data[offset..offset + run.len()]);
+                });
+        });
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! The block-linear layout of the Tegra X1 GPU of the Nintendo Switch.
+//!
+//! The data is stored in GOBs (groups of bytes) of 64 bytes x 8 rows,
+//! which are stacked vertically in blocks of 2^block_height_log2 GOBs.
+
+use super::num_blocks;
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::color::Flip;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+const GOB_WIDTH: usize = 64;
+const GOB_HEIGHT: usize = 8;
+const GOB_SIZE: usize = GOB_WIDTH * GOB_HEIGHT;
+
+/// The block height log2 the Switch uses for the base level of a texture with the given height in blocks.
+pub const fn block_height_log2_mip0(height_in_blocks: usize) -> u32 {
+    let height_and_half = height_in_blocks + height_in_blocks / 2;
+    if height_and_half >= 128 {
+        4
+    } else if height_and_half >= 64 {
+        3
+    } else if height_and_half >= 32 {
+        2
+    } else if height_and_half >= 16 {
+        1
+    } else {
+        0
+    }
+}
+
+/// The block height log2 of a mip level, the block height is halved while the mip fits into half of the GOBs.
+pub const fn mip_block_height_log2(mip_height_in_blocks: usize, block_height_log2: u32) -> u32 {
+    let mut block_height_log2 = block_height_log2;
+    while block_height_log2 > 0 && mip_height_in_blocks <= (GOB_HEIGHT << block_height_log2) / 2 {
+        block_height_log2 -= 1;
+    }
+    block_height_log2
+}
+
+/// The size of the block-linear data of a surface, padded to whole blocks of GOBs.
+pub const fn swizzled_size(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    block_height_log2: u32,
+) -> usize {
+    let width_in_gobs = (width_in_blocks * bytes_per_block).div_ceil(GOB_WIDTH);
+    let block_rows = height_in_blocks.div_ceil(GOB_HEIGHT << block_height_log2);
+    width_in_gobs * block_rows * (GOB_SIZE << block_height_log2)
+}
+
+/// The offset of the byte at (x, y) of the linear data in the block-linear data,
+/// x is in bytes and y in rows of blocks.
+#[inline]
+pub const fn block_linear_offset(
+    x: usize,
+    y: usize,
+    width_in_gobs: usize,
+    block_height_log2: u32,
+) -> usize {
+    let block_height = 1 << block_height_log2;
+    let block_size = GOB_SIZE * block_height;
+    let gob_address = (y / (GOB_HEIGHT * block_height)) * block_size * width_in_gobs
+        + (x / GOB_WIDTH) * block_size
+        + (y % (GOB_HEIGHT * block_height) / GOB_HEIGHT) * GOB_SIZE;
+    let (x, y) = (x % GOB_WIDTH, y % GOB_HEIGHT);
+    gob_address + (x / 32) * 256 + (y / 2) * 64 + (x % 32 / 16) * 32 + (y % 2) * 16 + x % 16
+}
+
+/// Calls f(block_linear_offset, linear_offset, len) for each run of contiguous bytes.
+fn for_each_run(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    block_height_log2: u32,
+    mut f: impl FnMut(usize, usize, usize),
+) {
+    let row_size = width_in_blocks * bytes_per_block;
+    let width_in_gobs = row_size.div_ceil(GOB_WIDTH);
+    for y in 0..height_in_blocks {
+        // the bytes of a GOB are contiguous in runs of 16
+        for x in (0..row_size).step_by(16) {
+            f(
+                block_linear_offset(x, y, width_in_gobs, block_height_log2),
+                y * row_size + x,
+                (row_size - x).min(16),
+            );
+        }
+    }
+}
+
+fn check_sizes(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    block_height_log2: u32,
+    block_linear: &[u8],
+    linear: &[u8],
+) -> Result<(), &'static str> {
+    if block_linear.len()
+        < swizzled_size(
+            width_in_blocks,
+            height_in_blocks,
+            bytes_per_block,
+            block_height_log2,
+        )
+    {
+        return Err("Block-linear buffer is too small!");
+    }
+    if linear.len() < width_in_blocks * height_in_blocks * bytes_per_block {
+        return Err("Linear buffer is too small!");
+    }
+    Ok(())
+}
+
+/// Converts block-linear data to the linear block order.
+pub fn deswizzle_block_linear(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    block_height_log2: u32,
+    data: &[u8],
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    check_sizes(
+        width_in_blocks,
+        height_in_blocks,
+        bytes_per_block,
+        block_height_log2,
+        data,
+        linear,
+    )?;
+    for_each_run(
+        width_in_blocks,
+        height_in_blocks,
+        bytes_per_block,
+        block_height_log2,
+        |src, dst, len| linear[dst..dst + len].copy_from_slice(&data[src..src + len]),
+    );
+    Ok(())
+}
+
+/// Converts data in linear block order to the block-linear layout, the inverse of [`deswizzle_block_linear()`].
+pub fn swizzle_block_linear(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    block_height_log2: u32,
+    linear: &[u8],
+    data: &mut [u8],
+) -> Result<(), &'static str> {
+    check_sizes(
+        width_in_blocks,
+        height_in_blocks,
+        bytes_per_block,
+        block_height_log2,
+        data,
+        linear,
+    )?;
+    for_each_run(
+        width_in_blocks,
+        height_in_blocks,
+        bytes_per_block,
+        block_height_log2,
+        |dst, src, len| data[dst..dst + len].copy_from_slice(&linear[src..src + len]),
+    );
+    Ok(())
+}
+
+/// The size in blocks and the block height log2 of a mip level,
+/// width, height and block_height_log2 are the ones of the base level.
+pub fn mip_dimensions(
+    format: TextureFormat,
+    width: usize,
+    height: usize,
+    level: u32,
+    block_height_log2: u32,
+) -> (usize, usize, u32) {
+    let (width_in_blocks, height_in_blocks) =
+        num_blocks(format, (width >> level).max(1), (height >> level).max(1));
+    (
+        width_in_blocks,
+        height_in_blocks,
+        mip_block_height_log2(height_in_blocks, block_height_log2),
+    )
+}
+
+/// The offset of a mip level in the block-linear data of a texture, the mips follow each other.
+pub fn mip_offset(
+    format: TextureFormat,
+    width: usize,
+    height: usize,
+    level: u32,
+    block_height_log2: u32,
+) -> usize {
+    (0..level)
+        .map(|level| {
+            let (mip_width, mip_height, mip_block_height_log2) =
+                mip_dimensions(format, width, height, level, block_height_log2);
+            swizzled_size(
+                mip_width,
+                mip_height,
+                format.block_bytes(),
+                mip_block_height_log2,
+            )
+        })
+        .sum()
+}
+
+/// Deswizzles a mip level of a block-linear texture,
+/// linear receives the blocks of the level in the layout [`TextureFormat::decode()`] expects.
+pub fn deswizzle_mip(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    level: u32,
+    block_height_log2: u32,
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    let (mip_width, mip_height, mip_block_height_log2) =
+        mip_dimensions(format, width, height, level, block_height_log2);
+    let offset = mip_offset(format, width, height, level, block_height_log2);
+    deswizzle_block_linear(
+        mip_width,
+        mip_height,
+        format.block_bytes(),
+        mip_block_height_log2,
+        data.get(offset..)
+            .ok_or("Not enough data to deswizzle image!")?,
+        linear,
+    )
+}
+
+/// Deswizzles and decodes a mip level of a block-linear texture,
+/// block_height_log2 is the one of the base level, see [`block_height_log2_mip0()`].
+#[cfg(feature = "alloc")]
+pub fn decode_tegra(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    level: u32,
+    block_height_log2: u32,
+) -> Result<DecodedImage, &'static str> {
+    decode_tegra_flipped(
+        format,
+        data,
+        width,
+        height,
+        level,
+        block_height_log2,
+        Flip::NONE,
+    )
+}
+
+/// Deswizzles and decodes a mip level of a block-linear texture into an image mirrored by flip.
+#[cfg(feature = "alloc")]
+pub fn decode_tegra_flipped(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    level: u32,
+    block_height_log2: u32,
+    flip: Flip,
+) -> Result<DecodedImage, &'static str> {
+    let (mip_width, mip_height, _) =
+        mip_dimensions(format, width, height, level, block_height_log2);
+    let mut linear = alloc::vec![0; mip_width * mip_height * format.block_bytes()];
+    deswizzle_mip(
+        format,
+        data,
+        width,
+        height,
+        level,
+        block_height_log2,
+        &mut linear,
+    )?;
+    format.decode_image_flipped(
+        &linear,
+        (width >> level).max(1),
+        (height >> level).max(1),
+        flip,
+    )
+}
@@ -0,0 +1,104 @@
+//! The swizzled layout of the PS Vita, the blocks (or pixels for uncompressed formats)
+//! are stored in Morton order on the dimensions padded to powers of two.
+
+use super::{morton_index, num_blocks};
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::color::Flip;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+/// Converts blocks in Morton order to the linear block order.
+/// The data has to contain the blocks of the dimensions padded to powers of two.
+pub fn deswizzle_morton(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    data: &[u8],
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    let (padded_width, padded_height) = (
+        width_in_blocks.next_power_of_two(),
+        height_in_blocks.next_power_of_two(),
+    );
+    if data.len() < padded_width * padded_height * bytes_per_block {
+        return Err("Not enough data to deswizzle image!");
+    }
+    if linear.len() < width_in_blocks * height_in_blocks * bytes_per_block {
+        return Err("Linear buffer is too small!");
+    }
+    linear
+        .chunks_exact_mut(bytes_per_block)
+        .take(width_in_blocks * height_in_blocks)
+        .enumerate()
+        .for_each(|(i, block)| {
+            let (x, y) = (i % width_in_blocks, i / width_in_blocks);
+            let offset = morton_index(x, y, padded_width, padded_height) * bytes_per_block;
+            block.copy_from_slice(&data[offset..offset + bytes_per_block]);
+        });
+    Ok(())
+}
+
+/// Deswizzles a swizzled PS Vita texture,
+/// linear receives the blocks in the layout [`TextureFormat::decode()`] expects.
+/// PVRTC textures are copied as they are, as the PVRTC decoders expect the blocks in Morton order.
+pub fn deswizzle_vita(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    match format {
+        TextureFormat::Pvrtc2bpp | TextureFormat::Pvrtc4bpp => {
+            let size = format.data_size(width, height);
+            if data.len() < size {
+                return Err("Not enough data to deswizzle image!");
+            }
+            if linear.len() < size {
+                return Err("Linear buffer is too small!");
+            }
+            linear[..size].copy_from_slice(&data[..size]);
+            Ok(())
+        }
+        _ => {
+            let (width_in_blocks, height_in_blocks) = num_blocks(format, width, height);
+            deswizzle_morton(
+                width_in_blocks,
+                height_in_blocks,
+                format.block_bytes(),
+                data,
+                linear,
+            )
+        }
+    }
+}
+
+/// Deswizzles and decodes a swizzled PS Vita texture.
+#[cfg(feature = "alloc")]
+pub fn decode_vita(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<DecodedImage, &'static str> {
+    decode_vita_flipped(format, data, width, height, Flip::NONE)
+}
+
+/// Deswizzles and decodes a swizzled PS Vita texture into an image mirrored by flip.
+#[cfg(feature = "alloc")]
+pub fn decode_vita_flipped(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    flip: Flip,
+) -> Result<DecodedImage, &'static str> {
+    let mut linear = alloc::vec![0; format.data_size(width, height)];
+    deswizzle_vita(format, data, width, height, &mut linear)?;
+    format.decode_image_flipped(&linear, width, height, flip)
+}
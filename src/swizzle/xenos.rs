@@ -0,0 +1,168 @@
+//! The tiled layout of the Xenos GPU of the Xbox 360.
+//!
+//! Tiled textures are padded to macro tiles of 32x32 blocks (or pixels for uncompressed formats),
+//! and their data is stored in big endian words, e.g. the 16 bit words of the BCn blocks are byte swapped.
+
+use super::num_blocks;
+use crate::format::TextureFormat;
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use crate::color::Flip;
+#[cfg(feature = "alloc")]
+use crate::format::DecodedImage;
+
+const MACRO_TILE_SIZE: usize = 32;
+
+/// The byte swap of the texture fetch constants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XenosEndian {
+    None,
+    /// Swaps the bytes of 16 bit words, used by the BCn formats and 16 bit pixels
+    Swap8In16,
+    /// Swaps the bytes of 32 bit words, used by 32 bit pixels
+    Swap8In32,
+    /// Swaps the 16 bit halves of 32 bit words
+    Swap16In32,
+}
+
+impl XenosEndian {
+    /// The byte swap of a format as written by the Xbox 360 tools.
+    pub const fn of_format(format: TextureFormat) -> XenosEndian {
+        match format {
+            TextureFormat::Raw(format, _) => match format.bytes_per_pixel() {
+                1 => XenosEndian::None,
+                2 => XenosEndian::Swap8In16,
+                _ => XenosEndian::Swap8In32,
+            },
+            _ => XenosEndian::Swap8In16,
+        }
+    }
+
+    /// Converts the data to little endian words in place.
+    pub fn swap(&self, data: &mut [u8]) {
+        match self {
+            XenosEndian::None => {}
+            XenosEndian::Swap8In16 => data.chunks_exact_mut(2).for_each(|word| word.swap(0, 1)),
+            XenosEndian::Swap8In32 => data.chunks_exact_mut(4).for_each(|word| word.reverse()),
+            XenosEndian::Swap16In32 => data
+                .chunks_exact_mut(4)
+                .for_each(|word| word.rotate_left(2)),
+        }
+    }
+}
+
+/// The width and height in blocks padded to whole macro tiles.
+pub const fn padded_size(width_in_blocks: usize, height_in_blocks: usize) -> (usize, usize) {
+    (
+        width_in_blocks.next_multiple_of(MACRO_TILE_SIZE),
+        height_in_blocks.next_multiple_of(MACRO_TILE_SIZE),
+    )
+}
+
+/// The index of the block at (x, y) in the tiled data, XGAddress2DTiledOffset of the XDK.
+/// width_in_blocks is the padded width, bytes_per_block is 1, 2, 4, 8 or 16.
+#[inline]
+pub const fn tiled_offset(
+    x: usize,
+    y: usize,
+    width_in_blocks: usize,
+    bytes_per_block: usize,
+) -> usize {
+    let log_bpp = (bytes_per_block >> 2) + ((bytes_per_block >> 1) >> (bytes_per_block >> 2));
+    let aligned_width = width_in_blocks.next_multiple_of(MACRO_TILE_SIZE);
+    let macro_offset = ((x >> 5) + (y >> 5) * (aligned_width >> 5)) << (log_bpp + 7);
+    let micro_offset = ((x & 7) + ((y & 6) << 2)) << log_bpp;
+    let offset = macro_offset
+        + ((micro_offset & !15) << 1)
+        + (micro_offset & 15)
+        + ((y & 8) << (3 + log_bpp))
+        + ((y & 1) << 4);
+    (((offset & !511) << 3)
+        + ((offset & 448) << 2)
+        + (offset & 63)
+        + ((y & 16) << 7)
+        + (((((y & 8) >> 2) + (x >> 3)) & 3) << 6))
+        >> log_bpp
+}
+
+/// Converts tiled data to the linear block order, without swapping bytes.
+/// The data has to contain the padded macro tiles, see [`padded_size()`].
+pub fn untile(
+    width_in_blocks: usize,
+    height_in_blocks: usize,
+    bytes_per_block: usize,
+    data: &[u8],
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    let (padded_width, padded_height) = padded_size(width_in_blocks, height_in_blocks);
+    if data.len() < padded_width * padded_height * bytes_per_block {
+        return Err("Not enough data to untile image!");
+    }
+    if linear.len() < width_in_blocks * height_in_blocks * bytes_per_block {
+        return Err("Linear buffer is too small!");
+    }
+    for (i, block) in linear
+        .chunks_exact_mut(bytes_per_block)
+        .take(width_in_blocks * height_in_blocks)
+        .enumerate()
+    {
+        let (x, y) = (i % width_in_blocks, i / width_in_blocks);
+        let offset = tiled_offset(x, y, padded_width, bytes_per_block) * bytes_per_block;
+        // the tiles of 8 and 16 bit pixels span more than 32x32 pixels
+        block.copy_from_slice(
+            data.get(offset..offset + bytes_per_block)
+                .ok_or("Not enough data to untile image!")?,
+        );
+    }
+    Ok(())
+}
+
+/// Untiles a tiled Xbox 360 texture and swaps its bytes,
+/// linear receives the blocks in the layout [`TextureFormat::decode()`] expects.
+pub fn untile_xenos(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    linear: &mut [u8],
+) -> Result<(), &'static str> {
+    let (width_in_blocks, height_in_blocks) = num_blocks(format, width, height);
+    let size = width_in_blocks * height_in_blocks * format.block_bytes();
+    untile(
+        width_in_blocks,
+        height_in_blocks,
+        format.block_bytes(),
+        data,
+        linear,
+    )?;
+    XenosEndian::of_format(format).swap(&mut linear[..size]);
+    Ok(())
+}
+
+/// Untiles, swaps and decodes a tiled Xbox 360 texture, DXN textures are [`TextureFormat::Bc5`].
+#[cfg(feature = "alloc")]
+pub fn decode_xenos(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<DecodedImage, &'static str> {
+    decode_xenos_flipped(format, data, width, height, Flip::NONE)
+}
+
+/// Untiles, swaps and decodes a tiled Xbox 360 texture into an image mirrored by flip.
+#[cfg(feature = "alloc")]
+pub fn decode_xenos_flipped(
+    format: TextureFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    flip: Flip,
+) -> Result<DecodedImage, &'static str> {
+    let mut linear = alloc::vec![0; format.data_size(width, height)];
+    untile_xenos(format, data, width, height, &mut linear)?;
+    format.decode_image_flipped(&linear, width, height, flip)
+}
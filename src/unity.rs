@@ -0,0 +1,286 @@
+//! Decoding of Unity Texture2D data identified by Unity's `TextureFormat` ids.
+
+use crate::color::Flip;
+use crate::format::TextureFormat;
+use crate::raw::{Endian, RawFormat};
+use crate::yuv::{decode_yuy2_flipped, YuvMatrix, YuvRange};
+use core::result::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// The first Unity version whose crunched textures use Unity's fork of crunch.
+pub const UNITY_CRUNCH_VERSION: (u32, u32) = (2017, 3);
+
+/// The decoder for a Unity texture format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnityDecoder {
+    /// A format decoded by [`TextureFormat::decode()`]
+    Format(TextureFormat),
+    /// YUY2 with BT.601 full range colors
+    Yuy2,
+    /// ETC1 textures of the 3DS decoded by [`crate::decode_etc1_3ds()`]
+    Etc1For3ds,
+    /// ETC1A4 textures of the 3DS decoded by [`crate::decode_etc1a4_3ds()`]
+    Etc1A4For3ds,
+    /// Crunched textures decoded by [`crate::decode_crunch()`]
+    Crunch,
+    /// Crunched textures decoded by [`crate::decode_unity_crunch()`]
+    UnityCrunch,
+}
+
+/// Returns the name of a Unity `TextureFormat` id, None for unknown ids.
+pub const fn unity_texture_format_name(format_id: u32) -> Option<&'static str> {
+    let name = match format_id {
+        1 => "Alpha8",
+        2 => "ARGB4444",
+        3 => "RGB24",
+        4 => "RGBA32",
+        5 => "ARGB32",
+        6 => "ARGBFloat",
+        7 => "RGB565",
+        8 => "BGR24",
+        9 => "R16",
+        10 => "DXT1",
+        11 => "DXT3",
+        12 => "DXT5",
+        13 => "RGBA4444",
+        14 => "BGRA32",
+        15 => "RHalf",
+        16 => "RGHalf",
+        17 => "RGBAHalf",
+        18 => "RFloat",
+        19 => "RGFloat",
+        20 => "RGBAFloat",
+        21 => "YUY2",
+        22 => "RGB9e5Float",
+        23 => "RGBFloat",
+        24 => "BC6H",
+        25 => "BC7",
+        26 => "BC4",
+        27 => "BC5",
+        28 => "DXT1Crunched",
+        29 => "DXT5Crunched",
+        30 => "PVRTC_RGB2",
+        31 => "PVRTC_RGBA2",
+        32 => "PVRTC_RGB4",
+        33 => "PVRTC_RGBA4",
+        34 => "ETC_RGB4",
+        35 => "ATC_RGB4",
+        36 => "ATC_RGBA8",
+        41 => "EAC_R",
+        42 => "EAC_R_SIGNED",
+        43 => "EAC_RG",
+        44 => "EAC_RG_SIGNED",
+        45 => "ETC2_RGB",
+        46 => "ETC2_RGBA1",
+        47 => "ETC2_RGBA8",
+        48 => "ASTC_RGB_4x4",
+        49 => "ASTC_RGB_5x5",
+        50 => "ASTC_RGB_6x6",
+        51 => "ASTC_RGB_8x8",
+        52 => "ASTC_RGB_10x10",
+        53 => "ASTC_RGB_12x12",
+        54 => "ASTC_RGBA_4x4",
+        55 => "ASTC_RGBA_5x5",
+        56 => "ASTC_RGBA_6x6",
+        57 => "ASTC_RGBA_8x8",
+        58 => "ASTC_RGBA_10x10",
+        59 => "ASTC_RGBA_12x12",
+        60 => "ETC_RGB4_3DS",
+        61 => "ETC_RGBA8_3DS",
+        62 => "RG16",
+        63 => "R8",
+        64 => "ETC_RGB4Crunched",
+        65 => "ETC2_RGBA8Crunched",
+        66 => "ASTC_HDR_4x4",
+        67 => "ASTC_HDR_5x5",
+        68 => "ASTC_HDR_6x6",
+        69 => "ASTC_HDR_8x8",
+        70 => "ASTC_HDR_10x10",
+        71 => "ASTC_HDR_12x12",
+        72 => "RG32",
+        73 => "RGB48",
+        74 => "RGBA64",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Maps a Unity `TextureFormat` id to its decoder.
+/// The crunched DXT formats of Unity versions before 2017.3 use the original crunch.
+pub fn unity_decoder(
+    format_id: u32,
+    unity_version: (u32, u32),
+) -> Result<UnityDecoder, &'static str> {
+    const ASTC_SIZES: [u8; 6] = [4, 5, 6, 8, 10, 12];
+    let raw = |format| TextureFormat::Raw(format, Endian::Little);
+    let format = match format_id {
+        1 => raw(RawFormat::Alpha8),
+        2 => raw(RawFormat::Argb4444),
+        3 => raw(RawFormat::Rgb24),
+        4 => raw(RawFormat::Rgba32),
+        5 => raw(RawFormat::Argb32),
+        7 => raw(RawFormat::Rgb565),
+        8 => raw(RawFormat::Bgr24),
+        9 => raw(RawFormat::R16),
+        10 => TextureFormat::Bc1,
+        11 => TextureFormat::Bc2,
+        12 => TextureFormat::Bc3,
+        13 => raw(RawFormat::Rgba4444),
+        14 => raw(RawFormat::Bgra32),
+        15 => raw(RawFormat::RHalf),
+        16 => raw(RawFormat::RgHalf),
+        17 => raw(RawFormat::RgbaHalf),
+        18 => raw(RawFormat::RFloat),
+        19 => raw(RawFormat::RgFloat),
+        20 => raw(RawFormat::RgbaFloat),
+        21 => return Ok(UnityDecoder::Yuy2),
+        24 => TextureFormat::Bc6Unsigned,
+        25 => TextureFormat::Bc7,
+        26 => TextureFormat::Bc4,
+        27 => TextureFormat::Bc5,
+        28 | 29 if unity_version < UNITY_CRUNCH_VERSION => return Ok(UnityDecoder::Crunch),
+        28 | 29 | 64 | 65 => return Ok(UnityDecoder::UnityCrunch),
+        30 | 31 => TextureFormat::Pvrtc2bpp,
+        32 | 33 => TextureFormat::Pvrtc4bpp,
+        34 => TextureFormat::Etc1,
+        35 => TextureFormat::AtcRgb4,
+        36 => TextureFormat::AtcRgba8,
+        41 => TextureFormat::EacR,
+        42 => TextureFormat::EacRSigned,
+        43 => TextureFormat::EacRg,
+        44 => TextureFormat::EacRgSigned,
+        45 => TextureFormat::Etc2Rgb,
+        46 => TextureFormat::Etc2Rgba1,
+        47 => TextureFormat::Etc2Rgba8,
+        // RGB, RGBA and HDR ASTC with the block sizes 4x4 to 12x12
+        48..=59 | 66..=71 => {
+            let size = ASTC_SIZES[(format_id as usize - 48) % 6];
+            TextureFormat::Astc(size, size)
+        }
+        60 => return Ok(UnityDecoder::Etc1For3ds),
+        61 => return Ok(UnityDecoder::Etc1A4For3ds),
+        62 => raw(RawFormat::Rg16),
+        63 => raw(RawFormat::R8),
+        6 => return Err("The Unity TextureFormat ARGBFloat isn't supported!"),
+        22 => return Err("The Unity TextureFormat RGB9e5Float isn't supported!"),
+        23 => return Err("The Unity TextureFormat RGBFloat isn't supported!"),
+        72 => return Err("The Unity TextureFormat RG32 isn't supported!"),
+        73 => return Err("The Unity TextureFormat RGB48 isn't supported!"),
+        74 => return Err("The Unity TextureFormat RGBA64 isn't supported!"),
+        _ => return Err("Unknown Unity TextureFormat id!"),
+    };
+    Ok(UnityDecoder::Format(format))
+}
+
+/// Decodes the image data of a Unity Texture2D with the given `TextureFormat` id,
+/// the unity version is (major, minor), e.g. (2019, 4).
+pub fn decode_unity_texture(
+    format_id: u32,
+    unity_version: (u32, u32),
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_unity_texture_flipped(
+        format_id,
+        unity_version,
+        data,
+        width,
+        height,
+        image,
+        Flip::NONE,
+    )
+}
+
+/// Decodes the image data of a Unity Texture2D with the given `TextureFormat` id into an image mirrored by flip.
+/// Unity stores the rows bottom-up, [`Flip::VERTICAL`] returns top-down images.
+pub fn decode_unity_texture_flipped(
+    format_id: u32,
+    unity_version: (u32, u32),
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    flip: Flip,
+) -> Result<(), &'static str> {
+    match unity_decoder(format_id, unity_version)? {
+        UnityDecoder::Format(format) => format.decode_flipped(data, width, height, flip, image),
+        UnityDecoder::Yuy2 => decode_yuy2_flipped(
+            data,
+            width,
+            height,
+            image,
+            YuvMatrix::Bt601,
+            YuvRange::Full,
+            flip,
+        ),
+        UnityDecoder::Etc1For3ds => {
+            crate::decode_etc1_3ds_flipped(data, width, height, image, flip)
+        }
+        UnityDecoder::Etc1A4For3ds => {
+            crate::decode_etc1a4_3ds_flipped(data, width, height, image, flip)
+        }
+        #[cfg(feature = "alloc")]
+        UnityDecoder::Crunch => crate::decode_crunch_flipped(data, width, height, image, flip),
+        #[cfg(feature = "alloc")]
+        UnityDecoder::UnityCrunch => {
+            crate::decode_unity_crunch_flipped(data, width, height, image, flip)
+        }
+        #[cfg(not(feature = "alloc"))]
+        UnityDecoder::Crunch | UnityDecoder::UnityCrunch => {
+            Err("Crunched textures require the alloc feature!")
+        }
+    }
+}
+
+// The position of the destination pixel i in the source with 8 fractional bits,
+// returned as the two neighbouring source pixels and the weight of the second one.
+#[cfg(feature = "alloc")]
+#[inline]
+fn resample_position(i: usize, size: usize, source_size: usize) -> (usize, usize, u32) {
+    let p = ((2 * i + 1) * source_size * 256 / (2 * size)).saturating_sub(128);
+    let i0 = (p >> 8).min(source_size - 1);
+    (i0, (i0 + 1).min(source_size - 1), (p & 0xff) as u32)
+}
+
+/// Decodes a texture whose alpha is stored in the red channel of a separate texture,
+/// as done by Unity's ETC1 builds with `_AlphaTex` or split sprite alpha.
+/// The image receives the colors of the first texture, the alpha texture is bilinearly resampled
+/// to the size of the color texture if their sizes differ.
+#[cfg(feature = "alloc")]
+#[allow(clippy::too_many_arguments)]
+pub fn decode_unity_split_alpha(
+    color_format: TextureFormat,
+    color_data: &[u8],
+    width: usize,
+    height: usize,
+    alpha_format: TextureFormat,
+    alpha_data: &[u8],
+    alpha_width: usize,
+    alpha_height: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    if alpha_width == 0 || alpha_height == 0 {
+        return Err("The alpha texture is empty!");
+    }
+    color_format.decode(color_data, width, height, image)?;
+    let alpha = alpha_format.decode_image(alpha_data, alpha_width, alpha_height)?;
+    let red = |x: usize, y: usize| (alpha.image[y * alpha_width + x] >> 16) & 0xff;
+    let columns = (0..width)
+        .map(|x| resample_position(x, width, alpha_width))
+        .collect::<alloc::vec::Vec<_>>();
+    for y in 0..height {
+        let (y0, y1, fy) = resample_position(y, height, alpha_height);
+        for (x, &(x0, x1, fx)) in columns.iter().enumerate() {
+            let top = red(x0, y0) * (256 - fx) + red(x1, y0) * fx;
+            let bottom = red(x0, y1) * (256 - fx) + red(x1, y1) * fx;
+            let a = (top * (256 - fy) + bottom * fy + (1 << 15)) >> 16;
+            let pixel = &mut image[y * width + x];
+            *pixel = (*pixel & 0x00ff_ffff) | a << 24;
+        }
+    }
+    Ok(())
+}
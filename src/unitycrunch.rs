@@ -1,9 +1,9 @@
 pub(crate) mod crn_decomp;
 pub(crate) mod crn_unpacker;
 use super::crnlib::{CrnFormat, CrnTextureInfo};
-use crate::bcn;
+use crate::color::Flip;
 use crate::crunch::CrunchDecodeHandler;
-use crate::{decode_etc1, decode_etc2_rgb, decode_etc2_rgba8};
+use crate::format::TextureFormat;
 extern crate alloc;
 
 pub fn unity_crunch_unpack_level(
@@ -38,31 +38,39 @@ pub fn decode_unity_crunch(
     width: usize,
     height: usize,
     image: &mut [u32],
+) -> Result<(), &'static str> {
+    decode_unity_crunch_flipped(data, width, height, image, Flip::NONE)
+}
+
+/// Decodes a texture of Unity's crunch into an image mirrored by flip.
+pub fn decode_unity_crunch_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    flip: Flip,
 ) -> Result<(), &'static str> {
     let handler = unity_crunch_unpack_level(data, data.len() as u32, 0)?;
-    match handler.format {
-        CrnFormat::Dxt1 => bcn::decode_bc1(&handler.dxt_data, width, height, image),
+    let format = match handler.format {
+        CrnFormat::Dxt1 => TextureFormat::Bc1,
 
-        CrnFormat::Etc1 | CrnFormat::Etc1s => decode_etc1(&handler.dxt_data, width, height, image),
+        CrnFormat::Etc1 | CrnFormat::Etc1s => TextureFormat::Etc1,
 
         CrnFormat::CCrnfmtDxt5
         | CrnFormat::Dxt5CcxY
         | CrnFormat::Dxt5XGbr
         | CrnFormat::Dxt5Agbr
-        | CrnFormat::Dxt5XGxR => bcn::decode_bc3(&handler.dxt_data, width, height, image),
+        | CrnFormat::Dxt5XGxR => TextureFormat::Bc3,
 
-        CrnFormat::Dxt5a => bcn::decode_bc4(&handler.dxt_data, width, height, image),
+        CrnFormat::Dxt5a => TextureFormat::Bc4,
 
-        CrnFormat::DxnXy | CrnFormat::DxnYx => {
-            bcn::decode_bc5(&handler.dxt_data, width, height, image)
-        }
+        CrnFormat::DxnXy | CrnFormat::DxnYx => TextureFormat::Bc5,
 
-        CrnFormat::Etc2 => decode_etc2_rgb(&handler.dxt_data, width, height, image),
+        CrnFormat::Etc2 => TextureFormat::Etc2Rgb,
 
-        CrnFormat::Etc2a | CrnFormat::Etc2as => {
-            decode_etc2_rgba8(&handler.dxt_data, width, height, image)
-        }
+        CrnFormat::Etc2a | CrnFormat::Etc2as => TextureFormat::Etc2Rgba8,
 
-        _ => Err("Invalid crunch format."),
-    }
+        _ => return Err("Invalid crunch format."),
+    };
+    format.decode_flipped(&handler.dxt_data, width, height, flip, image)
 }
@@ -0,0 +1,241 @@
+//! Decoding of Unreal Engine textures identified by their `EPixelFormat`.
+
+use crate::format::{NumericFormat, TextureFormat};
+use crate::raw::{Endian, RawFormat};
+use crate::yuv::{decode_nv12, decode_uyvy, YuvMatrix, YuvRange};
+use core::result::Result;
+
+/// The names of the `EPixelFormat` values, indexed by the value.
+pub static UNREAL_PIXEL_FORMATS: [&str; 92] = [
+    "PF_Unknown",
+    "PF_A32B32G32R32F",
+    "PF_B8G8R8A8",
+    "PF_G8",
+    "PF_G16",
+    "PF_DXT1",
+    "PF_DXT3",
+    "PF_DXT5",
+    "PF_UYVY",
+    "PF_FloatRGB",
+    "PF_FloatRGBA",
+    "PF_DepthStencil",
+    "PF_ShadowDepth",
+    "PF_R32_FLOAT",
+    "PF_G16R16",
+    "PF_G16R16F",
+    "PF_G16R16F_FILTER",
+    "PF_G32R32F",
+    "PF_A2B10G10R10",
+    "PF_A16B16G16R16",
+    "PF_D24",
+    "PF_R16F",
+    "PF_R16F_FILTER",
+    "PF_BC5",
+    "PF_V8U8",
+    "PF_A1",
+    "PF_FloatR11G11B10",
+    "PF_A8",
+    "PF_R32_UINT",
+    "PF_R32_SINT",
+    "PF_PVRTC2",
+    "PF_PVRTC4",
+    "PF_R16_UINT",
+    "PF_R16_SINT",
+    "PF_R16G16B16A16_UINT",
+    "PF_R16G16B16A16_SINT",
+    "PF_R5G6B5_UNORM",
+    "PF_R8G8B8A8",
+    "PF_A8R8G8B8",
+    "PF_BC4",
+    "PF_R8G8",
+    "PF_ATC_RGB",
+    "PF_ATC_RGBA_E",
+    "PF_ATC_RGBA_I",
+    "PF_X24_G8",
+    "PF_ETC1",
+    "PF_ETC2_RGB",
+    "PF_ETC2_RGBA",
+    "PF_R32G32B32A32_UINT",
+    "PF_R16G16_UINT",
+    "PF_ASTC_4x4",
+    "PF_ASTC_6x6",
+    "PF_ASTC_8x8",
+    "PF_ASTC_10x10",
+    "PF_ASTC_12x12",
+    "PF_BC6H",
+    "PF_BC7",
+    "PF_R8_UINT",
+    "PF_L8",
+    "PF_XGXR8",
+    "PF_R8G8B8A8_UINT",
+    "PF_R8G8B8A8_SNORM",
+    "PF_R16G16B16A16_UNORM",
+    "PF_R16G16B16A16_SNORM",
+    "PF_PLATFORM_HDR_0",
+    "PF_PLATFORM_HDR_1",
+    "PF_PLATFORM_HDR_2",
+    "PF_NV12",
+    "PF_R32G32_UINT",
+    "PF_ETC2_R11_EAC",
+    "PF_ETC2_RG11_EAC",
+    "PF_R8",
+    "PF_B5G5R5A1_UNORM",
+    "PF_ASTC_4x4_HDR",
+    "PF_ASTC_6x6_HDR",
+    "PF_ASTC_8x8_HDR",
+    "PF_ASTC_10x10_HDR",
+    "PF_ASTC_12x12_HDR",
+    "PF_G16R16_SNORM",
+    "PF_R8G8_UINT",
+    "PF_R32G32B32_UINT",
+    "PF_R32G32B32_SINT",
+    "PF_R32G32B32F",
+    "PF_R8_SINT",
+    "PF_R64_UINT",
+    "PF_R9G9B9EXP5",
+    "PF_P010",
+    "PF_ASTC_4x4_NORM_RG",
+    "PF_ASTC_6x6_NORM_RG",
+    "PF_ASTC_8x8_NORM_RG",
+    "PF_ASTC_10x10_NORM_RG",
+    "PF_ASTC_12x12_NORM_RG",
+];
+
+/// The decoder for an Unreal pixel format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnrealDecoder {
+    /// A format decoded by [`TextureFormat::decode()`]
+    Format(TextureFormat),
+    /// UYVY with BT.709 limited range colors
+    Uyvy,
+    /// NV12 with BT.709 limited range colors
+    Nv12,
+}
+
+/// The decoder of an Unreal pixel format and the interpretation of its channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnrealFormat {
+    pub decoder: UnrealDecoder,
+    pub numeric_format: NumericFormat,
+}
+
+/// Returns the `EPixelFormat` value of a name, with or without the `PF_` prefix.
+pub fn unreal_pixel_format_value(name: &str) -> Option<u32> {
+    let name = name.strip_prefix("PF_").unwrap_or(name);
+    UNREAL_PIXEL_FORMATS
+        .iter()
+        .position(|v| &v[3..] == name)
+        .map(|v| v as u32)
+}
+
+/// Returns the name of an `EPixelFormat` value.
+pub fn unreal_pixel_format_name(pixel_format: u32) -> Option<&'static str> {
+    UNREAL_PIXEL_FORMATS.get(pixel_format as usize).copied()
+}
+
+/// Maps an `EPixelFormat` value to its decoder.
+/// Unreal stores sRGB as a flag of the texture, srgb is that flag and applies to the color formats.
+pub fn unreal_format(pixel_format: u32, srgb: bool) -> Result<UnrealFormat, &'static str> {
+    let raw = |format| TextureFormat::Raw(format, Endian::Little);
+    // the block sizes 4x4, 6x6, 8x8, 10x10 and 12x12
+    let astc = |index: u32| {
+        let size = [4, 6, 8, 10, 12][index as usize];
+        TextureFormat::Astc(size, size)
+    };
+    let (format, numeric_format) = match pixel_format {
+        1 => (raw(RawFormat::RgbaFloat), NumericFormat::Sfloat),
+        // the D3D9 names list the channels from the most significant bits
+        2 | 38 => (raw(RawFormat::Bgra32), NumericFormat::Unorm),
+        3 | 58 => (raw(RawFormat::L8), NumericFormat::Unorm),
+        4 => (raw(RawFormat::L16), NumericFormat::Unorm),
+        5 => (TextureFormat::Bc1, NumericFormat::Unorm),
+        6 => (TextureFormat::Bc2, NumericFormat::Unorm),
+        7 => (TextureFormat::Bc3, NumericFormat::Unorm),
+        8 => {
+            return Ok(UnrealFormat {
+                decoder: UnrealDecoder::Uyvy,
+                numeric_format: NumericFormat::Unorm,
+            })
+        }
+        10 => (raw(RawFormat::RgbaHalf), NumericFormat::Sfloat),
+        13 => (raw(RawFormat::RFloat), NumericFormat::Sfloat),
+        15 | 16 => (raw(RawFormat::RgHalf), NumericFormat::Sfloat),
+        17 => (raw(RawFormat::RgFloat), NumericFormat::Sfloat),
+        18 => (raw(RawFormat::Rgb10A2), NumericFormat::Unorm),
+        21 | 22 => (raw(RawFormat::RHalf), NumericFormat::Sfloat),
+        23 => (TextureFormat::Bc5, NumericFormat::Unorm),
+        27 => (raw(RawFormat::Alpha8), NumericFormat::Unorm),
+        30 => (TextureFormat::Pvrtc2bpp, NumericFormat::Unorm),
+        31 => (TextureFormat::Pvrtc4bpp, NumericFormat::Unorm),
+        36 => (raw(RawFormat::Rgb565), NumericFormat::Unorm),
+        37 => (raw(RawFormat::Rgba32), NumericFormat::Unorm),
+        39 => (TextureFormat::Bc4, NumericFormat::Unorm),
+        40 => (raw(RawFormat::Rg16), NumericFormat::Unorm),
+        41 => (TextureFormat::AtcRgb4, NumericFormat::Unorm),
+        42 => (TextureFormat::AtcRgba8Explicit, NumericFormat::Unorm),
+        43 => (TextureFormat::AtcRgba8, NumericFormat::Unorm),
+        45 => (TextureFormat::Etc1, NumericFormat::Unorm),
+        46 => (TextureFormat::Etc2Rgb, NumericFormat::Unorm),
+        47 => (TextureFormat::Etc2Rgba8, NumericFormat::Unorm),
+        // LDR, HDR and normal map ASTC
+        50..=54 => (astc(pixel_format - 50), NumericFormat::Unorm),
+        73..=77 => (astc(pixel_format - 73), NumericFormat::Sfloat),
+        87..=91 => (astc(pixel_format - 87), NumericFormat::Unorm),
+        55 => (TextureFormat::Bc6Unsigned, NumericFormat::Ufloat),
+        56 => (TextureFormat::Bc7, NumericFormat::Unorm),
+        57 => (raw(RawFormat::R8), NumericFormat::Uint),
+        60 => (raw(RawFormat::Rgba32), NumericFormat::Uint),
+        67 => {
+            return Ok(UnrealFormat {
+                decoder: UnrealDecoder::Nv12,
+                numeric_format: NumericFormat::Unorm,
+            })
+        }
+        69 => (TextureFormat::EacR, NumericFormat::Unorm),
+        70 => (TextureFormat::EacRg, NumericFormat::Unorm),
+        71 => (raw(RawFormat::R8), NumericFormat::Unorm),
+        72 => (raw(RawFormat::Argb1555), NumericFormat::Unorm),
+        79 => (raw(RawFormat::Rg16), NumericFormat::Uint),
+        0..=91 => return Err("The Unreal pixel format isn't supported!"),
+        _ => return Err("Unknown Unreal pixel format!"),
+    };
+    let numeric_format =
+        if srgb && numeric_format == NumericFormat::Unorm && format.has_srgb_variant() {
+            NumericFormat::Srgb
+        } else {
+            numeric_format
+        };
+    Ok(UnrealFormat {
+        decoder: UnrealDecoder::Format(format),
+        numeric_format,
+    })
+}
+
+/// Decodes the data of a mip level of an Unreal texture with the given `EPixelFormat` value.
+pub fn decode_unreal_texture(
+    pixel_format: u32,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+) -> Result<(), &'static str> {
+    match unreal_format(pixel_format, false)?.decoder {
+        UnrealDecoder::Format(format) => format.decode(data, width, height, image),
+        UnrealDecoder::Uyvy => decode_uyvy(
+            data,
+            width,
+            height,
+            image,
+            YuvMatrix::Bt709,
+            YuvRange::Limited,
+        ),
+        UnrealDecoder::Nv12 => decode_nv12(
+            data,
+            width,
+            height,
+            image,
+            YuvMatrix::Bt709,
+            YuvRange::Limited,
+        ),
+    }
+}
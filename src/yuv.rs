@@ -0,0 +1,257 @@
+use crate::color::{color, Flip};
+use core::result::Result;
+
+/// The color matrix used to convert YUV to RGB.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YuvMatrix {
+    Bt601,
+    Bt709,
+}
+
+/// The value range of the YUV samples.
+/// Limited range uses 16-235 for luma and 16-240 for chroma, full range the whole byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YuvRange {
+    Full,
+    Limited,
+}
+
+// 16.16 fixed point factors: luma scale, v to red, u to green, v to green, u to blue
+static YUV_COEFFICIENTS: [[[i32; 5]; 2]; 2] = [
+    [
+        [65536, 91881, 22553, 46802, 116130],
+        [76309, 104597, 25675, 53279, 132201],
+    ],
+    [
+        [65536, 103206, 12276, 30679, 121609],
+        [76309, 117489, 13975, 34925, 138438],
+    ],
+];
+
+#[inline]
+const fn clamp(n: i32) -> u8 {
+    (if n < 0 {
+        0
+    } else if n > 255 {
+        255
+    } else {
+        n
+    }) as u8
+}
+
+#[inline]
+fn yuv_to_color(y: u8, u: u8, v: u8, matrix: YuvMatrix, range: YuvRange) -> u32 {
+    let [ys, rv, gu, gv, bu] = YUV_COEFFICIENTS[matrix as usize][range as usize];
+    let y = match range {
+        YuvRange::Full => y as i32,
+        YuvRange::Limited => y as i32 - 16,
+    } * ys
+        + 32768;
+    let u = u as i32 - 128;
+    let v = v as i32 - 128;
+    color(
+        clamp((y + rv * v) >> 16),
+        clamp((y - gu * u - gv * v) >> 16),
+        clamp((y + bu * u) >> 16),
+        255,
+    )
+}
+
+// packed 4:2:2, two pixels share one u and v sample
+#[allow(clippy::too_many_arguments)]
+fn decode_packed_422(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+    flip: Flip,
+    offsets: [usize; 4],
+) -> Result<(), &'static str> {
+    let [y0_offset, u_offset, y1_offset, v_offset] = offsets;
+    let row_size = width.div_ceil(2) * 4;
+
+    if data.len() < row_size * height {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < width * height {
+        return Err("Image buffer is too small!");
+    }
+
+    (0..height).for_each(|y| {
+        let row = &data[y * row_size..(y + 1) * row_size];
+        (0..width).for_each(|x| {
+            let macropixel = &row[(x >> 1) * 4..];
+            let luma = macropixel[if x & 1 == 0 { y0_offset } else { y1_offset }];
+            image[flip.pixel_index(x, y, width, height)] = yuv_to_color(
+                luma,
+                macropixel[u_offset],
+                macropixel[v_offset],
+                matrix,
+                range,
+            );
+        });
+    });
+    Ok(())
+}
+
+/// Decodes a YUY2 (YUYV) texture into an image
+pub fn decode_yuy2(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> Result<(), &'static str> {
+    decode_yuy2_flipped(data, width, height, image, matrix, range, Flip::NONE)
+}
+
+/// Decodes a YUY2 (YUYV) texture into an image mirrored by flip.
+pub fn decode_yuy2_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_packed_422(
+        data,
+        width,
+        height,
+        image,
+        matrix,
+        range,
+        flip,
+        [0, 1, 2, 3],
+    )
+}
+
+/// Decodes an UYVY texture into an image
+pub fn decode_uyvy(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> Result<(), &'static str> {
+    decode_uyvy_flipped(data, width, height, image, matrix, range, Flip::NONE)
+}
+
+/// Decodes an UYVY texture into an image mirrored by flip.
+pub fn decode_uyvy_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_packed_422(
+        data,
+        width,
+        height,
+        image,
+        matrix,
+        range,
+        flip,
+        [1, 0, 3, 2],
+    )
+}
+
+// planar 4:2:0, a 2x2 pixel square shares one u and v sample
+#[allow(clippy::too_many_arguments)]
+fn decode_planar_420(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+    flip: Flip,
+    interleaved: bool,
+) -> Result<(), &'static str> {
+    let chroma_width = width.div_ceil(2);
+    let chroma_size = chroma_width * height.div_ceil(2);
+    let luma_size = width * height;
+
+    if data.len() < luma_size + chroma_size * 2 {
+        return Err("Not enough data to decode image!");
+    }
+
+    if image.len() < luma_size {
+        return Err("Image buffer is too small!");
+    }
+
+    let luma = &data[..luma_size];
+    let chroma = &data[luma_size..];
+    (0..height).for_each(|y| {
+        (0..width).for_each(|x| {
+            let c = (y >> 1) * chroma_width + (x >> 1);
+            let (u, v) = if interleaved {
+                (chroma[c * 2], chroma[c * 2 + 1])
+            } else {
+                (chroma[c], chroma[chroma_size + c])
+            };
+            image[flip.pixel_index(x, y, width, height)] =
+                yuv_to_color(luma[y * width + x], u, v, matrix, range);
+        });
+    });
+    Ok(())
+}
+
+/// Decodes a NV12 texture, a luma plane followed by an interleaved u/v plane, into an image
+pub fn decode_nv12(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> Result<(), &'static str> {
+    decode_nv12_flipped(data, width, height, image, matrix, range, Flip::NONE)
+}
+
+/// Decodes a NV12 texture, a luma plane followed by an interleaved u/v plane, into an image mirrored by flip.
+pub fn decode_nv12_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_planar_420(data, width, height, image, matrix, range, flip, true)
+}
+
+/// Decodes an I420 texture, a luma plane followed by an u and a v plane, into an image
+pub fn decode_i420(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> Result<(), &'static str> {
+    decode_i420_flipped(data, width, height, image, matrix, range, Flip::NONE)
+}
+
+/// Decodes an I420 texture, a luma plane followed by an u and a v plane, into an image mirrored by flip.
+pub fn decode_i420_flipped(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    image: &mut [u32],
+    matrix: YuvMatrix,
+    range: YuvRange,
+    flip: Flip,
+) -> Result<(), &'static str> {
+    decode_planar_420(data, width, height, image, matrix, range, flip, false)
+}
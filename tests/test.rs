@@ -31,6 +31,20 @@ mod tests {
         test_format("ATC_RGBA_Explicit", "dds", decode_atc_rgba8)
     }
 
+    #[test]
+    fn test_ATC_RGBA_Explicit_alpha() {
+        // white block with the 4 bit alphas 0 and 15 alternating
+        let data: [u8; 16] = [
+            0xf0, 0xf0, 0xf0, 0xf0, 0xf0, 0xf0, 0xf0, 0xf0, 0xff, 0x7f, 0xff, 0xff, 0, 0, 0, 0,
+        ];
+        let mut image: Vec<u32> = vec![0; 16];
+        decode_atc_rgba8_explicit(&data, 4, 4, &mut image).unwrap();
+        for (i, pixel) in image.iter().enumerate() {
+            let a = if i % 2 == 0 { 0 } else { 255 };
+            assert_eq!(pixel.to_le_bytes(), [255, 255, 255, a]);
+        }
+    }
+
     #[test]
     fn test_ATC_RGBA_Interpolated() {
         test_format("ATC_RGBA_Interpolated", "dds", decode_atc_rgba8)
@@ -76,6 +90,44 @@ mod tests {
         test_format("ASTC_8x8", "ktx2", decode_astc_8_8)
     }
 
+    #[test]
+    fn test_ASTC_3x3x3() {
+        // single block with a 2x2x2 weight grid, the lower weight layer selects black,
+        // the upper one white, so the middle slice has to be interpolated along z
+        let data: [u8; 16] = [
+            0x01, 0x00, 0x01, 0xfe, 0x01, 0xfe, 0x01, 0xfe, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x0f,
+        ];
+        let mut image: Vec<u32> = vec![0; 27];
+        decode_astc_3_3_3(&data, 3, 3, 3, &mut image).unwrap();
+        for (z, v) in [0u8, 128, 255].iter().enumerate() {
+            for pixel in &image[z * 9..(z + 1) * 9] {
+                assert_eq!(pixel.to_le_bytes(), [*v, *v, *v, 255]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ASTC_3x3x3_partitioned() {
+        // two partitions with the even seed 18, partition 0 is black and partition 1 white,
+        // the expected layout follows the partition function of the specification
+        let data: [u8; 16] = [
+            0x01, 0x48, 0x02, 0x00, 0x00, 0xe0, 0xff, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+        let partitions: [u8; 27] = [
+            1, 1, 0, 0, 0, 0, 1, 1, 1, //
+            1, 0, 0, 0, 0, 0, 1, 1, 1, //
+            0, 0, 0, 0, 0, 0, 1, 1, 1,
+        ];
+        let mut image: Vec<u32> = vec![0; 27];
+        decode_astc_3_3_3(&data, 3, 3, 3, &mut image).unwrap();
+        for (pixel, partition) in image.iter().zip(partitions) {
+            let v = partition * 255;
+            assert_eq!(pixel.to_le_bytes(), [v, v, v, 255]);
+        }
+    }
+
     #[test]
     fn test_BC1() {
         test_format("BC1", "ktx2", decode_bc1)
@@ -116,6 +168,36 @@ mod tests {
         test_format("BC7", "ktx2", decode_bc7)
     }
 
+    #[test]
+    fn test_DXT2() {
+        test_format("DXT2", "ktx2", decode_dxt2);
+
+        // 4 bit alpha 0, 15, 8 and 7 in the first row, the color is RGB565 0x8410
+        let block = [
+            0xf0, 0x78, 0, 0, 0, 0, 0, 0, 0x10, 0x84, 0x10, 0x84, 0, 0, 0, 0,
+        ];
+        let mut pixels = [0u32; 16];
+        decode_dxt2_block(&block, &mut pixels);
+        assert_unpremultiplied(&pixels);
+        decode_dxt2_premultiplied_block(&block, &mut pixels);
+        assert_premultiplied(&pixels);
+    }
+
+    #[test]
+    fn test_DXT4() {
+        test_format("DXT4", "ktx2", decode_dxt4);
+
+        // the 6 alpha mode of 119 and 136 with the indices 6 (0), 7 (255), 1 and 0 in the first row
+        let block = [
+            119, 136, 0x7e, 0, 0, 0, 0, 0, 0x10, 0x84, 0x10, 0x84, 0, 0, 0, 0,
+        ];
+        let mut pixels = [0u32; 16];
+        decode_dxt4_block(&block, &mut pixels);
+        assert_unpremultiplied(&pixels);
+        decode_dxt4_premultiplied_block(&block, &mut pixels);
+        assert_premultiplied(&pixels);
+    }
+
     #[test]
     fn test_ETC1_RGB() {
         test_format("ETC1_RGB", "ktx2", decode_etc1)
@@ -231,6 +313,1829 @@ mod tests {
         test_format("UNITYCRUNCH_ETC2AS", "crn", decode_unity_crunch)
     }
 
+    #[test]
+    fn test_RAW() {
+        let half_one = half::f16::from_f32(1.0).to_bits();
+        let half_mid = half::f16::from_f32(0.5).to_bits();
+        let cases: Vec<(RawFormat, Endian, Vec<u8>, [u8; 4])> = vec![
+            (
+                RawFormat::Alpha8,
+                Endian::Little,
+                vec![0x80],
+                [255, 255, 255, 0x80],
+            ),
+            (RawFormat::R8, Endian::Little, vec![0x12], [0x12, 0, 0, 255]),
+            (
+                RawFormat::R16,
+                Endian::Big,
+                vec![0xab, 0xcd],
+                [0xab, 0, 0, 255],
+            ),
+            (
+                RawFormat::L16,
+                Endian::Little,
+                vec![0xcd, 0xab],
+                [0xab, 0xab, 0xab, 255],
+            ),
+            (RawFormat::Rg16, Endian::Little, vec![1, 2], [1, 2, 0, 255]),
+            (
+                RawFormat::Rgb24,
+                Endian::Little,
+                vec![1, 2, 3],
+                [1, 2, 3, 255],
+            ),
+            (
+                RawFormat::Bgr24,
+                Endian::Little,
+                vec![3, 2, 1],
+                [1, 2, 3, 255],
+            ),
+            (
+                RawFormat::Rgba32,
+                Endian::Little,
+                vec![1, 2, 3, 4],
+                [1, 2, 3, 4],
+            ),
+            (
+                RawFormat::Argb32,
+                Endian::Little,
+                vec![4, 1, 2, 3],
+                [1, 2, 3, 4],
+            ),
+            (
+                RawFormat::Bgra32,
+                Endian::Little,
+                vec![3, 2, 1, 4],
+                [1, 2, 3, 4],
+            ),
+            (
+                RawFormat::Rgb565,
+                Endian::Little,
+                vec![0x1f, 0xf8],
+                [255, 0, 255, 255],
+            ),
+            (
+                RawFormat::Rgb565,
+                Endian::Big,
+                vec![0x07, 0xe0],
+                [0, 255, 0, 255],
+            ),
+            (
+                RawFormat::Rgba4444,
+                Endian::Little,
+                vec![0x3c, 0xf0],
+                [255, 0, 0x33, 0xcc],
+            ),
+            (
+                RawFormat::Argb4444,
+                Endian::Little,
+                vec![0x3c, 0xf0],
+                [0, 0x33, 0xcc, 255],
+            ),
+            (
+                RawFormat::Rgba5551,
+                Endian::Little,
+                vec![0x01, 0xf8],
+                [255, 0, 0, 255],
+            ),
+            (
+                RawFormat::Argb1555,
+                Endian::Little,
+                vec![0x1f, 0x80],
+                [0, 0, 255, 255],
+            ),
+            (
+                RawFormat::Rgb10A2,
+                Endian::Little,
+                vec![0xff, 0x03, 0, 0xc0],
+                [255, 0, 0, 255],
+            ),
+            (
+                RawFormat::RgHalf,
+                Endian::Little,
+                [half_one.to_le_bytes(), half_mid.to_le_bytes()].concat(),
+                [255, 127, 0, 255],
+            ),
+            (
+                RawFormat::RgbaHalf,
+                Endian::Big,
+                [half_mid, half_one, 0, half_one]
+                    .iter()
+                    .flat_map(|h| h.to_be_bytes())
+                    .collect(),
+                [127, 255, 0, 255],
+            ),
+            (
+                RawFormat::RgbaFloat,
+                Endian::Little,
+                [1.0f32, 0.0, 2.0, 0.5]
+                    .iter()
+                    .flat_map(|f| f.to_le_bytes())
+                    .collect(),
+                [255, 0, 255, 127],
+            ),
+        ];
+        for (format, endian, data, [r, g, b, a]) in cases {
+            let mut image = [0u32; 1];
+            decode_raw(&data, 1, 1, &mut image, format, endian).unwrap();
+            assert_eq!(image[0].to_le_bytes(), [b, g, r, a], "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_YUV() {
+        let mut image = [0u32; 9];
+        // limited range white and black
+        decode_yuy2(
+            &[235, 128, 16, 128],
+            2,
+            1,
+            &mut image,
+            YuvMatrix::Bt601,
+            YuvRange::Limited,
+        )
+        .unwrap();
+        assert_eq!(image[0].to_le_bytes(), [255, 255, 255, 255]);
+        assert_eq!(image[1].to_le_bytes(), [0, 0, 0, 255]);
+        // full range red
+        decode_uyvy(
+            &[85, 76, 255, 76],
+            2,
+            1,
+            &mut image,
+            YuvMatrix::Bt601,
+            YuvRange::Full,
+        )
+        .unwrap();
+        assert_eq!(image[1].to_le_bytes(), [0, 0, 254, 255]);
+        // odd sizes, the last row and column use the last chroma samples
+        let mut nv12 = vec![128u8; 9];
+        nv12.extend_from_slice(&[128, 128, 128, 128, 128, 128, 0, 0]);
+        decode_nv12(&nv12, 3, 3, &mut image, YuvMatrix::Bt709, YuvRange::Full).unwrap();
+        assert_eq!(image[0].to_le_bytes(), [128, 128, 128, 255]);
+        assert_eq!(image[8].to_le_bytes(), [0, 212, 0, 255]);
+        let mut i420 = vec![16u8, 235, 235, 16];
+        i420.extend_from_slice(&[128, 128]);
+        decode_i420(&i420, 2, 2, &mut image, YuvMatrix::Bt709, YuvRange::Limited).unwrap();
+        assert_eq!(image[1].to_le_bytes(), [255, 255, 255, 255]);
+        assert_eq!(image[3].to_le_bytes(), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_PALETTE() {
+        let mut image = [0u32; 4];
+        // opaque RGB555 and translucent ARGB3444 entries
+        let palette = [0x80, 0x1f, 0x4f, 0x00];
+        decode_p4(
+            &[0x01, 0x10],
+            2,
+            2,
+            &mut image,
+            &palette,
+            PaletteFormat::Rgb5a3,
+            NibbleOrder::HighFirst,
+        )
+        .unwrap();
+        assert_eq!(image[0].to_le_bytes(), [255, 0, 0, 255]);
+        assert_eq!(image[1].to_le_bytes(), [0, 0, 255, 0x92]);
+        assert_eq!(image[2], image[1]);
+        assert_eq!(image[3], image[0]);
+        // the indices 8 and 16 refer to the swapped entries of a CSM1 palette
+        let mut palette = vec![0u8; 256 * 4];
+        palette[8 * 4..8 * 4 + 4].copy_from_slice(&[1, 2, 3, 4]);
+        palette[16 * 4..16 * 4 + 4].copy_from_slice(&[5, 6, 7, 8]);
+        decode_p8(
+            &[8, 16, 0, 255],
+            2,
+            2,
+            &mut image,
+            &palette,
+            PaletteFormat::Rgba8888,
+            true,
+        )
+        .unwrap();
+        assert_eq!(image[0].to_le_bytes(), [7, 6, 5, 8]);
+        assert_eq!(image[1].to_le_bytes(), [3, 2, 1, 4]);
+        // a truncated CSM1 palette leaves the entries swapped in from beyond its end empty
+        decode_p8(
+            &[8, 12, 16, 19],
+            2,
+            2,
+            &mut image,
+            &palette[..20 * 4],
+            PaletteFormat::Rgba8888,
+            true,
+        )
+        .unwrap();
+        assert_eq!(image[0].to_le_bytes(), [7, 6, 5, 8]);
+        assert_eq!(image[1], 0);
+        assert_eq!(image[2].to_le_bytes(), [3, 2, 1, 4]);
+        assert_eq!(image[3], 0);
+        let palette = [0x1f, 0x80, 0x00, 0x7c];
+        decode_p4(
+            &[0x10],
+            2,
+            1,
+            &mut image,
+            &palette,
+            PaletteFormat::Rgba5551,
+            NibbleOrder::LowFirst,
+        )
+        .unwrap();
+        assert_eq!(image[0].to_le_bytes(), [0, 0, 255, 255]);
+        assert_eq!(image[1].to_le_bytes(), [255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_DDS() {
+        fn dds_header(width: u32, height: u32, mips: u32, pixel_format: [u32; 8]) -> Vec<u8> {
+            let mut header = vec![0u8; 128];
+            header[0..4].copy_from_slice(b"DDS ");
+            for (i, v) in [124, 0x1007, height, width, 0, 0, mips].iter().enumerate() {
+                header[4 + i * 4..8 + i * 4].copy_from_slice(&v.to_le_bytes());
+            }
+            for (i, v) in pixel_format.iter().enumerate() {
+                header[76 + i * 4..80 + i * 4].copy_from_slice(&v.to_le_bytes());
+            }
+            header
+        }
+
+        // DX10 BC1 array of two 8x8 cubemaps with 2 mip levels
+        let mut data = dds_header(
+            8,
+            8,
+            2,
+            [32, 4, u32::from_le_bytes(*b"DX10"), 0, 0, 0, 0, 0],
+        );
+        for v in [71u32, 3, 4, 2, 0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.resize(148 + 2 * 6 * (32 + 8), 0);
+        let dds = container::dds::DdsFile::parse(&data).unwrap();
+        assert_eq!(dds.format, TextureFormat::Bc1a);
+        assert_eq!(dds.header.color_space(), ColorSpace::Linear);
+        assert_eq!(dds.surfaces().count(), 24);
+        let surface = dds.surface(1, 1, 5).unwrap();
+        assert_eq!((surface.width, surface.offset, surface.size), (4, 620, 8));
+        assert_eq!(dds.surfaces().nth(23), Some(surface));
+        assert!(container::dds::DdsFile::parse(&data[..data.len() - 1]).is_err());
+        // more mip levels than a 8x8 texture can have
+        data[28..32].copy_from_slice(&100u32.to_le_bytes());
+        assert!(container::dds::DdsFile::parse(&data).is_err());
+        // an array size that can't be stored in the data
+        data[28..32].copy_from_slice(&2u32.to_le_bytes());
+        data[140..144].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(container::dds::DdsFile::parse(&data).is_err());
+        // BC1_UNORM_SRGB
+        data[140..144].copy_from_slice(&2u32.to_le_bytes());
+        data[128..132].copy_from_slice(&72u32.to_le_bytes());
+        let dds = container::dds::DdsFile::parse(&data).unwrap();
+        assert_eq!(dds.header.color_space(), ColorSpace::Srgb);
+
+        // legacy BGRA32 with 2 mip levels
+        let mut data = dds_header(
+            2,
+            1,
+            2,
+            [32, 0x41, 0, 32, 0xff0000, 0xff00, 0xff, 0xff000000],
+        );
+        data.extend_from_slice(&[3, 2, 1, 4, 0, 0, 0, 0, 7, 6, 5, 8]);
+        let image = container::dds::decode_dds(&data, 1, 0).unwrap();
+        assert_eq!((image.width, image.height), (1, 1));
+        assert_eq!(image.image[0].to_le_bytes(), [7, 6, 5, 8]);
+
+        // the surfaces of the sample files have to match the data read by ddsfile
+        for name in ["ATC_RGB", "ATC_RGBA_Explicit", "ATC_RGBA_Interpolated"] {
+            let data = fs::read(get_texture_fp(&format!("{}.dds", name))).unwrap();
+            let reference = ddsfile::Dds::read(&data[..]).unwrap();
+            let dds = container::dds::DdsFile::parse(&data).unwrap();
+            assert_eq!(
+                (dds.header.width, dds.header.height),
+                (reference.get_width(), reference.get_height())
+            );
+            assert_eq!(
+                dds.header.mip_levels() as u32,
+                reference.get_num_mipmap_levels()
+            );
+            let surfaces: Vec<u8> = dds
+                .surfaces()
+                .flat_map(|surface| dds.surface_data(&surface).to_vec())
+                .collect();
+            assert_eq!(surfaces, reference.data);
+        }
+    }
+
+    #[test]
+    fn test_KTX() {
+        fn ktx_header(big_endian: bool, fields: [u32; 13]) -> Vec<u8> {
+            let mut header = vec![
+                0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+            ];
+            for v in fields {
+                header.extend_from_slice(&if big_endian {
+                    v.to_be_bytes()
+                } else {
+                    v.to_le_bytes()
+                });
+            }
+            header
+        }
+
+        // ETC1 cubemap with 2 mip levels, each face is padded to 4 bytes
+        let mut data = ktx_header(
+            false,
+            [0x04030201, 0, 1, 0, 0x8D64, 0x1907, 4, 4, 0, 0, 6, 2, 0],
+        );
+        for _ in 0..2 {
+            data.extend_from_slice(&8u32.to_le_bytes());
+            data.extend_from_slice(&[0; 6 * 8]);
+        }
+        let ktx = container::ktx::KtxFile::parse(&data).unwrap();
+        assert_eq!(ktx.format, TextureFormat::Etc1);
+        assert_eq!(ktx.surfaces().count(), 12);
+        let surface = ktx.surface(1, 0, 5).unwrap();
+        assert_eq!((surface.width, surface.offset, surface.size), (2, 160, 8));
+        assert_eq!(ktx.surfaces().nth(11), Some(surface));
+        assert!(container::ktx::KtxFile::parse(&data[..data.len() - 1]).is_err());
+        // the mip levels are clamped to the 3 levels of a 4x4 texture
+        data[56..60].copy_from_slice(&100u32.to_le_bytes());
+        let header = container::ktx::KtxHeader::parse(&data).unwrap();
+        assert_eq!(header.mip_levels(), 3);
+        assert!(container::ktx::KtxFile::parse(&data).is_err());
+
+        // big endian RGB565 with orientation metadata
+        let mut data = ktx_header(
+            true,
+            [
+                0x04030201, 0x8363, 2, 0x1907, 0x8D62, 0x1907, 2, 1, 0, 0, 1, 1, 28,
+            ],
+        );
+        data.extend_from_slice(&23u32.to_be_bytes());
+        data.extend_from_slice(b"KTXorientation\0S=r,T=d\0\0");
+        data.extend_from_slice(&4u32.to_be_bytes());
+        data.extend_from_slice(&[0xf8, 0x00, 0x00, 0x1f]);
+        let ktx = container::ktx::KtxFile::parse(&data).unwrap();
+        assert_eq!(
+            ktx.key_values().collect::<Vec<_>>(),
+            [(&b"KTXorientation"[..], &b"S=r,T=d\0"[..])]
+        );
+        assert_eq!(ktx.orientation(), Flip::NONE);
+        let image = container::ktx::decode_ktx(&data, 0, 0).unwrap();
+        assert_eq!(image.image[0].to_le_bytes(), [0, 0, 255, 255]);
+        assert_eq!(image.image[1].to_le_bytes(), [255, 0, 0, 255]);
+        // images stored right to left and bottom-up are mirrored back
+        let value = data.len() - 17;
+        data[value..value + 7].copy_from_slice(b"S=l,T=u");
+        let ktx = container::ktx::KtxFile::parse(&data).unwrap();
+        assert_eq!(ktx.orientation(), Flip::BOTH);
+        let image = container::ktx::decode_ktx(&data, 0, 0).unwrap();
+        assert_eq!(image.image[0].to_le_bytes(), [255, 0, 0, 255]);
+        assert_eq!(image.image[1].to_le_bytes(), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_KTX2() {
+        use container::ktx2::*;
+        use std::io::Write;
+
+        fn ktx2_file(vk_format: u32, size: u32, scheme: u32, dfd: u32, level: &[u8]) -> Vec<u8> {
+            let mut data = vec![
+                0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+            ];
+            // header, index and level index
+            for v in [
+                vk_format, 1, size, size, 0, 0, 1, 1, scheme, 104, 44, 148, 0,
+            ] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+            for v in [0u64, 0, 148, level.len() as u64, (size * size * 4) as u64] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+            // basic data format descriptor with a single sample
+            for v in [44u32, 0, 2 | 40 << 16, dfd, 0x0303, 0, 0, 0, 0, 0, 0] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+            data.extend_from_slice(level);
+            data
+        }
+
+        // undefined vkFormat, the premultiplied sRGB BC3 color model is read from the DFD
+        let data = ktx2_file(0, 4, 0, 130 | 1 << 8 | 2 << 16 | 1 << 24, &[0; 16]);
+        let ktx2 = Ktx2File::parse(&data).unwrap();
+        assert_eq!(ktx2.format, TextureFormat::Dxt4);
+        assert!(ktx2.dfd.is_srgb() && ktx2.dfd.is_premultiplied());
+        assert_eq!(ktx2.dfd.texel_block_dimensions, [4, 4, 1, 1]);
+        assert_eq!(ktx2.dfd.samples().count(), 1);
+
+        // zlib supercompressed RGBA8, with stored and huffman coded deflate blocks
+        let pixels: Vec<u8> = (0..32 * 32 * 4u32).map(|i| (i * i / 7) as u8).collect();
+        for compression in [flate2::Compression::none(), flate2::Compression::best()] {
+            let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), compression);
+            encoder.write_all(&pixels).unwrap();
+            let data = ktx2_file(37, 32, 3, 1 | 1 << 8 | 1 << 16, &encoder.finish().unwrap());
+            let image = decode_ktx2(&data, 0, 0).unwrap();
+            for (pixel, rgba) in image.image.iter().zip(pixels.chunks(4)) {
+                assert_eq!(pixel.to_le_bytes(), [rgba[2], rgba[1], rgba[0], rgba[3]]);
+            }
+        }
+
+        // sRGB ASTC is decoded with the sRGB decode mode
+        let texture = Texture::from_file(&get_texture_fp("ASTC_4x4.ktx2"));
+        let size = texture.width as usize;
+        let data = ktx2_file(158, size as u32, 0, 162 | 1 << 8 | 2 << 16, &texture.data);
+        let ktx2 = Ktx2File::parse(&data).unwrap();
+        assert_eq!(ktx2.color_space(), ColorSpace::Srgb);
+        let mut srgb = vec![0u32; size * size];
+        decode_astc_srgb(&texture.data, size, size, 4, 4, &mut srgb).unwrap();
+        let mut linear = vec![0u32; size * size];
+        decode_astc_4_4(&texture.data, size, size, &mut linear).unwrap();
+        assert!(srgb != linear);
+        assert!(decode_ktx2(&data, 0, 0).unwrap().image == srgb);
+        let data = ktx2_file(157, size as u32, 0, 162 | 1 << 8 | 1 << 16, &texture.data);
+        assert_eq!(
+            Ktx2File::parse(&data).unwrap().color_space(),
+            ColorSpace::Linear
+        );
+        assert!(decode_ktx2(&data, 0, 0).unwrap().image == linear);
+
+        // zstd isn't supported
+        let data = ktx2_file(37, 32, 2, 1 | 1 << 8 | 1 << 16, &pixels);
+        assert_eq!(
+            decode_ktx2(&data, 0, 0).unwrap_err(),
+            Ktx2Error::UnsupportedSupercompression(SupercompressionScheme::Zstandard)
+        );
+
+        // the zlib stream inflates to more than the uncompressed byte length of the level
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&pixels).unwrap();
+        encoder.write_all(&pixels).unwrap();
+        let data = ktx2_file(37, 32, 3, 1 | 1 << 8 | 1 << 16, &encoder.finish().unwrap());
+        assert_eq!(
+            decode_ktx2(&data, 0, 0).unwrap_err(),
+            Ktx2Error::Invalid("The zlib stream is larger than expected!")
+        );
+
+        // level ranges that overflow and more mip levels than a 4x4 texture can have
+        let mut data = ktx2_file(0, 4, 0, 130 | 1 << 8 | 2 << 16 | 1 << 24, &[0; 16]);
+        data[80..88].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Ktx2File::parse(&data).is_err());
+        data[80..88].copy_from_slice(&148u64.to_le_bytes());
+        data[40..44].copy_from_slice(&100u32.to_le_bytes());
+        assert_eq!(Ktx2Header::parse(&data).unwrap().mip_levels(), 3);
+        assert!(Ktx2File::parse(&data).is_err());
+
+        // the levels of the sample files have to match the data read by ktx2
+        for name in ["BC1", "BC7", "ETC2_RGBA", "ASTC_6x6", "PVRTCI_4bpp_RGBA"] {
+            let data = fs::read(get_texture_fp(&format!("{}.ktx2", name))).unwrap();
+            let reader = ktx2::Reader::new(&data[..]).unwrap();
+            let ktx2 = Ktx2File::parse(&data).unwrap();
+            assert_eq!(
+                (ktx2.header.pixel_width, ktx2.header.pixel_height),
+                (reader.header().pixel_width, reader.header().pixel_height)
+            );
+            assert_eq!(
+                ktx2.header.mip_levels(),
+                reader.header().level_count.max(1) as usize
+            );
+            for (level, reference) in reader.levels().enumerate() {
+                assert_eq!(ktx2.level_data(level), reference);
+            }
+        }
+    }
+
+    #[test]
+    fn test_PVR() {
+        use container::pvr::*;
+
+        // v3 RGBA8 array with 2 elements and 2 mip levels, and an orientation metadata block
+        let mut data = Vec::new();
+        for v in [0x03525650u32, 0, u32::from_le_bytes(*b"rgba"), 0x08080808] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in [0u32, 0, 2, 2, 1, 2, 1, 2, 15] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in [0x03525650u32, 3, 3] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(&[0, 1, 0]);
+        let pixels: Vec<u8> = (0..40).collect();
+        data.extend_from_slice(&pixels);
+        let pvr = PvrFile::parse(&data).unwrap();
+        assert_eq!(
+            pvr.format,
+            TextureFormat::Raw(RawFormat::Rgba32, Endian::Little)
+        );
+        assert_eq!(
+            pvr.meta_data().collect::<Vec<_>>(),
+            [(0x03525650, 3, &[0, 1, 0][..])]
+        );
+        let surface = pvr.surface(1, 1, 0).unwrap();
+        assert_eq!((surface.width, surface.offset, surface.size), (1, 103, 4));
+        assert_eq!(pvr.surfaces().nth(3), Some(surface));
+        // the orientation metadata marks the image as bottom-up, so the rows are flipped
+        assert_eq!(pvr.orientation(), Flip::VERTICAL);
+        let image = decode_pvr(&data, 0, 1).unwrap();
+        assert_eq!(image.image[0].to_le_bytes(), [26, 25, 24, 27]);
+        assert!(PvrFile::parse(&data[..data.len() - 1]).is_err());
+        // the mip levels are clamped to the 2 levels of a 2x2 texture
+        data[44..48].copy_from_slice(&100u32.to_le_bytes());
+        assert_eq!(PvrFile::parse(&data).unwrap().header.mip_levels(), 2);
+
+        // PVRTC-II is identified, but can't be decoded
+        data[8..16].copy_from_slice(&5u64.to_le_bytes());
+        let header = PvrHeader::parse(&data).unwrap();
+        assert_eq!(header.pixel_format(), Some(PvrPixelFormat::Pvrtc2_4bpp));
+        assert_eq!(header.format(), None);
+
+        // legacy v2 PVRTC 4bpp cubemap with 2 mip levels, the faces store all of their levels
+        let mut data = Vec::new();
+        for v in [
+            52u32,
+            16,
+            16,
+            1,
+            0x19 | 0x100 | 0x200 | 0x1000,
+            960,
+            4,
+            0,
+            0,
+            0,
+            0,
+        ] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(b"PVR!");
+        data.extend_from_slice(&6u32.to_le_bytes());
+        data.resize(52 + 6 * (128 + 32), 0);
+        let pvr = PvrFile::parse(&data).unwrap();
+        assert_eq!(pvr.format, TextureFormat::Pvrtc4bpp);
+        assert_eq!((pvr.header.faces(), pvr.header.array_size()), (6, 1));
+        let surface = pvr.surface(1, 0, 2).unwrap();
+        assert_eq!(
+            (surface.width, surface.offset, surface.size),
+            (8, 52 + 2 * 160 + 128, 32)
+        );
+        assert_eq!(pvr.surfaces().nth(5), Some(surface));
+        assert_eq!(pvr.orientation(), Flip::NONE);
+
+        // legacy v2 RGBA8 texture stored bottom-up
+        let mut data = Vec::new();
+        for v in [52u32, 2, 2, 0, 0x12 | 0x10000, 16, 32, 0, 0, 0, 0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(b"PVR!");
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend(0..16u8);
+        let pvr = PvrFile::parse(&data).unwrap();
+        assert_eq!(pvr.orientation(), Flip::VERTICAL);
+        let image = decode_pvr(&data, 0, 0).unwrap();
+        assert_eq!(image.image[0].to_le_bytes(), [10, 9, 8, 11]);
+        assert_eq!(image.image[3].to_le_bytes(), [6, 5, 4, 7]);
+
+        // v3 3x3x3 volume of a single 3x3x3 ASTC block, black to white along z
+        let mut data = Vec::new();
+        for v in [0x03525650u32, 0, 41, 0, 0, 0, 3, 3, 3, 1, 1, 1, 0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(&[
+            0x01, 0x00, 0x01, 0xfe, 0x01, 0xfe, 0x01, 0xfe, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x0f,
+        ]);
+        let pvr = PvrFile::parse(&data).unwrap();
+        assert_eq!(pvr.block_depth(), 3);
+        let image = decode_pvr(&data, 0, 0).unwrap();
+        assert_eq!((image.width, image.height), (3, 9));
+        for (z, v) in [0u8, 128, 255].iter().enumerate() {
+            for pixel in &image.image[z * 9..(z + 1) * 9] {
+                assert_eq!(pixel.to_le_bytes(), [*v, *v, *v, 255]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ASTC_file() {
+        use container::astc::*;
+
+        // 6x6x2 texture of 4x4x4 blocks, each a void-extent block with a constant color
+        let mut data = vec![0x13, 0xAB, 0xA1, 0x5C, 4, 4, 4, 6, 0, 0, 6, 0, 0, 2, 0, 0];
+        for _ in 0..4 {
+            data.extend_from_slice(&[0xFC, 0xFD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+            data.extend_from_slice(&[0x00, 0xFF, 0x00, 0x80, 0x00, 0x00, 0xFF, 0xFF]);
+        }
+        let header = AstcHeader::parse(&data).unwrap();
+        assert_eq!((header.width, header.height, header.depth), (6, 6, 2));
+        assert_eq!((header.format(), header.data_size()), (None, Some(64)));
+        let image = decode_astc_file(&data).unwrap();
+        assert_eq!((image.width, image.height), (6, 12));
+        assert!(image
+            .image
+            .iter()
+            .all(|pixel| pixel.to_le_bytes() == [0, 128, 255, 255]));
+        assert!(AstcHeader::parse(&data[1..]).is_err());
+        // only the block sizes of the specification are accepted
+        for block_size in [[1, 1, 1], [4, 4, 2], [255, 255, 1], [4, 6, 1], [7, 7, 7]] {
+            let mut invalid = data.clone();
+            invalid[4..7].copy_from_slice(&block_size);
+            assert!(AstcHeader::parse(&invalid).is_err());
+            assert!(decode_astc_file(&invalid).is_err());
+        }
+        let header = AstcHeader {
+            block_width: 1,
+            block_height: 1,
+            block_depth: 1,
+            ..header
+        };
+        assert_eq!(header.format(), None);
+        // the size is checked before the image is allocated
+        data[7..16].fill(0xff);
+        assert!(decode_astc_file(&data).is_err());
+    }
+
+    #[test]
+    fn test_PKM() {
+        use container::pkm::*;
+
+        // 6x5 ETC1 texture padded to 8x8
+        let mut data = b"PKM 10\0\0\0\x08\0\x08\0\x06\0\x05".to_vec();
+        let blocks: Vec<u8> = (0..32u32).map(|i| (i * 37 + 11) as u8).collect();
+        data.extend_from_slice(&blocks);
+        let header = PkmHeader::parse(&data).unwrap();
+        assert_eq!(header.format(), Some(TextureFormat::Etc1));
+        assert_eq!(header.data_size(), Some(32));
+
+        let mut expected = vec![0u32; 8 * 8];
+        decode_etc1(&blocks, 8, 8, &mut expected).unwrap();
+        let image = decode_pkm(&data).unwrap();
+        assert_eq!((image.width, image.height), (6, 5));
+        for y in 0..5 {
+            assert_eq!(image.image[y * 6..y * 6 + 6], expected[y * 8..y * 8 + 6]);
+        }
+        let mut large = data[..PkmHeader::SIZE].to_vec();
+        large[8..16].fill(0xff);
+        assert!(decode_pkm(&large).is_err());
+        // empty images are rejected
+        for offset in [12, 14] {
+            let mut empty = data.clone();
+            empty[offset..offset + 2].fill(0);
+            assert!(PkmHeader::parse(&empty).is_err());
+            assert!(decode_pkm(&empty).is_err());
+        }
+
+        // ETC2 formats require version 2.0
+        data[7] = 3;
+        assert_eq!(PkmHeader::parse(&data).unwrap().format(), None);
+        data[5] = b'0';
+        data[4] = b'2';
+        assert_eq!(
+            PkmHeader::parse(&data).unwrap().format(),
+            Some(TextureFormat::Etc2Rgba8)
+        );
+    }
+
+    #[test]
+    fn test_detect() {
+        for (name, decode_func) in [
+            ("BC1.ktx2", decode_bc1a as DecodeFunction),
+            ("ATC_RGB.dds", decode_atc_rgb4),
+            ("CRUNCH_DXT1.crn", decode_crunch),
+            ("UNITYCRUNCH_ETC1.crn", decode_unity_crunch),
+        ] {
+            let data = fs::read(get_texture_fp(name)).unwrap();
+            let texture = Texture::from_file(&get_texture_fp(name));
+            let mut expected = vec![0u32; (texture.width * texture.height) as usize];
+            decode_func(
+                &texture.data,
+                texture.width as usize,
+                texture.height as usize,
+                &mut expected,
+            )
+            .unwrap();
+            let image = container::decode_any(&data).unwrap();
+            assert_eq!(
+                (image.width as u32, image.height as u32),
+                (texture.width, texture.height)
+            );
+            assert!(image.image == expected, "{}", name);
+        }
+
+        let data = fs::read(get_texture_fp("CRUNCH_DXT1.crn")).unwrap();
+        assert!(matches!(
+            container::detect(&data),
+            Some(container::Container::Crunch(_))
+        ));
+        let data = fs::read(get_texture_fp("BASISU_ETC1S.ktx2")).unwrap();
+        assert!(matches!(
+            container::detect(&data),
+            Some(container::Container::Ktx2(_))
+        ));
+        assert!(container::decode_any(&data).is_err());
+        assert!(container::detect(b"not a texture").is_none());
+
+        let mut data = b"sB".to_vec();
+        data.resize(77, 0);
+        data[4] = 77;
+        data[20] = 1;
+        match container::detect(&data) {
+            Some(container::Container::Basis(header)) => {
+                assert_eq!(
+                    header.tex_format,
+                    container::basis::BasisTexFormat::Uastc4x4
+                )
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unity() {
+        use texture2ddecoder::unity::*;
+
+        for (name, format_id, unity_version, decode_func) in [
+            (
+                "ETC1_RGB.ktx2",
+                34,
+                (2019, 4),
+                decode_etc1 as DecodeFunction,
+            ),
+            ("CRUNCH_DXT1.crn", 28, (5, 6), decode_crunch),
+            ("UNITYCRUNCH_DXT1.crn", 28, (2017, 3), decode_unity_crunch),
+            ("UNITYCRUNCH_ETC1.crn", 64, (2020, 1), decode_unity_crunch),
+        ] {
+            let texture = Texture::from_file(&get_texture_fp(name));
+            let (width, height) = (texture.width as usize, texture.height as usize);
+            let mut expected = vec![0u32; width * height];
+            decode_func(&texture.data, width, height, &mut expected).unwrap();
+            let mut image = vec![0u32; width * height];
+            decode_unity_texture(
+                format_id,
+                unity_version,
+                &texture.data,
+                width,
+                height,
+                &mut image,
+            )
+            .unwrap();
+            assert!(image == expected, "{}", name);
+        }
+
+        assert_eq!(
+            unity_decoder(55, (2021, 3)),
+            Ok(UnityDecoder::Format(TextureFormat::Astc(5, 5)))
+        );
+        assert_eq!(unity_decoder(29, (2017, 2)), Ok(UnityDecoder::Crunch));
+        assert_eq!(unity_texture_format_name(22), Some("RGB9e5Float"));
+        assert!(unity_decoder(22, (2021, 3)).is_err());
+        assert!(unity_decoder(1000, (2021, 3)).is_err());
+    }
+
+    #[test]
+    fn test_unreal() {
+        use texture2ddecoder::unreal::*;
+
+        for (name, pixel_format, decode_func) in [
+            ("BC7.ktx2", "PF_BC7", decode_bc7 as DecodeFunction),
+            ("ETC2_RGBA.ktx2", "ETC2_RGBA", decode_etc2_rgba8),
+            ("ASTC_6x6.ktx2", "PF_ASTC_6x6", decode_astc_6_6),
+        ] {
+            let texture = Texture::from_file(&get_texture_fp(name));
+            let (width, height) = (texture.width as usize, texture.height as usize);
+            let mut expected = vec![0u32; width * height];
+            decode_func(&texture.data, width, height, &mut expected).unwrap();
+            let mut image = vec![0u32; width * height];
+            let pixel_format = unreal_pixel_format_value(pixel_format).unwrap();
+            decode_unreal_texture(pixel_format, &texture.data, width, height, &mut image).unwrap();
+            assert!(image == expected, "{}", name);
+        }
+
+        assert_eq!(unreal_pixel_format_name(55), Some("PF_BC6H"));
+        assert_eq!(
+            unreal_format(55, true),
+            Ok(UnrealFormat {
+                decoder: UnrealDecoder::Format(TextureFormat::Bc6Unsigned),
+                numeric_format: NumericFormat::Ufloat,
+            })
+        );
+        assert_eq!(
+            unreal_format(5, true).unwrap().numeric_format,
+            NumericFormat::Srgb
+        );
+        assert_eq!(
+            unreal_format(23, true).unwrap().numeric_format,
+            NumericFormat::Unorm
+        );
+        assert!(unreal_format(26, false).is_err());
+        assert!(unreal_format(1000, false).is_err());
+    }
+
+    #[test]
+    fn test_api() {
+        use texture2ddecoder::api::*;
+
+        for table in [
+            &DXGI_FORMATS[..],
+            &VK_FORMATS,
+            &GL_INTERNAL_FORMATS,
+            &MTL_PIXEL_FORMATS,
+        ] {
+            for (_, descriptor) in table {
+                let value = table.iter().find(|(_, d)| d == descriptor).unwrap().0;
+                assert_eq!(
+                    table.iter().find(|(v, _)| *v == value).unwrap().1,
+                    *descriptor
+                );
+            }
+        }
+
+        let bc7_srgb = FormatDescriptor::new(TextureFormat::Bc7, NumericFormat::Srgb);
+        assert_eq!(from_dxgi_format(99), Some(bc7_srgb));
+        assert_eq!(to_vk_format(bc7_srgb), Some(146));
+        assert_eq!(to_gl_internal_format(bc7_srgb), Some(0x8E8D));
+        assert_eq!(to_mtl_pixel_format(bc7_srgb), Some(153));
+
+        let astc_hdr = FormatDescriptor::new(TextureFormat::Astc(8, 6), NumericFormat::Sfloat);
+        assert_eq!(from_vk_format(1000066006), Some(astc_hdr));
+        assert_eq!(to_mtl_pixel_format(astc_hdr), Some(229));
+        assert_eq!(
+            from_mtl_pixel_format(151),
+            Some(FormatDescriptor::new(
+                TextureFormat::Bc6Unsigned,
+                NumericFormat::Ufloat
+            ))
+        );
+        assert_eq!(
+            to_gl_internal_format(FormatDescriptor::new(
+                TextureFormat::Pvrtc4bpp,
+                NumericFormat::Unorm
+            )),
+            Some(0x8C02)
+        );
+        assert_eq!(
+            from_vk_format(154).map(|d| d.format),
+            Some(TextureFormat::EacRSigned)
+        );
+        assert_eq!(from_dxgi_format(81), None);
+    }
+
+    #[test]
+    fn test_tegra() {
+        use texture2ddecoder::swizzle::tegra::*;
+
+        assert_eq!(block_linear_offset(16, 0, 1, 0), 32);
+        assert_eq!(block_linear_offset(0, 1, 1, 0), 16);
+        assert_eq!(block_linear_offset(64, 0, 2, 0), 512);
+        assert_eq!(block_linear_offset(0, 8, 2, 1), 512);
+        assert_eq!(block_height_log2_mip0(16), 1);
+        assert_eq!(block_height_log2_mip0(256), 4);
+        assert_eq!(mip_block_height_log2(8, 1), 0);
+        assert_eq!(mip_offset(TextureFormat::Bc1, 64, 64, 2, 1), 2048 + 512);
+
+        let texture = Texture::from_file(&get_texture_fp("BC7.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let (width_in_blocks, height_in_blocks) = (width.div_ceil(4), height.div_ceil(4));
+        let block_height_log2 = block_height_log2_mip0(height_in_blocks);
+        let mut swizzled =
+            vec![0u8; swizzled_size(width_in_blocks, height_in_blocks, 16, block_height_log2)];
+        swizzle_block_linear(
+            width_in_blocks,
+            height_in_blocks,
+            16,
+            block_height_log2,
+            &texture.data,
+            &mut swizzled,
+        )
+        .unwrap();
+        assert!(swizzled[..texture.data.len()] != texture.data[..]);
+
+        let mut expected = vec![0u32; width * height];
+        decode_bc7(&texture.data, width, height, &mut expected).unwrap();
+        let decoded = decode_tegra(
+            TextureFormat::Bc7,
+            &swizzled,
+            width,
+            height,
+            0,
+            block_height_log2,
+        )
+        .unwrap();
+        assert!(decoded.image == expected);
+    }
+
+    #[test]
+    fn test_gnf() {
+        use texture2ddecoder::container::gnf::*;
+        use texture2ddecoder::swizzle::gnm::*;
+
+        assert_eq!(micro_tile_index(GnmTileMode::Thin1dThin, 1, 0, 8), 1);
+        assert_eq!(micro_tile_index(GnmTileMode::Thin1dThin, 0, 1, 8), 2);
+        assert_eq!(micro_tile_index(GnmTileMode::Display1dThin, 0, 1, 4), 4);
+
+        // offsets of addrlib's ComputeSurfaceAddrFromCoordMacroTiled for 8 pipes and 16 banks
+        let offset = |x, y, layer| element_offset(GnmTileMode::Thin2dThin, x, y, layer, 128, 8);
+        assert_eq!(offset(1, 0, 0), 8);
+        assert_eq!(offset(8, 0, 0), 256);
+        assert_eq!(offset(16, 0, 0), 768);
+        assert_eq!(offset(0, 8, 0), 16640);
+        assert_eq!(offset(64, 0, 0), 2048);
+        assert_eq!(offset(0, 64, 0), 71680);
+        assert_eq!(offset(0, 0, 1), 14336);
+        // 16 byte elements use 8 banks
+        let offset = |x, y| element_offset(GnmTileMode::Display2dThin, x, y, 0, 64, 16);
+        assert_eq!(offset(0, 1), 16);
+        assert_eq!(offset(1, 0), 32);
+        assert_eq!(offset(8, 8), 8192);
+        assert_eq!(macro_tile_size(1), (128, 256));
+        assert_eq!(macro_tile_size(8), (128, 64));
+        assert_eq!(macro_tile_size(16), (64, 64));
+
+        for tile_mode in [GnmTileMode::Display2dThin, GnmTileMode::Thin2dThin] {
+            for bytes_per_element in [1, 2, 4, 8, 16] {
+                let format = TextureFormat::Raw(
+                    match bytes_per_element {
+                        1 => RawFormat::R8,
+                        2 => RawFormat::Rgb565,
+                        4 => RawFormat::Rgba32,
+                        8 => RawFormat::RgbaHalf,
+                        _ => RawFormat::RgbaFloat,
+                    },
+                    Endian::Little,
+                );
+                let surface = GnmSurface {
+                    format,
+                    tile_mode,
+                    width: 300,
+                    height: 260,
+                    pitch: 0,
+                    array_size: 2,
+                    pow2_pad: false,
+                };
+                let layout = surface.mip_layout(0);
+                assert_eq!(layout.tile_mode, tile_mode);
+                assert_eq!(surface.mip_layout(3).tile_mode, tile_mode.micro_tiled());
+                for layer in 0..2 {
+                    let mut offsets = (0..layout.pitch * layout.padded_height)
+                        .map(|i| {
+                            element_offset(
+                                tile_mode,
+                                i % layout.pitch,
+                                i / layout.pitch,
+                                layer,
+                                layout.pitch,
+                                bytes_per_element,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    offsets.sort();
+                    assert!(
+                        offsets
+                            .iter()
+                            .copied()
+                            .eq((0..layout.size).step_by(bytes_per_element)),
+                        "{:?} {}",
+                        tile_mode,
+                        bytes_per_element
+                    );
+                }
+            }
+        }
+
+        let texture = Texture::from_file(&get_texture_fp("BC1.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let surface = GnmSurface {
+            format: TextureFormat::Bc1,
+            tile_mode: GnmTileMode::Thin1dThin,
+            width,
+            height,
+            pitch: 0,
+            array_size: 1,
+            pow2_pad: false,
+        };
+        let layout = surface.mip_layout(0);
+        assert_eq!(layout.pitch % 8, 0);
+        let mut tiled = vec![0u8; layout.size];
+        for y in 0..layout.height {
+            for x in 0..layout.width {
+                let tile = (y / 8) * (layout.pitch / 8) + x / 8;
+                let dst = (tile * 64 + micro_tile_index(surface.tile_mode, x % 8, y % 8, 8)) * 8;
+                let src = (y * layout.width + x) * 8;
+                tiled[dst..dst + 8].copy_from_slice(&texture.data[src..src + 8]);
+            }
+        }
+
+        let mut regs = [0u32; 8];
+        regs[1] = 35 << 20;
+        regs[2] = (width as u32 - 1) | (height as u32 - 1) << 14;
+        regs[3] = 13 << 20 | 9 << 28;
+        regs[4] = (layout.pitch as u32 * 4 - 1) << 13;
+        regs[7] = tiled.len() as u32;
+        let mut data = b"GNF ".to_vec();
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&[2, 1, 8, 0]);
+        data.extend_from_slice(&(48 + tiled.len() as u32).to_le_bytes());
+        regs.iter()
+            .for_each(|reg| data.extend_from_slice(&reg.to_le_bytes()));
+        data.extend_from_slice(&tiled);
+
+        let file = GnfFile::parse(&data).unwrap();
+        let descriptor = file.texture(0).unwrap();
+        assert_eq!((descriptor.width(), descriptor.height()), (width, height));
+        assert_eq!(
+            descriptor.surface().unwrap(),
+            GnmSurface {
+                pitch: layout.pitch * 4,
+                ..surface
+            }
+        );
+
+        let mut expected = vec![0u32; width * height];
+        decode_bc1(&texture.data, width, height, &mut expected).unwrap();
+        assert!(decode_gnf(&data, 0, 0, 0).unwrap().image == expected);
+        assert!(
+            texture2ddecoder::container::decode_any(&data)
+                .unwrap()
+                .image
+                == expected
+        );
+        assert!(decode_gnf(&data, 1, 0, 0).is_err());
+
+        // a 2D tiled array texture of 32 bit pixels
+        let (width, height) = (130, 70);
+        let surface = GnmSurface {
+            format: TextureFormat::Raw(RawFormat::Rgba32, Endian::Little),
+            tile_mode: GnmTileMode::Thin2dThin,
+            width,
+            height,
+            pitch: 0,
+            array_size: 2,
+            pow2_pad: false,
+        };
+        let layout = surface.mip_layout(0);
+        assert_eq!((layout.pitch, layout.padded_height), (256, 128));
+        let pixels = (0..width * height * 4)
+            .map(|i| (i * 7 % 251) as u8)
+            .collect::<Vec<_>>();
+        let mut tiled = vec![0u8; layout.size * 2];
+        for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+            let dst = layout.size
+                + element_offset(surface.tile_mode, i % width, i / width, 1, layout.pitch, 4);
+            tiled[dst..dst + 4].copy_from_slice(pixel);
+        }
+        let mut regs = [0u32; 8];
+        regs[1] = 10 << 20;
+        regs[2] = (width as u32 - 1) | (height as u32 - 1) << 14;
+        regs[3] = 4 | 5 << 3 | 6 << 6 | 7 << 9 | 14 << 20 | 13 << 28;
+        regs[4] = (width as u32 - 1) << 13;
+        regs[5] = 1 << 13;
+        regs[7] = tiled.len() as u32;
+        let mut data = b"GNF ".to_vec();
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&[2, 1, 8, 0]);
+        data.extend_from_slice(&(48 + tiled.len() as u32).to_le_bytes());
+        regs.iter()
+            .for_each(|reg| data.extend_from_slice(&reg.to_le_bytes()));
+        data.extend_from_slice(&tiled);
+        let expected = surface
+            .format
+            .decode_image(&pixels, width, height)
+            .unwrap()
+            .image;
+        assert!(decode_gnf(&data, 0, 0, 1).unwrap().image == expected);
+    }
+
+    #[test]
+    fn test_xenos() {
+        use texture2ddecoder::swizzle::xenos::*;
+
+        assert_eq!(tiled_offset(1, 0, 32, 8), 1);
+        assert_eq!(tiled_offset(8, 0, 32, 8), 8);
+        // values of XGAddress2DTiledOffset
+        assert_eq!(tiled_offset(0, 1, 64, 8), 2);
+        assert_eq!(tiled_offset(0, 2, 64, 8), 64);
+        assert_eq!(tiled_offset(0, 8, 64, 8), 528);
+        assert_eq!(tiled_offset(8, 8, 64, 8), 536);
+        assert_eq!(tiled_offset(0, 16, 64, 8), 256);
+        assert_eq!(tiled_offset(16, 0, 64, 8), 16);
+        assert_eq!(tiled_offset(32, 0, 64, 8), 1024);
+        assert_eq!(tiled_offset(0, 32, 64, 8), 2048);
+        assert_eq!(tiled_offset(0, 1, 32, 4), 4);
+        assert_eq!(tiled_offset(1, 1, 32, 4), 5);
+        assert_eq!(tiled_offset(4, 0, 32, 4), 8);
+        for bytes_per_block in [4, 8, 16] {
+            let mut offsets = (0..32 * 32)
+                .map(|i| tiled_offset(i % 32, i / 32, 32, bytes_per_block))
+                .collect::<Vec<_>>();
+            offsets.sort();
+            assert!(offsets.iter().copied().eq(0..32 * 32));
+        }
+
+        let texture = Texture::from_file(&get_texture_fp("BC1.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let (width_in_blocks, height_in_blocks) = (width.div_ceil(4), height.div_ceil(4));
+        let (padded_width, padded_height) = padded_size(width_in_blocks, height_in_blocks);
+        let mut tiled = vec![0u8; padded_width * padded_height * 8];
+        for y in 0..height_in_blocks {
+            for x in 0..width_in_blocks {
+                let dst = tiled_offset(x, y, padded_width, 8) * 8;
+                let src = (y * width_in_blocks + x) * 8;
+                tiled[dst..dst + 8].copy_from_slice(&texture.data[src..src + 8]);
+            }
+        }
+        XenosEndian::Swap8In16.swap(&mut tiled);
+
+        let mut expected = vec![0u32; width * height];
+        decode_bc1(&texture.data, width, height, &mut expected).unwrap();
+        let decoded = decode_xenos(TextureFormat::Bc1, &tiled, width, height).unwrap();
+        assert!(decoded.image == expected);
+        assert!(
+            decode_xenos(TextureFormat::Bc1, &tiled[..tiled.len() - 1], width, height).is_err()
+        );
+    }
+
+    #[test]
+    fn test_vita_psp() {
+        use texture2ddecoder::swizzle::{morton_index, psp::*, vita::*};
+
+        assert_eq!(morton_index(1, 0, 4, 4), 2);
+        assert_eq!(morton_index(0, 1, 4, 4), 1);
+        assert_eq!(morton_index(4, 1, 8, 2), 9);
+
+        let texture = Texture::from_file(&get_texture_fp("BC1.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let (width_in_blocks, height_in_blocks) = (width.div_ceil(4), height.div_ceil(4));
+        let (padded_width, padded_height) = (
+            width_in_blocks.next_power_of_two(),
+            height_in_blocks.next_power_of_two(),
+        );
+        let mut swizzled = vec![0u8; padded_width * padded_height * 8];
+        for y in 0..height_in_blocks {
+            for x in 0..width_in_blocks {
+                let dst = morton_index(x, y, padded_width, padded_height) * 8;
+                let src = (y * width_in_blocks + x) * 8;
+                swizzled[dst..dst + 8].copy_from_slice(&texture.data[src..src + 8]);
+            }
+        }
+        let mut expected = vec![0u32; width * height];
+        decode_bc1(&texture.data, width, height, &mut expected).unwrap();
+        let decoded = decode_vita(TextureFormat::Bc1, &swizzled, width, height).unwrap();
+        assert!(decoded.image == expected);
+
+        // a 40x12 texture with 8 bit indices spans 3x2 tiles
+        let (width, height) = (40, 12);
+        let linear = (0..width * height)
+            .map(|i| (i * 7) as u8)
+            .collect::<Vec<_>>();
+        let (row_size, size) = swizzled_size(width, height, 8);
+        assert_eq!((row_size, size), (40, 48 * 16));
+        let mut swizzled = vec![0u8; size];
+        for y in 0..height {
+            for x in 0..width {
+                let tile = (y / 8) * 3 + x / 16;
+                swizzled[tile * 128 + (y % 8) * 16 + x % 16] = linear[y * width + x];
+            }
+        }
+        let mut deswizzled = vec![0u8; width * height];
+        deswizzle_psp(width, height, 8, &swizzled, &mut deswizzled).unwrap();
+        assert!(deswizzled == linear);
+        assert!(deswizzle_psp(width, height, 8, &swizzled[..size - 1], &mut deswizzled).is_err());
+    }
+
+    #[test]
+    fn test_gx2() {
+        use texture2ddecoder::swizzle::gx2::*;
+
+        assert_eq!(micro_tile_index(1, 0, 64), 1);
+        assert_eq!(micro_tile_index(0, 1, 64), 2);
+        assert_eq!(micro_tile_index(0, 1, 128), 1);
+
+        // offsets of addrlib's computeSurfaceAddrFromCoordMacroTiled for 2 pipes and 4 banks
+        let offset = |x, y| element_offset(x, y, 32, 64, Gx2TileMode::Tiled2dThin1, 0, 0);
+        assert_eq!(offset(1, 0), 4);
+        assert_eq!(offset(0, 1), 16);
+        assert_eq!(offset(8, 0), 768);
+        assert_eq!(offset(0, 8), 256);
+        assert_eq!(offset(16, 0), 1024);
+        assert_eq!(offset(0, 16), 5120);
+        assert_eq!(offset(32, 0), 2048);
+        assert_eq!(
+            element_offset(0, 0, 32, 64, Gx2TileMode::Tiled2dThin1, 1, 2),
+            1280
+        );
+        assert_eq!(
+            element_offset(8, 0, 32, 64, Gx2TileMode::Tiled1dThin1, 0, 0),
+            256
+        );
+        assert_eq!(
+            element_offset(0, 8, 32, 64, Gx2TileMode::Tiled1dThin1, 0, 0),
+            2048
+        );
+
+        for tile_mode in [
+            Gx2TileMode::LinearAligned,
+            Gx2TileMode::Tiled1dThin1,
+            Gx2TileMode::Tiled2dThin1,
+            Gx2TileMode::Tiled2dThin4,
+            Gx2TileMode::Tiled2bThin1,
+            Gx2TileMode::Tiled3dThin1,
+        ] {
+            for bytes_per_element in [1, 2, 4, 8, 16] {
+                let format = TextureFormat::Raw(
+                    match bytes_per_element {
+                        1 => RawFormat::R8,
+                        2 => RawFormat::Rgb565,
+                        4 => RawFormat::Rgba32,
+                        8 => RawFormat::RgbaHalf,
+                        _ => RawFormat::RgbaFloat,
+                    },
+                    Endian::Little,
+                );
+                let surface = Gx2Surface {
+                    format,
+                    tile_mode,
+                    width: 100,
+                    height: 70,
+                    pitch: 0,
+                    swizzle: 0x500,
+                };
+                let layout = surface.mip_layout(0);
+                assert_eq!(layout.tile_mode, tile_mode);
+                let mut offsets = (0..layout.pitch * layout.padded_height)
+                    .map(|i| {
+                        element_offset(
+                            i % layout.pitch,
+                            i / layout.pitch,
+                            bytes_per_element * 8,
+                            layout.pitch,
+                            tile_mode,
+                            1,
+                            2,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                offsets.sort();
+                assert!(
+                    offsets
+                        .iter()
+                        .copied()
+                        .eq((0..layout.size).step_by(bytes_per_element)),
+                    "{:?} {}",
+                    tile_mode,
+                    bytes_per_element
+                );
+            }
+        }
+
+        let texture = Texture::from_file(&get_texture_fp("BC1.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let surface = Gx2Surface {
+            format: TextureFormat::Bc1,
+            tile_mode: Gx2TileMode::Tiled2dThin1,
+            width,
+            height,
+            pitch: 0,
+            swizzle: 0x700,
+        };
+        let layout = surface.mip_layout(0);
+        let mut tiled = vec![0u8; layout.size];
+        for y in 0..layout.height {
+            for x in 0..layout.width {
+                let dst = element_offset(x, y, 64, layout.pitch, layout.tile_mode, 1, 3);
+                let src = (y * layout.width + x) * 8;
+                tiled[dst..dst + 8].copy_from_slice(&texture.data[src..src + 8]);
+            }
+        }
+        let mut expected = vec![0u32; width * height];
+        decode_bc1(&texture.data, width, height, &mut expected).unwrap();
+        assert!(surface.decode_mip(&tiled, 0).unwrap().image == expected);
+        assert_eq!(surface.mip_layout(4).tile_mode, Gx2TileMode::Tiled1dThin1);
+    }
+
+    #[test]
+    fn test_etc1_3ds() {
+        let texture = Texture::from_file(&get_texture_fp("ETC1_RGB.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let num_blocks_x = width.div_ceil(4);
+        let mut expected = vec![0u32; width * height];
+        decode_etc1(&texture.data, width, height, &mut expected).unwrap();
+        // the 3DS stores the image bottom-up
+        let expected = expected
+            .chunks_exact(width)
+            .rev()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+
+        // the alpha of the pixel (x, y) of a block is (x * 4 + y) * 0x11
+        let alpha = (0..16u64).fold(0, |a, i| a | i << (4 * i));
+        let (mut etc1, mut etc1a4) = (Vec::new(), Vec::new());
+        for ty in 0..height.div_ceil(8) {
+            for tx in 0..width.div_ceil(8) {
+                for i in 0..4 {
+                    let (bx, by) = (tx * 2 + (i & 1), ty * 2 + (i >> 1));
+                    let offset = (by * num_blocks_x + bx) * 8;
+                    let mut block = texture.data[offset..offset + 8].to_vec();
+                    block.reverse();
+                    etc1.extend_from_slice(&block);
+                    etc1a4.extend_from_slice(&alpha.to_le_bytes());
+                    etc1a4.extend_from_slice(&block);
+                }
+            }
+        }
+
+        let mut image = vec![0u32; width * height];
+        decode_etc1_3ds(&etc1, width, height, &mut image).unwrap();
+        assert!(image == expected);
+
+        decode_etc1a4_3ds(&etc1a4, width, height, &mut image).unwrap();
+        for (i, (pixel, expected)) in image.iter().zip(expected.iter()).enumerate() {
+            let (x, y) = (i % width % 4, (height - 1 - i / width) % 4);
+            let mut expected = expected.to_le_bytes();
+            expected[3] = (x * 4 + y) as u8 * 0x11;
+            assert_eq!(pixel.to_le_bytes(), expected);
+        }
+
+        let mut unity_image = vec![0u32; width * height];
+        texture2ddecoder::unity::decode_unity_texture(
+            61,
+            (2019, 4),
+            &etc1a4,
+            width,
+            height,
+            &mut unity_image,
+        )
+        .unwrap();
+        assert!(unity_image == image);
+        assert!(decode_etc1_3ds(&etc1[..etc1.len() - 1], width, height, &mut image).is_err());
+    }
+
+    #[test]
+    fn test_gx() {
+        use texture2ddecoder::gx::*;
+
+        // CMPR tiles of 2x2 DXT1 blocks with big endian endpoints and reversed indices
+        let texture = Texture::from_file(&get_texture_fp("BC1.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let num_blocks_x = width.div_ceil(4);
+        let mut cmpr = Vec::new();
+        for ty in 0..height.div_ceil(8) {
+            for tx in 0..width.div_ceil(8) {
+                for i in 0..4 {
+                    let (bx, by) = (tx * 2 + (i & 1), ty * 2 + (i >> 1));
+                    let block = &texture.data[(by * num_blocks_x + bx) * 8..][..8];
+                    cmpr.extend_from_slice(&[block[1], block[0], block[3], block[2]]);
+                    cmpr.extend(
+                        block[4..]
+                            .iter()
+                            .map(|v| v >> 6 | (v >> 2 & 0x0c) | (v << 2 & 0x30) | v << 6),
+                    );
+                }
+            }
+        }
+        let mut expected = vec![0u32; width * height];
+        decode_bc1a(&texture.data, width, height, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height];
+        decode_gx(&cmpr, width, height, &mut image, GxFormat::Cmpr).unwrap();
+        assert!(image == expected);
+
+        // a 6x5 texture covers 2x2 tiles of 4x4 pixels
+        let mut rgba8 = vec![0u8; 4 * 64];
+        for (i, tile) in rgba8.chunks_exact_mut(64).enumerate() {
+            for p in 0..16 {
+                tile[p * 2..p * 2 + 2].copy_from_slice(&[255 - p as u8, i as u8]);
+                tile[32 + p * 2..32 + p * 2 + 2].copy_from_slice(&[p as u8, 10]);
+            }
+        }
+        let mut image = vec![0u32; 6 * 5];
+        decode_gx(&rgba8, 6, 5, &mut image, GxFormat::Rgba8).unwrap();
+        // the pixel (5, 4) is the pixel (1, 0) of the 4th tile
+        assert_eq!(image[4 * 6 + 5].to_le_bytes(), [10, 1, 3, 254]);
+
+        let i4 = [0x0f; 32];
+        let mut image = vec![0u32; 8 * 8];
+        decode_gx(&i4, 8, 8, &mut image, GxFormat::I4).unwrap();
+        assert_eq!(image[0].to_le_bytes(), [0, 0, 0, 0]);
+        assert_eq!(image[1].to_le_bytes(), [255, 255, 255, 255]);
+
+        let c8 = (0..32).collect::<Vec<u8>>();
+        let palette = [0xf8, 0x00, 0x80, 0x40];
+        let mut image = vec![0u32; 8 * 4];
+        decode_gx_indexed(
+            &c8,
+            8,
+            4,
+            &mut image,
+            GxFormat::C8,
+            &palette,
+            GxTlutFormat::Rgb565,
+        )
+        .unwrap();
+        assert_eq!(image[0].to_le_bytes(), [0, 0, 255, 255]);
+        assert_eq!(image[1].to_le_bytes(), [0, 8, 132, 255]);
+        assert_eq!(image[2], 0);
+        assert!(decode_gx(&c8, 8, 4, &mut image, GxFormat::C8).is_err());
+        assert_eq!(GxFormat::from_u32(14), Some(GxFormat::Cmpr));
+    }
+
+    #[test]
+    fn test_fxt1() {
+        let decode = |block: u128| {
+            let mut image = vec![0u32; 8 * 4];
+            decode_fxt1(&block.to_le_bytes(), 8, 4, &mut image).unwrap();
+            image
+        };
+
+        // CC_CHROMA: red, green, blue and white, the left half uses all of them, the right half white
+        let indices = (0..16).fold(0u128, |a, i| a | (i & 3) << (i * 2));
+        let colors = 0x7c00 | 0x3e0 << 15 | 0x1f << 30 | 0x7fff << 45;
+        let image = decode(2 << 125 | colors << 64 | 0xffff_ffff << 32 | indices);
+        assert_eq!(image[0].to_le_bytes(), [0, 0, 255, 255]);
+        assert_eq!(image[1].to_le_bytes(), [0, 255, 0, 255]);
+        assert_eq!(image[3].to_le_bytes(), [255, 255, 255, 255]);
+        assert_eq!(image[8].to_le_bytes(), [0, 0, 255, 255]);
+        assert_eq!(image[4].to_le_bytes(), [255, 255, 255, 255]);
+
+        // CC_HI: 7 steps between black and white, index 7 is transparent
+        let image = decode(0x7fff << 111 | 7 << 3 | 3);
+        assert_eq!(image[0].to_le_bytes(), [128, 128, 128, 255]);
+        assert_eq!(image[1], 0);
+        assert_eq!(image[2].to_le_bytes(), [0, 0, 0, 255]);
+
+        // CC_ALPHA without interpolation: ARGB5555 colors, index 3 is transparent
+        let image = decode(3 << 125 | 15 << 109 | 0x7c00 << 64 | 3 << 2);
+        assert_eq!(image[0].to_le_bytes(), [0, 0, 255, 123]);
+        assert_eq!(image[1], 0);
+
+        // CC_MIXED: interpolated between black and white with a 6 bit green
+        let image = decode(4 << 125 | 0x7fff << 79 | 3 << 2 | 1 << 4);
+        assert_eq!(image[0].to_le_bytes(), [0, 0, 0, 255]);
+        assert_eq!(image[1].to_le_bytes(), [255, 251, 255, 255]);
+        assert_eq!(image[2].to_le_bytes(), [85, 84, 85, 255]);
+
+        assert_eq!(TextureFormat::Fxt1.data_size(9, 4), 32);
+    }
+
+    #[test]
+    fn test_unity_split_alpha() {
+        use texture2ddecoder::unity::*;
+
+        // the alpha of an ETC1 texture with itself as alpha texture is its red channel
+        let texture = Texture::from_file(&get_texture_fp("ETC1_RGB.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut expected = vec![0u32; width * height];
+        decode_etc1(&texture.data, width, height, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height];
+        decode_unity_split_alpha(
+            TextureFormat::Etc1,
+            &texture.data,
+            width,
+            height,
+            TextureFormat::Etc1,
+            &texture.data,
+            width,
+            height,
+            &mut image,
+        )
+        .unwrap();
+        assert!(image.iter().zip(&expected).all(|(pixel, color)| {
+            let [b, g, r, _] = color.to_le_bytes();
+            pixel.to_le_bytes() == [b, g, r, r]
+        }));
+
+        // a 2x1 alpha texture is bilinearly resampled to the 4x2 color texture
+        let color = [10u8, 20, 30, 40].repeat(8);
+        let mut image = vec![0u32; 4 * 2];
+        decode_unity_split_alpha(
+            TextureFormat::Raw(RawFormat::Rgba32, Endian::Little),
+            &color,
+            4,
+            2,
+            TextureFormat::Raw(RawFormat::R8, Endian::Little),
+            &[0, 255],
+            2,
+            1,
+            &mut image,
+        )
+        .unwrap();
+        let alpha = image.iter().map(|c| c.to_le_bytes()[3]).collect::<Vec<_>>();
+        assert_eq!(alpha, [0, 64, 191, 255, 0, 64, 191, 255]);
+        assert_eq!(image[0].to_le_bytes(), [30, 20, 10, 0]);
+    }
+
+    #[test]
+    fn test_color_space() {
+        assert_eq!(srgb_to_linear(188), 128);
+        assert_eq!(linear_to_srgb(128), 188);
+        assert!((0..=255).all(|v| linear_to_srgb(srgb_to_linear(v)).abs_diff(v) <= 13));
+
+        // the sRGB decode mode of ASTC rounds the interpolated endpoints differently
+        let texture = Texture::from_file(&get_texture_fp("ASTC_4x4.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut linear = vec![0u32; width * height];
+        decode_astc_4_4(&texture.data, width, height, &mut linear).unwrap();
+        let mut srgb = vec![0u32; width * height];
+        decode_astc_srgb(&texture.data, width, height, 4, 4, &mut srgb).unwrap();
+        assert!(srgb != linear);
+        let mut srgb_3d = vec![0u32; width * height];
+        decode_astc_3d_srgb(&texture.data, width, height, 1, 4, 4, 1, &mut srgb_3d).unwrap();
+        assert!(srgb_3d == srgb);
+        assert!(srgb.iter().zip(&linear).all(|(a, b)| {
+            a.to_le_bytes()
+                .iter()
+                .zip(b.to_le_bytes())
+                .all(|(a, b)| a.abs_diff(b) <= 1)
+        }));
+        // the alpha endpoints are expanded by bit replication in both modes
+        assert!(srgb
+            .iter()
+            .zip(&linear)
+            .all(|(a, b)| a.to_le_bytes()[3] == b.to_le_bytes()[3]));
+
+        let descriptor = FormatDescriptor::new(TextureFormat::Astc(4, 4), NumericFormat::Srgb);
+        assert_eq!(descriptor.color_space(), ColorSpace::Srgb);
+        let decoded = descriptor
+            .decode_image(&texture.data, width, height, ColorSpace::Srgb)
+            .unwrap();
+        assert!(decoded.image == srgb);
+        let decoded = descriptor
+            .decode_image(&texture.data, width, height, ColorSpace::Linear)
+            .unwrap();
+        convert_color_space(&mut srgb, ColorSpace::Srgb, ColorSpace::Linear);
+        assert!(decoded.image == srgb);
+        assert_eq!(srgb[0].to_le_bytes()[3], linear[0].to_le_bytes()[3]);
+    }
+
+    #[test]
+    fn test_flip() {
+        use texture2ddecoder::gx::*;
+        use texture2ddecoder::swizzle::{tegra::*, vita::*, xenos::*};
+
+        let flipped = |image: &[u32], width: usize, flip: Flip| {
+            let mut rows = image.chunks_exact(width).collect::<Vec<_>>();
+            if flip.vertical {
+                rows.reverse();
+            }
+            rows.iter()
+                .flat_map(|row| {
+                    let mut row = row.to_vec();
+                    if flip.horizontal {
+                        row.reverse();
+                    }
+                    row
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // sizes which aren't multiples of the block sizes
+        let (width, height) = (61, 37);
+        for (name, format) in [
+            ("BC1.ktx2", TextureFormat::Bc1),
+            ("ASTC_10x6.ktx2", TextureFormat::Astc(10, 6)),
+            ("ETC2_RGBA.ktx2", TextureFormat::Etc2Rgba8),
+            ("ATC_RGB.dds", TextureFormat::AtcRgb4),
+            ("BC7.ktx2", TextureFormat::Fxt1),
+            (
+                "BC7.ktx2",
+                TextureFormat::Raw(RawFormat::Rgba32, Endian::Little),
+            ),
+        ] {
+            let texture = Texture::from_file(&get_texture_fp(name));
+            let mut expected = vec![0u32; width * height];
+            format
+                .decode(&texture.data, width, height, &mut expected)
+                .unwrap();
+            for flip in [Flip::NONE, Flip::HORIZONTAL, Flip::VERTICAL, Flip::BOTH] {
+                let mut image = vec![0u32; width * height];
+                format
+                    .decode_flipped(&texture.data, width, height, flip, &mut image)
+                    .unwrap();
+                assert!(image == flipped(&expected, width, flip), "{:?}", format);
+            }
+        }
+
+        // PVRTC requires power of two sizes in blocks
+        let texture = Texture::from_file(&get_texture_fp("PVRTCI_2bpp_RGBA.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut expected = vec![0u32; width * height];
+        decode_pvrtc_2bpp(&texture.data, width, height, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height];
+        TextureFormat::Pvrtc2bpp
+            .decode_flipped(&texture.data, width, height, Flip::BOTH, &mut image)
+            .unwrap();
+        assert!(image == flipped(&expected, width, Flip::BOTH));
+
+        let texture = Texture::from_file(&get_texture_fp("BC7.ktx2"));
+        let (width, height) = (13, 11);
+        let mut expected = vec![0u32; width * height];
+        decode_gx(
+            &texture.data,
+            width,
+            height,
+            &mut expected,
+            GxFormat::Rgb5a3,
+        )
+        .unwrap();
+        let mut image = vec![0u32; width * height];
+        decode_gx_flipped(
+            &texture.data,
+            width,
+            height,
+            &mut image,
+            GxFormat::Rgb5a3,
+            Flip::VERTICAL,
+        )
+        .unwrap();
+        assert!(image == flipped(&expected, width, Flip::VERTICAL));
+
+        // the decoders outside of TextureFormat
+        let data = texture.data.clone();
+        let (width, height) = (5, 3);
+        let mut expected = vec![0u32; width * height];
+        let mut image = vec![0u32; width * height];
+        decode_yuy2(
+            &data,
+            width,
+            height,
+            &mut expected,
+            YuvMatrix::Bt601,
+            YuvRange::Limited,
+        )
+        .unwrap();
+        decode_yuy2_flipped(
+            &data,
+            width,
+            height,
+            &mut image,
+            YuvMatrix::Bt601,
+            YuvRange::Limited,
+            Flip::BOTH,
+        )
+        .unwrap();
+        assert!(image == flipped(&expected, width, Flip::BOTH));
+        decode_i420(
+            &data,
+            width,
+            height,
+            &mut expected,
+            YuvMatrix::Bt709,
+            YuvRange::Full,
+        )
+        .unwrap();
+        decode_i420_flipped(
+            &data,
+            width,
+            height,
+            &mut image,
+            YuvMatrix::Bt709,
+            YuvRange::Full,
+            Flip::VERTICAL,
+        )
+        .unwrap();
+        assert!(image == flipped(&expected, width, Flip::VERTICAL));
+        decode_p4(
+            &data,
+            width,
+            height,
+            &mut expected,
+            &data[..32],
+            PaletteFormat::Rgb565,
+            NibbleOrder::LowFirst,
+        )
+        .unwrap();
+        decode_p4_flipped(
+            &data,
+            width,
+            height,
+            &mut image,
+            &data[..32],
+            PaletteFormat::Rgb565,
+            NibbleOrder::LowFirst,
+            Flip::HORIZONTAL,
+        )
+        .unwrap();
+        assert!(image == flipped(&expected, width, Flip::HORIZONTAL));
+        decode_p8(
+            &data,
+            width,
+            height,
+            &mut expected,
+            &data[..1024],
+            PaletteFormat::Rgba8888,
+            true,
+        )
+        .unwrap();
+        decode_p8_flipped(
+            &data,
+            width,
+            height,
+            &mut image,
+            &data[..1024],
+            PaletteFormat::Rgba8888,
+            true,
+            Flip::BOTH,
+        )
+        .unwrap();
+        assert!(image == flipped(&expected, width, Flip::BOTH));
+
+        // the 3DS flip is combined with the flip of the caller
+        let (width, height) = (13, 11);
+        let mut expected = vec![0u32; width * height];
+        let mut image = vec![0u32; width * height];
+        for flip in [Flip::NONE, Flip::HORIZONTAL, Flip::VERTICAL, Flip::BOTH] {
+            decode_etc1_3ds(&data, width, height, &mut expected).unwrap();
+            decode_etc1_3ds_flipped(&data, width, height, &mut image, flip).unwrap();
+            assert!(image == flipped(&expected, width, flip));
+            decode_etc1a4_3ds(&data, width, height, &mut expected).unwrap();
+            decode_etc1a4_3ds_flipped(&data, width, height, &mut image, flip).unwrap();
+            assert!(image == flipped(&expected, width, flip));
+        }
+        // Unity's bottom-up textures, RGBA32, YUY2, ETC1 and 3DS ETC1A4
+        for format_id in [4, 21, 34, 61] {
+            texture2ddecoder::unity::decode_unity_texture(
+                format_id,
+                (2019, 4),
+                &data,
+                width,
+                height,
+                &mut expected,
+            )
+            .unwrap();
+            texture2ddecoder::unity::decode_unity_texture_flipped(
+                format_id,
+                (2019, 4),
+                &data,
+                width,
+                height,
+                &mut image,
+                Flip::VERTICAL,
+            )
+            .unwrap();
+            assert!(image == flipped(&expected, width, Flip::VERTICAL));
+        }
+
+        let (width, height) = (32, 16);
+        for flip in [Flip::HORIZONTAL, Flip::VERTICAL, Flip::BOTH] {
+            let expected = decode_vita(TextureFormat::Bc1, &data, width, height).unwrap();
+            let decoded =
+                decode_vita_flipped(TextureFormat::Bc1, &data, width, height, flip).unwrap();
+            assert!(decoded.image == flipped(&expected.image, width, flip));
+            let expected = decode_xenos(TextureFormat::Bc1, &data, width, height).unwrap();
+            let decoded =
+                decode_xenos_flipped(TextureFormat::Bc1, &data, width, height, flip).unwrap();
+            assert!(decoded.image == flipped(&expected.image, width, flip));
+            let expected = decode_tegra(TextureFormat::Bc1, &data, width, height, 0, 1).unwrap();
+            let decoded =
+                decode_tegra_flipped(TextureFormat::Bc1, &data, width, height, 0, 1, flip).unwrap();
+            assert!(decoded.image == flipped(&expected.image, width, flip));
+        }
+
+        let texture = Texture::from_file(&get_texture_fp("ASTC_10x6.ktx2"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut expected = vec![0u32; width * height];
+        decode_astc_srgb(&texture.data, width, height, 10, 6, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height];
+        decode_astc_srgb_flipped(&texture.data, width, height, 10, 6, &mut image, Flip::BOTH)
+            .unwrap();
+        assert!(image == flipped(&expected, width, Flip::BOTH));
+        // each depth slice of a volume texture is flipped on its own
+        let (width, height, depth) = (7, 5, 3);
+        let mut expected = vec![0u32; width * height * depth];
+        decode_astc_3d(&texture.data, width, height, depth, 4, 4, 4, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height * depth];
+        decode_astc_3d_flipped(
+            &texture.data,
+            width,
+            height,
+            depth,
+            4,
+            4,
+            4,
+            &mut image,
+            Flip::VERTICAL,
+        )
+        .unwrap();
+        for (image, expected) in image
+            .chunks_exact(width * height)
+            .zip(expected.chunks_exact(width * height))
+        {
+            assert!(image == flipped(expected, width, Flip::VERTICAL));
+        }
+
+        let texture = Texture::from_file(&get_texture_fp("CRUNCH_DXT1.crn"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut expected = vec![0u32; width * height];
+        decode_crunch(&texture.data, width, height, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height];
+        decode_crunch_flipped(&texture.data, width, height, &mut image, Flip::BOTH).unwrap();
+        assert!(image == flipped(&expected, width, Flip::BOTH));
+        let texture = Texture::from_file(&get_texture_fp("UNITYCRUNCH_ETC1.crn"));
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut expected = vec![0u32; width * height];
+        decode_unity_crunch(&texture.data, width, height, &mut expected).unwrap();
+        let mut image = vec![0u32; width * height];
+        decode_unity_crunch_flipped(&texture.data, width, height, &mut image, Flip::VERTICAL)
+            .unwrap();
+        assert!(image == flipped(&expected, width, Flip::VERTICAL));
+    }
+
     // helper structs and functions
     struct Texture {
         width: u32,
@@ -346,4 +2251,22 @@ mod tests {
         let texture = Texture::from_file(&src_fp);
         texture.save_as_image(&dst_fp, decode_func);
     }
+
+    // the color (132, 130, 132) with the alpha 0, 255, 136 and 119
+    fn assert_unpremultiplied(pixels: &[u32]) {
+        // transparent pixels are transparent black
+        assert_eq!(pixels[0].to_le_bytes(), [0, 0, 0, 0]);
+        assert_eq!(pixels[1].to_le_bytes(), [132, 130, 132, 255]);
+        // (v * 255 + a / 2) / a
+        assert_eq!(pixels[2].to_le_bytes(), [248, 244, 248, 136]);
+        // clamped to 255
+        assert_eq!(pixels[3].to_le_bytes(), [255, 255, 255, 119]);
+    }
+
+    fn assert_premultiplied(pixels: &[u32]) {
+        assert_eq!(pixels[0].to_le_bytes(), [132, 130, 132, 0]);
+        assert_eq!(pixels[1].to_le_bytes(), [132, 130, 132, 255]);
+        assert_eq!(pixels[2].to_le_bytes(), [132, 130, 132, 136]);
+        assert_eq!(pixels[3].to_le_bytes(), [132, 130, 132, 119]);
+    }
 }